@@ -0,0 +1,5 @@
+fn main() {
+    // The gRPC service is generated from its protobuf definitions
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/bracket.proto").expect("Valid protobuf definitions");
+}
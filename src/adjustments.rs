@@ -0,0 +1,145 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use crate::teams::{find_team, normalize_name, Team};
+use crate::tiebreaker::MARGIN_SCALE;
+
+/// One row of an adjustments file, e.g. `Gonzaga,-3` when a starter is out
+#[derive(Debug, Deserialize)]
+struct AdjustmentRecord {
+    team: String,
+    /// Points per game to add to the team's expected margin, negative for a penalty
+    points: f64,
+}
+
+/// A quick change to one team's chance of winning each of its games, e.g. `Duke:+5` for five
+/// percentage points more
+#[derive(Debug, Clone)]
+pub struct Boost {
+    pub team: String,
+    /// Percentage points to add to the team's chance in every game, negative to fade it
+    pub points: f64,
+}
+
+impl FromStr for Boost {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (team, points) = s
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow!("Expected team:points, got {}", s))?;
+        let points: f64 = points.trim().parse()?;
+        if points.abs() > 100. {
+            return Err(anyhow!("Can't boost {} by more than 100 points", team));
+        }
+        Ok(Self {
+            team: team.trim().to_string(),
+            points,
+        })
+    }
+}
+
+/// Manual bonuses and penalties for teams, such as for injuries, applied on top of whatever
+/// probabilities are used
+#[derive(Debug, Default)]
+pub struct Adjustments {
+    /// Points per game for each team, keyed by normalized team name
+    points: HashMap<String, f64>,
+    /// Percentage points for each boosted team, keyed by normalized team name
+    boosts: HashMap<String, f64>,
+    /// Every adjusted or boosted team as it was named
+    named: BTreeSet<String>,
+}
+
+impl Adjustments {
+    /// Load adjustments from a CSV with a `team,points` header
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = csv::Reader::from_path(path)
+            .with_context(|| format!("Could not open adjustments file {}", path.display()))?;
+        let mut adjustments = Self::default();
+        for record in reader.deserialize() {
+            let record: AdjustmentRecord = record?;
+            *adjustments
+                .points
+                .entry(normalize_name(&record.team))
+                .or_insert(0.) += record.points;
+            adjustments.named.insert(record.team);
+        }
+        Ok(adjustments)
+    }
+
+    /// Add a boost on top of any adjustments
+    pub fn add_boost(&mut self, boost: &Boost) {
+        *self.boosts.entry(normalize_name(&boost.team)).or_insert(0.) += boost.points;
+        self.named.insert(boost.team.clone());
+    }
+
+    /// Adjusted or boosted teams which aren't in `teams`, such as misspelled ones, whose
+    /// adjustments never apply
+    pub fn unknown_teams(&self, teams: &[Team]) -> Vec<&str> {
+        self.named
+            .iter()
+            .filter(|name| find_team(teams, name).is_none())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Shift the chance of `team1` beating `team2` by the difference in their adjustments,
+    /// converting points to log odds on the same curve used for score predictions, then by
+    /// the difference in their boosts
+    pub fn adjust(&self, team1: &str, team2: &str, prob: f64) -> f64 {
+        let shift = get(&self.points, team1) - get(&self.points, team2);
+        let prob = if shift == 0. || prob <= 0. || prob >= 1. {
+            prob
+        } else {
+            let logit = (prob / (1. - prob)).ln() + shift / MARGIN_SCALE;
+            1. / (1. + (-logit).exp())
+        };
+        let boost = get(&self.boosts, team1) - get(&self.boosts, team2);
+        (prob + boost / 100.).clamp(0., 1.)
+    }
+}
+
+fn get(values: &HashMap<String, f64>, team: &str) -> f64 {
+    values.get(&normalize_name(team)).copied().unwrap_or(0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tournament::Region;
+
+    #[test]
+    fn parses_boosts() {
+        let boost: Boost = "Duke:+5".parse().unwrap();
+        assert_eq!((boost.team.as_str(), boost.points), ("Duke", 5.));
+        let boost: Boost = " Texas A&M : -10.5".parse().unwrap();
+        assert_eq!((boost.team.as_str(), boost.points), ("Texas A&M", -10.5));
+        for bad in ["Duke", "Duke:", "Duke:five", "Duke:+101"] {
+            assert!(bad.parse::<Boost>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn finds_boosts_for_teams_not_in_the_field() {
+        let teams: Vec<Team> = ["Duke", "Gonzaga"]
+            .iter()
+            .map(|name| {
+                serde_json::from_value(json!({"name": name, "region": Region::West, "seed": 1}))
+                    .unwrap()
+            })
+            .collect();
+        let mut adjustments = Adjustments::default();
+        for boost in ["duke:+5", "Gonzga:-5"] {
+            adjustments.add_boost(&boost.parse().unwrap());
+        }
+        assert_eq!(adjustments.unknown_teams(&teams), ["Gonzga"]);
+        assert!((adjustments.adjust("Duke", "Gonzaga", 0.5) - 0.55).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,558 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::probabilities::ProbabilityMatrix;
+use crate::scoring::ScoringSystem;
+use crate::series::series_win_probability;
+use crate::summary::SimulationSummary;
+use crate::tournament::{Matchup, RoundKind, Tournament};
+
+/// Each team's chance of winning its game in each round
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RoundProbabilities {
+    /// For each team, the chance of winning its round 1..=6 game
+    pub probs: BTreeMap<String, [f64; 6]>,
+}
+
+impl RoundProbabilities {
+    /// Chance that the team wins its game in the given round, so round 4 gives the chance of
+    /// reaching the Final Four and round 6 the chance of a title
+    pub fn win_probability(&self, team: &str, round_num: usize) -> f64 {
+        self.probs.get(team).map(|p| p[round_num - 1]).unwrap_or(0.)
+    }
+
+    /// How often each team won in each round of a set of simulations
+    pub fn from_summary(summary: &SimulationSummary) -> Self {
+        let probs = summary
+            .wins
+            .keys()
+            .map(|team| {
+                let mut probs = [0.; 6];
+                for (round_ind, prob) in probs.iter_mut().enumerate() {
+                    *prob = summary.win_probability(team, round_ind + 1);
+                }
+                (team.clone(), probs)
+            })
+            .collect();
+        Self { probs }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?,
+        );
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// Where the most likely bracket is saved
+pub const MOST_LIKELY_PATH: &str = "most_likely_bracket.json";
+
+/// Distribution over who wins a matchup
+type WinnerDistribution = HashMap<String, f64>;
+
+/// Exactly compute every team's chance of winning in each round by propagating probabilities
+/// forward through the bracket, instead of sampling. Games already decided in `bracket` are
+/// respected, and every other game uses the head-to-head probabilities in `matrix`.
+pub fn round_probabilities(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+) -> anyhow::Result<RoundProbabilities> {
+    let mut res = RoundProbabilities::default();
+    for (round_ind, dists) in matchup_distributions(bracket, matrix)?.iter().enumerate() {
+        for dist in dists {
+            for (team, prob) in dist {
+                res.probs.entry(team.clone()).or_default()[round_ind] += prob;
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Who a team is likely to face in its next game if it gets there, most likely first. Empty if
+/// the team is out or the tournament is over.
+pub fn next_opponents(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    team: &str,
+) -> anyhow::Result<Vec<(String, f64)>> {
+    let rounds = 1..=bracket.field.round_count();
+    let next = rounds.map(RoundKind::Round).find_map(|round| {
+        bracket.rounds[&round]
+            .matchups
+            .iter()
+            .find(|m| m.includes_team(team) && !m.completed())
+            .map(|m| (round, m))
+    });
+    let (round, matchup) = match next {
+        Some(next) => next,
+        None => return Ok(vec![]),
+    };
+    if let Some(teams) = matchup.try_teams() {
+        let opponent = if teams[0] == team { teams[1] } else { teams[0] };
+        return Ok(vec![(opponent.to_string(), 1.)]);
+    }
+
+    // The opponent comes out of the other matchup feeding this one
+    let round_ind = round.number() - 1;
+    let feeder = bracket.rounds[&RoundKind::Round(round_ind)]
+        .get_matchup_with_team(team)
+        .index();
+    let dists = matchup_distributions(bracket, matrix)?;
+    let mut opponents = dists[round_ind - 1][feeder ^ 1]
+        .iter()
+        .map(|(opponent, prob)| (opponent.clone(), *prob))
+        .collect::<Vec<_>>();
+    opponents.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    Ok(opponents)
+}
+
+/// One round of a team's way to the title
+#[derive(Debug, Clone, Serialize)]
+pub struct PathRound {
+    pub round: usize,
+    /// Chance the team plays in this round
+    pub reach: f64,
+    /// Chance the team wins this round's game, given it gets to play it
+    pub win_if_reached: f64,
+    /// Who the team could face in this round, most likely first. Empty if it can't get here.
+    pub opponents: Vec<PathOpponent>,
+}
+
+/// A possible opponent on a team's way to the title
+#[derive(Debug, Clone, Serialize)]
+pub struct PathOpponent {
+    pub team: String,
+    /// Chance of facing this opponent, given the team gets to the round
+    pub chance: f64,
+    /// Chance of beating them
+    pub win: f64,
+}
+
+/// Every round of a team's way to the title: its chance of getting there, who it could meet
+/// and its chance of beating each. This is what 538 shows when hovering over the bracket,
+/// but for all rounds at once.
+pub fn title_path(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    team: &str,
+) -> anyhow::Result<Vec<PathRound>> {
+    let round1 = &bracket.rounds[&RoundKind::Round(1)];
+    let first = round1
+        .matchups
+        .iter()
+        .find(|m| m.includes_team(team))
+        .ok_or_else(|| anyhow!("{} isn't in the bracket", team))?;
+    let first_ind = first.index();
+    let dists = matchup_distributions(bracket, matrix)?;
+    let mut path = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let (reach, opponents) = if round_num == 1 {
+            let [team1, team2] = round1_teams(first)?;
+            let opponent = if team1 == team { team2 } else { team1 };
+            (1., HashMap::from([(opponent.to_string(), 1.)]))
+        } else {
+            // The opponent comes out of the other matchup feeding this one
+            let prev = &dists[round_num - 2];
+            let feeder = first_ind >> (round_num - 2);
+            let reach = prev[feeder].get(team).copied().unwrap_or(0.);
+            (reach, prev[feeder ^ 1].clone())
+        };
+        let ind = first_ind >> (round_num - 1);
+        let matchup = &bracket.rounds[&RoundKind::Round(round_num)].matchups[ind];
+        let mut path_opponents = vec![];
+        if reach > 0. {
+            for (opponent, chance) in opponents {
+                if chance <= 0. {
+                    continue;
+                }
+                let win = match matchup.winning_team() {
+                    Some(winner) if winner == team => 1.,
+                    Some(_) => 0.,
+                    None => matchup_probability(matrix, bracket.field.best_of, team, &opponent)?,
+                };
+                path_opponents.push(PathOpponent {
+                    team: opponent,
+                    chance,
+                    win,
+                });
+            }
+        }
+        path_opponents.sort_by(|a, b| b.chance.partial_cmp(&a.chance).unwrap());
+        let won = dists[round_num - 1][ind].get(team).copied().unwrap_or(0.);
+        path.push(PathRound {
+            round: round_num,
+            reach,
+            win_if_reached: if reach > 0. { won / reach } else { 0. },
+            opponents: path_opponents,
+        });
+    }
+    Ok(path)
+}
+
+/// How much one game's probability moves a contender's title chances
+#[derive(Debug, Clone, Serialize)]
+pub struct SwingGame {
+    pub contender: String,
+    /// The teams playing, the first being the one whose chance of winning is nudged
+    pub teams: [String; 2],
+    /// The contender's title chance with the first team's chance of winning lowered
+    pub title_if_down: f64,
+    /// The contender's title chance with the first team's chance of winning raised
+    pub title_if_up: f64,
+}
+
+impl SwingGame {
+    pub fn swing(&self) -> f64 {
+        self.title_if_up - self.title_if_down
+    }
+}
+
+/// For each of the `contenders` likeliest champions, the `shown` games which move its title
+/// chances the most when the head-to-head probability is nudged `delta` either way. Every
+/// game which can still be played is tried, not just the contender's own, so this finds the
+/// games elsewhere in the bracket which decide who it has to beat.
+pub fn swing_games(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    delta: f64,
+    contenders: usize,
+    shown: usize,
+) -> anyhow::Result<Vec<SwingGame>> {
+    let base = round_probabilities(bracket, matrix)?;
+    let title = bracket.field.round_count();
+    let mut ranked = base.probs.iter().collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.1[title - 1].partial_cmp(&a.1[title - 1]).unwrap());
+    let contenders = ranked
+        .into_iter()
+        .take(contenders)
+        .map(|(team, _)| team.as_str())
+        .collect::<Vec<_>>();
+
+    let eliminated = bracket.eliminated_teams();
+    let alive = |team: &str| base.probs.contains_key(team) && !eliminated.contains(team);
+    let games = matrix
+        .pairs()
+        .filter(|(team1, team2, _)| {
+            alive(team1) && alive(team2) && bracket.meeting_round(team1, team2).is_some()
+        })
+        .map(|(team1, team2, prob)| (team1.to_string(), team2.to_string(), prob))
+        .collect::<Vec<_>>();
+
+    let mut nudged = matrix.clone();
+    let mut swings: HashMap<&str, Vec<SwingGame>> = HashMap::new();
+    for (team1, team2, prob) in games {
+        nudged.insert(&team1, &team2, (prob - delta).max(0.));
+        let down = round_probabilities(bracket, &nudged)?;
+        nudged.insert(&team1, &team2, (prob + delta).min(1.));
+        let up = round_probabilities(bracket, &nudged)?;
+        nudged.insert(&team1, &team2, prob);
+        for contender in &contenders {
+            swings.entry(contender).or_default().push(SwingGame {
+                contender: contender.to_string(),
+                teams: [team1.clone(), team2.clone()],
+                title_if_down: down.win_probability(contender, title),
+                title_if_up: up.win_probability(contender, title),
+            });
+        }
+    }
+
+    let mut res = vec![];
+    for contender in contenders {
+        let mut games = swings.remove(contender).unwrap_or_default();
+        games.sort_by(|a, b| b.swing().abs().partial_cmp(&a.swing().abs()).unwrap());
+        res.extend(games.into_iter().take(shown));
+    }
+    Ok(res)
+}
+
+/// For each round, the distribution over who wins each of its matchups
+fn matchup_distributions(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+) -> anyhow::Result<Vec<Vec<WinnerDistribution>>> {
+    let mut rounds: Vec<Vec<WinnerDistribution>> = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let round = &bracket.rounds[&RoundKind::Round(round_num)];
+        let mut dists = vec![];
+        for matchup in &round.matchups {
+            let dist = if let Some(winner) = matchup.winning_team() {
+                HashMap::from([(winner.to_string(), 1.)])
+            } else if round_num == 1 {
+                let [team1, team2] = round1_teams(matchup)?;
+                let side1 = HashMap::from([(team1.to_string(), 1.)]);
+                let side2 = HashMap::from([(team2.to_string(), 1.)]);
+                winner_distribution(&side1, &side2, matrix, bracket.field.best_of)?
+            } else {
+                let prev = &rounds[round_num - 2];
+                let ind = matchup.index();
+                winner_distribution(
+                    &prev[2 * ind],
+                    &prev[2 * ind + 1],
+                    matrix,
+                    bracket.field.best_of,
+                )?
+            };
+            dists.push(dist);
+        }
+        rounds.push(dists);
+    }
+    Ok(rounds)
+}
+
+fn round1_teams(matchup: &Matchup) -> anyhow::Result<[&str; 2]> {
+    matchup
+        .try_teams()
+        .ok_or_else(|| anyhow!("Round 1 matchup {} is missing a team", matchup.index()))
+}
+
+/// Chance of `team1` winning a matchup against `team2`, which over a best of `best_of`
+/// series is the chance of winning the series rather than a single game
+fn matchup_probability(
+    matrix: &ProbabilityMatrix,
+    best_of: usize,
+    team1: &str,
+    team2: &str,
+) -> anyhow::Result<f64> {
+    let prob = matrix.get(team1, team2).ok_or_else(|| {
+        anyhow!(
+            "No probability for {} vs {}, scrape probabilities first",
+            team1,
+            team2
+        )
+    })?;
+    Ok(series_win_probability(prob, best_of))
+}
+
+/// Combine the distributions over who comes out of each side of a matchup into the
+/// distribution over who wins it, with matchups played as best of `best_of` series. The
+/// sides come from disjoint parts of the bracket, so they are independent.
+pub fn winner_distribution(
+    side1: &WinnerDistribution,
+    side2: &WinnerDistribution,
+    matrix: &ProbabilityMatrix,
+    best_of: usize,
+) -> anyhow::Result<WinnerDistribution> {
+    let mut dist = HashMap::new();
+    for (team1, reach1) in side1 {
+        for (team2, reach2) in side2 {
+            let prob = matchup_probability(matrix, best_of, team1, team2)?;
+            let meet = reach1 * reach2;
+            *dist.entry(team1.clone()).or_insert(0.) += meet * prob;
+            *dist.entry(team2.clone()).or_insert(0.) += meet * (1. - prob);
+        }
+    }
+    Ok(dist)
+}
+
+/// Best outcome of a matchup's part of the bracket given who wins it: the value of the best
+/// way for that to happen, such as its log probability, and the opponent beaten in this matchup
+type BestOutcomes = HashMap<String, (f64, Option<String>)>;
+
+/// What picking a team to win a game is worth, given the round number, the team and its
+/// chance of beating the opponent (1 if the game was already decided). Values add up over
+/// the bracket.
+type PickValue<'a> = dyn Fn(usize, &str, f64) -> f64 + 'a;
+
+/// Find the single most likely complete bracket under the head-to-head probabilities in
+/// `matrix` using dynamic programming over the bracket, keeping games already decided in
+/// `bracket`. Returns the filled in bracket and its probability of being exactly right.
+pub fn most_probable_bracket(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+) -> anyhow::Result<(Tournament, f64)> {
+    let (bracket, log_prob) = best_bracket(bracket, matrix, &|_, _, prob| prob.ln())?;
+    Ok((bracket, log_prob.exp()))
+}
+
+/// Find the complete bracket scoring the most points on average under `scoring`, where a
+/// pick is worth its round's points times the team's chance of winning that round. Unlike
+/// the most likely bracket, this leans towards teams with many ways to go deep. Returns the
+/// filled in bracket and its expected points.
+pub fn max_expected_points_bracket(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    scoring: &ScoringSystem,
+) -> anyhow::Result<(Tournament, f64)> {
+    let probs = round_probabilities(bracket, matrix)?;
+    best_bracket(bracket, matrix, &|round_num, team, _| {
+        scoring.points[round_num - 1] as f64 * probs.win_probability(team, round_num)
+    })
+}
+
+/// The complete bracket with the highest total value of its picks, and that value
+fn best_bracket(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    value: &PickValue,
+) -> anyhow::Result<(Tournament, f64)> {
+    let rounds = best_outcome_rounds(bracket, matrix, value)?;
+    let (champion, total) = title_outcomes(&rounds)
+        .iter()
+        .max_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
+        .map(|(team, (total, _))| (team.clone(), *total))
+        .ok_or_else(|| anyhow!("No possible champion"))?;
+    Ok((fill_bracket(bracket, &rounds, champion)?, total))
+}
+
+/// The most likely complete bracket with the given champion, where each time a team reached
+/// the Final Four its log probability of getting there is lowered by `penalties`. Used to
+/// steer entries in a portfolio apart.
+pub(crate) fn most_probable_bracket_with(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    champion: &str,
+    penalties: &HashMap<String, f64>,
+) -> anyhow::Result<Tournament> {
+    // The winners of the round two before the title game make up the Final Four
+    let final_four_round = bracket.field.round_count().checked_sub(2);
+    let rounds = best_outcome_rounds(bracket, matrix, &|round_num, team, prob| {
+        let penalty = if Some(round_num) == final_four_round {
+            penalties.get(team).copied().unwrap_or(0.)
+        } else {
+            0.
+        };
+        prob.ln() - penalty
+    })?;
+    if !title_outcomes(&rounds).contains_key(champion) {
+        return Err(anyhow!("{} can't win the title", champion));
+    }
+    fill_bracket(bracket, &rounds, champion.to_string())
+}
+
+/// Best outcomes of every matchup, round by round, so rounds[r][i] holds the best outcomes of
+/// matchup i in round r + 1
+fn best_outcome_rounds(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    value: &PickValue,
+) -> anyhow::Result<Vec<Vec<BestOutcomes>>> {
+    let mut rounds: Vec<Vec<BestOutcomes>> = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let round = &bracket.rounds[&RoundKind::Round(round_num)];
+        let mut outcomes = vec![];
+        for matchup in &round.matchups {
+            let (side1, side2) = if round_num == 1 {
+                let [team1, team2] = round1_teams(matchup)?;
+                (
+                    HashMap::from([(team1.to_string(), (0., None))]),
+                    HashMap::from([(team2.to_string(), (0., None))]),
+                )
+            } else {
+                let prev = &rounds[round_num - 2];
+                let ind = matchup.index();
+                (prev[2 * ind].clone(), prev[2 * ind + 1].clone())
+            };
+            let winner = matchup.winning_team();
+            let score = |team: &str, prob| value(round_num, team, prob);
+            let best_of = bracket.field.best_of;
+            let mut best = best_outcomes(&side1, &side2, winner, matrix, best_of, &score)?;
+            best.extend(best_outcomes(
+                &side2, &side1, winner, matrix, best_of, &score,
+            )?);
+            outcomes.push(best);
+        }
+        rounds.push(outcomes);
+    }
+    Ok(rounds)
+}
+
+/// Best outcomes of the title game, the only matchup of the last round
+fn title_outcomes(rounds: &[Vec<BestOutcomes>]) -> &BestOutcomes {
+    &rounds[rounds.len() - 1][0]
+}
+
+/// Fill in `bracket` from the best outcomes with `champion` winning the title
+fn fill_bracket(
+    bracket: &Tournament,
+    rounds: &[Vec<BestOutcomes>],
+    champion: String,
+) -> anyhow::Result<Tournament> {
+    // Walk back down the bracket: a matchup's winner and beaten opponent are the winners of
+    // the two matchups feeding it
+    let mut winners: Vec<Vec<String>> = rounds
+        .iter()
+        .map(|outcomes| vec![String::new(); outcomes.len()])
+        .collect();
+    let title_ind = winners.len() - 1;
+    winners[title_ind][0] = champion;
+    for round_ind in (1..=title_ind).rev() {
+        for ind in 0..winners[round_ind].len() {
+            let winner = winners[round_ind][ind].clone();
+            let opponent = rounds[round_ind][ind][&winner]
+                .1
+                .clone()
+                .ok_or_else(|| anyhow!("No opponent recorded for {}", winner))?;
+            let (left, right) = (2 * ind, 2 * ind + 1);
+            let (winner_slot, opponent_slot) = if rounds[round_ind - 1][left].contains_key(&winner)
+            {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            winners[round_ind - 1][winner_slot] = winner;
+            winners[round_ind - 1][opponent_slot] = opponent;
+        }
+    }
+
+    let mut res = bracket.clone();
+    for (round_ind, round_winners) in winners.iter().enumerate() {
+        let round = RoundKind::Round(round_ind + 1);
+        for (ind, winner) in round_winners.iter().enumerate() {
+            if !res.rounds[&round].matchups[ind].completed() {
+                res.advance_team(winner, round);
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// For each team who can come out of `side`, the best way for it to then beat someone from
+/// `other` in a best of `best_of` series, valuing the win with `score`. If the game was already
+/// won by `decided`, only that team can win it and the game itself is certain.
+fn best_outcomes(
+    side: &BestOutcomes,
+    other: &BestOutcomes,
+    decided: Option<&str>,
+    matrix: &ProbabilityMatrix,
+    best_of: usize,
+    score: &dyn Fn(&str, f64) -> f64,
+) -> anyhow::Result<BestOutcomes> {
+    let mut best = HashMap::new();
+    for (team, (team_value, _)) in side {
+        if decided.map(|winner| winner != team).unwrap_or(false) {
+            continue;
+        }
+        let mut team_best: Option<(f64, String)> = None;
+        for (opp, (opp_value, _)) in other {
+            let prob = if decided.is_some() {
+                1.
+            } else {
+                matchup_probability(matrix, best_of, team, opp)?
+            };
+            let value = team_value + opp_value + score(team, prob);
+            if team_best.as_ref().map(|b| value > b.0).unwrap_or(true) {
+                team_best = Some((value, opp.clone()));
+            }
+        }
+        if let Some((value, opp)) = team_best {
+            best.insert(team.clone(), (value, Some(opp)));
+        }
+    }
+    Ok(best)
+}
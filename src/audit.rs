@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+use crate::probabilities::ProbabilityMatrix;
+
+/// Audit log every probability is recorded in, once opened
+static AUDIT_LOG: OnceCell<Mutex<BufWriter<File>>> = OnceCell::new();
+
+/// One probability as it was read or worked out, and what was done to it afterwards
+#[derive(Debug, Serialize)]
+pub struct AuditEntry<'a> {
+    /// Where the probability came from, e.g. 538 for a hover label, override, or game for the
+    /// chance a simulated game was drawn with
+    pub source: &'a str,
+    pub team1: &'a str,
+    pub team2: &'a str,
+    pub round: Option<usize>,
+    /// Text the probability was read or computed from, such as 538's hover label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    /// Chance of `team1` winning as read or computed
+    pub value: f64,
+    /// Probabilities of each source `value` was blended from
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<(&'a str, f64)>,
+    /// Each change made to `value` in order, with the chance after it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<(String, f64)>,
+}
+
+impl<'a> AuditEntry<'a> {
+    pub fn new(
+        source: &'a str,
+        team1: &'a str,
+        team2: &'a str,
+        round: Option<usize>,
+        value: f64,
+    ) -> Self {
+        Self {
+            source,
+            team1,
+            team2,
+            round,
+            raw: None,
+            value,
+            inputs: vec![],
+            transforms: vec![],
+        }
+    }
+
+    pub fn with_raw(mut self, raw: impl Into<String>) -> Self {
+        self.raw = Some(raw.into());
+        self
+    }
+}
+
+/// Record every probability from now on in the given file as JSON lines, replacing it
+pub fn open(path: &Path) -> anyhow::Result<()> {
+    let file = File::create(path)
+        .with_context(|| format!("Could not create audit log {}", path.display()))?;
+    AUDIT_LOG
+        .set(Mutex::new(BufWriter::new(file)))
+        .map_err(|_| anyhow!("The audit log is already open"))
+}
+
+/// Whether probabilities are being recorded
+pub fn enabled() -> bool {
+    AUDIT_LOG.get().is_some()
+}
+
+/// Add a probability to the audit log, if one is open. A failed write is only warned about
+/// so it doesn't stop a long scrape.
+pub fn record(entry: &AuditEntry) {
+    let log = match AUDIT_LOG.get() {
+        Some(log) => log,
+        None => return,
+    };
+    let mut writer = log.lock().unwrap();
+    let res = serde_json::to_writer(&mut *writer, entry)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| Ok(writeln!(writer)?))
+        .and_then(|_| Ok(writer.flush()?));
+    if let Err(e) = res {
+        log::warn!("Could not write to the audit log: {}", e);
+    }
+}
+
+/// Record every pair in a matrix worked out from a per-team input, described by `describe`
+/// such as `seed 5`
+pub fn record_matrix(source: &str, matrix: &ProbabilityMatrix, describe: impl Fn(&str) -> String) {
+    if !enabled() {
+        return;
+    }
+    for (team1, team2, prob) in matrix.pairs() {
+        let raw = format!("{} vs {}", describe(team1), describe(team2));
+        record(&AuditEntry::new(source, team1, team2, None, prob).with_raw(raw));
+    }
+}
+
+/// Record every pair in a matrix worked out from team ratings
+pub fn record_ratings(source: &str, matrix: &ProbabilityMatrix, ratings: &[(&str, f64)]) {
+    let ratings: HashMap<_, _> = ratings.iter().copied().collect();
+    record_matrix(source, matrix, |team| format!("rating {}", ratings[team]));
+}
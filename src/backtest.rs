@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::analytic::most_probable_bracket;
+use crate::engine::sample_bracket;
+use crate::probabilities::{ProbabilityMatrix, PROBABILITIES_PATH};
+use crate::rng::Sampler;
+use crate::scoring::{score_bracket, ScoringSystem};
+use crate::tournament::{RoundKind, Tournament};
+
+/// File in each season's directory holding the real results
+pub const RESULTS_FILE: &str = "results.json";
+
+/// A past tournament: the head-to-head probabilities from before it started and what
+/// actually happened
+#[derive(Debug, Clone)]
+pub struct Season {
+    pub name: String,
+    pub matrix: ProbabilityMatrix,
+    pub results: Tournament,
+}
+
+/// Load every season from a directory of per-year directories, each holding a
+/// `probabilities.json` and a `results.json`
+pub fn load_seasons(dir: &Path) -> anyhow::Result<Vec<Season>> {
+    let mut seasons = vec![];
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Could not read history directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let matrix = ProbabilityMatrix::load(path.join(PROBABILITIES_PATH))
+            .with_context(|| format!("Could not load probabilities for {}", name))?;
+        let results = Tournament::load(path.join(RESULTS_FILE))
+            .with_context(|| format!("Could not load results for {}", name))?;
+        seasons.push(Season {
+            name,
+            matrix,
+            results,
+        });
+    }
+    seasons.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(seasons)
+}
+
+/// How brackets built from a season's probabilities would have scored
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonScore {
+    pub season: String,
+    /// Score of the single most likely bracket
+    pub most_likely: u32,
+    /// Average score of the sampled brackets
+    pub sampled_mean: f64,
+    /// Best score among the sampled brackets
+    pub sampled_best: u32,
+    /// Score of a perfect bracket
+    pub perfect: u32,
+}
+
+/// Score the most likely bracket and `runs` brackets sampled with probabilities sharpened by
+/// `sharpen_factor` against the season's real results
+pub fn backtest_season(
+    season: &Season,
+    scoring: &ScoringSystem,
+    runs: usize,
+    sharpen_factor: f64,
+    sampler: &mut Sampler,
+) -> anyhow::Result<SeasonScore> {
+    let start = starting_bracket(&season.results);
+    let score = |bracket: &Tournament| score_bracket(bracket, &season.results, scoring).points;
+
+    let (most_likely, _) = most_probable_bracket(&start, &season.matrix)?;
+    let mut sampled = vec![];
+    for _ in 0..runs {
+        sampler.start_run();
+        let bracket = sample_bracket(&start, &season.matrix, sharpen_factor, sampler)?;
+        sampled.push(score(&bracket));
+    }
+    Ok(SeasonScore {
+        season: season.name.clone(),
+        most_likely: score(&most_likely),
+        sampled_mean: sampled.iter().sum::<u32>() as f64 / runs.max(1) as f64,
+        sampled_best: sampled.iter().copied().max().unwrap_or(0),
+        perfect: score(&season.results),
+    })
+}
+
+/// The bracket as it stood before the first game, with every result taken out
+fn starting_bracket(results: &Tournament) -> Tournament {
+    let mut start = results.clone();
+    let round1 = RoundKind::Round(1);
+    let winners = start.rounds[&round1]
+        .matchups
+        .iter()
+        .filter_map(|m| m.winning_team().map(str::to_string))
+        .collect::<Vec<_>>();
+    for winner in winners {
+        start.retract_team(&winner, round1);
+    }
+    start
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// Relative weights of each prediction source when blending them into one probability per
+/// game, e.g. `538=2,kenpom=1,odds=1,seeds=0.5`. Sources left out get no weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceWeights {
+    /// 538's probabilities from the bracket page
+    pub fivethirtyeight: f64,
+    /// Probabilities from efficiency ratings
+    pub kenpom: f64,
+    /// Sportsbook moneylines
+    pub odds: f64,
+    /// Historical win rates between the two seeds, a prior pulling the other sources towards
+    /// what usually happens
+    pub seeds: f64,
+}
+
+impl Default for SourceWeights {
+    /// Odds replace 538 for the games they cover
+    fn default() -> Self {
+        Self {
+            fivethirtyeight: 0.,
+            kenpom: 0.,
+            odds: 1.,
+            seeds: 0.,
+        }
+    }
+}
+
+impl FromStr for SourceWeights {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Self {
+            fivethirtyeight: 0.,
+            kenpom: 0.,
+            odds: 0.,
+            seeds: 0.,
+        };
+        for part in s.split(',') {
+            let (source, weight) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Expected source=weight, got {}", part))?;
+            let weight = weight.trim().parse::<f64>()?;
+            if weight < 0. {
+                return Err(anyhow!("Weight for {} can't be negative", source));
+            }
+            match source.trim().to_lowercase().as_str() {
+                "538" => weights.fivethirtyeight = weight,
+                "kenpom" => weights.kenpom = weight,
+                "odds" => weights.odds = weight,
+                "seeds" => weights.seeds = weight,
+                other => return Err(anyhow!("Unknown prediction source {}", other)),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+impl SourceWeights {
+    /// Weighted average of the sources with a probability for this game, None if none of
+    /// them has both a probability and some weight
+    pub fn blend(
+        &self,
+        fivethirtyeight: Option<f64>,
+        kenpom: Option<f64>,
+        odds: Option<f64>,
+        seeds: Option<f64>,
+    ) -> Option<f64> {
+        let (total, weight) = [
+            (fivethirtyeight, self.fivethirtyeight),
+            (kenpom, self.kenpom),
+            (odds, self.odds),
+            (seeds, self.seeds),
+        ]
+        .iter()
+        .filter_map(|(prob, weight)| prob.map(|prob| (prob, *weight)))
+        .fold((0., 0.), |(total, sum), (prob, weight)| {
+            (total + prob * weight, sum + weight)
+        });
+        (weight > 0.).then(|| total / weight)
+    }
+
+    /// Whether 538's probability counts, so it has to be read from the page
+    pub fn uses_fivethirtyeight(&self) -> bool {
+        self.fivethirtyeight > 0.
+    }
+}
@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::gender::gender;
+use crate::season;
+
+/// Where cached page HTML is kept
+const CACHE_DIR: &str = ".cache";
+
+/// How long cached HTML stays fresh, in seconds. 0 turns the cache off
+static TTL_SECS: AtomicU64 = AtomicU64::new(0);
+
+/// Set how long scraped HTML is reused for, with zero turning the cache off
+pub fn set_ttl(ttl: Duration) {
+    TTL_SECS.store(ttl.as_secs(), Ordering::Relaxed);
+}
+
+/// Cached HTML is kept apart for each year and tournament, since their pages differ
+fn path(key: &str) -> PathBuf {
+    let name = format!("{}-{}-{}.html", key, season::year(), gender().key());
+    PathBuf::from(CACHE_DIR).join(name)
+}
+
+/// Cached HTML for `key`, None if the cache is off, empty or stale
+pub fn get(key: &str) -> Option<String> {
+    let ttl = Duration::from_secs(TTL_SECS.load(Ordering::Relaxed));
+    if ttl.is_zero() {
+        return None;
+    }
+    let path = path(key);
+    let age = SystemTime::now()
+        .duration_since(fs::metadata(&path).ok()?.modified().ok()?)
+        .ok()?;
+    if age > ttl {
+        return None;
+    }
+    log::info!("Using {} cached {}s ago", path.display(), age.as_secs());
+    fs::read_to_string(path).ok()
+}
+
+/// Save HTML for `key` when the cache is on. A cache which can't be written only costs the
+/// next run a page load, so failures are just logged.
+pub fn put(key: &str, html: &str) {
+    if TTL_SECS.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let res = fs::create_dir_all(CACHE_DIR).and_then(|_| fs::write(path(key), html));
+    if let Err(e) = res {
+        log::warn!("Could not cache {}: {}", key, e);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thirtyfour::error::{WebDriverError, WebDriverResult};
+use thirtyfour::http::connection_async::{HttpClientCreateParams, WebDriverHttpClientAsync};
+use thirtyfour::http::reqwest_async::ReqwestDriverAsync;
+use thirtyfour::RequestData;
+
+/// Where the WebDriver traffic of a session is recorded to, or played back from
+#[derive(Debug, Clone)]
+pub enum Cassette {
+    /// Send commands to the server as usual, writing every response to the file
+    Record(PathBuf),
+    /// Answer commands from a recorded file without a server, so a run can be repeated
+    /// offline exactly as it went
+    Replay(PathBuf),
+}
+
+impl Cassette {
+    /// HTTP client for a session on the server at `url`, which isn't contacted when replaying
+    pub fn client(&self, url: &str) -> anyhow::Result<Box<dyn WebDriverHttpClientAsync>> {
+        Ok(match self {
+            Cassette::Record(path) => {
+                log::info!("Recording WebDriver traffic to {}", path.display());
+                Box::new(RecordingClient::new(url, path)?)
+            }
+            Cassette::Replay(path) => {
+                log::info!("Replaying WebDriver traffic from {}", path.display());
+                Box::new(ReplayClient::load(path)?)
+            }
+        })
+    }
+}
+
+/// One command sent to the WebDriver server and what came back, a line of the cassette file
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    body: Option<Value>,
+    /// The server's response, or the error message for a failed command
+    response: Result<Value, String>,
+}
+
+impl Interaction {
+    fn describe(&self) -> String {
+        format!("{} {}", self.method, self.url)
+    }
+}
+
+/// Key a request is matched on, as written to the cassette
+fn request_key(request_data: &RequestData) -> (String, String, Option<Value>) {
+    (
+        format!("{:?}", request_data.method).to_uppercase(),
+        request_data.url.clone(),
+        request_data.body.clone(),
+    )
+}
+
+/// HTTP client which sends commands on to the server and appends each exchange to the
+/// cassette as it happens, so a run that crashes still leaves everything up to the crash
+#[derive(Debug)]
+struct RecordingClient {
+    inner: ReqwestDriverAsync,
+    file: Mutex<File>,
+}
+
+impl RecordingClient {
+    fn new(url: &str, path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("Could not create cassette {}", path.display()))?;
+        Ok(Self {
+            inner: ReqwestDriverAsync::create(HttpClientCreateParams {
+                server_url: url.to_string(),
+                timeout: None,
+            })?,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn record(&self, interaction: &Interaction) {
+        let written = serde_json::to_string(interaction)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| {
+                let mut file = self.file.lock().unwrap();
+                Ok(writeln!(file, "{}", line)?)
+            });
+        if let Err(e) = written {
+            log::warn!("Could not record {}: {}", interaction.describe(), e);
+        }
+    }
+}
+
+#[async_trait]
+impl WebDriverHttpClientAsync for RecordingClient {
+    fn create(params: HttpClientCreateParams) -> WebDriverResult<Self> {
+        Err(WebDriverError::CustomError(format!(
+            "A recording client for {} needs a cassette to write to",
+            params.server_url
+        )))
+    }
+
+    fn set_request_timeout(&mut self, timeout: Duration) {
+        self.inner.set_request_timeout(timeout);
+    }
+
+    async fn execute(&self, request_data: RequestData) -> WebDriverResult<Value> {
+        let (method, url, body) = request_key(&request_data);
+        let response = self.inner.execute(request_data).await;
+        self.record(&Interaction {
+            method,
+            url,
+            body,
+            response: response.as_ref().cloned().map_err(|e| e.to_string()),
+        });
+        response
+    }
+}
+
+/// HTTP client which answers commands with the recorded responses, in the order they were
+/// recorded. A command other than the next recorded one means the run has gone differently
+/// from the recording, so it fails instead of guessing.
+#[derive(Debug)]
+struct ReplayClient {
+    interactions: Mutex<VecDeque<Interaction>>,
+}
+
+impl ReplayClient {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Could not read cassette {}", path.display()))?;
+        let interactions = data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Bad interaction at {}:{}", path.display(), i + 1))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            interactions: Mutex::new(interactions),
+        })
+    }
+}
+
+#[async_trait]
+impl WebDriverHttpClientAsync for ReplayClient {
+    fn create(params: HttpClientCreateParams) -> WebDriverResult<Self> {
+        Err(WebDriverError::CustomError(format!(
+            "A replay client for {} needs a cassette to read from",
+            params.server_url
+        )))
+    }
+
+    fn set_request_timeout(&mut self, _timeout: Duration) {}
+
+    async fn execute(&self, request_data: RequestData) -> WebDriverResult<Value> {
+        let (method, url, body) = request_key(&request_data);
+        let next = self.interactions.lock().unwrap().pop_front();
+        let next = next.ok_or_else(|| {
+            WebDriverError::CustomError(format!("Cassette ran out before {} {}", method, url))
+        })?;
+        if (&next.method, &next.url) != (&method, &url) {
+            return Err(WebDriverError::CustomError(format!(
+                "Replay diverged from the cassette: expected {}, got {} {}",
+                next.describe(),
+                method,
+                url
+            )));
+        }
+        if next.body != body {
+            return Err(WebDriverError::CustomError(format!(
+                "Replay diverged from the cassette: {} was sent with a different body",
+                next.describe()
+            )));
+        }
+        next.response.map_err(WebDriverError::CustomError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use thirtyfour::RequestMethod;
+
+    use super::*;
+
+    fn request(method: RequestMethod, url: &str, body: Option<Value>) -> RequestData {
+        RequestData {
+            method,
+            url: url.to_string(),
+            body,
+        }
+    }
+
+    fn replay(interactions: &[Interaction]) -> ReplayClient {
+        ReplayClient {
+            interactions: Mutex::new(interactions.iter().cloned().collect()),
+        }
+    }
+
+    fn interactions() -> Vec<Interaction> {
+        vec![
+            Interaction {
+                method: "POST".to_string(),
+                url: "/session".to_string(),
+                body: Some(json!({ "capabilities": {} })),
+                response: Ok(json!({ "value": { "sessionId": "abc" } })),
+            },
+            Interaction {
+                method: "GET".to_string(),
+                url: "/session/abc/url".to_string(),
+                body: None,
+                response: Err("no such window".to_string()),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn replays_responses_in_order() {
+        let client = replay(&interactions());
+        let session = client
+            .execute(request(
+                RequestMethod::Post,
+                "/session",
+                Some(json!({ "capabilities": {} })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(session, json!({ "value": { "sessionId": "abc" } }));
+        let url = client
+            .execute(request(RequestMethod::Get, "/session/abc/url", None))
+            .await;
+        assert!(url.is_err());
+        // Nothing is left on the cassette
+        let extra = client
+            .execute(request(RequestMethod::Get, "/session/abc/url", None))
+            .await;
+        assert!(extra.is_err());
+    }
+
+    #[tokio::test]
+    async fn diverging_requests_fail() {
+        let client = replay(&interactions());
+        let wrong_url = client
+            .execute(request(RequestMethod::Post, "/session/abc", None))
+            .await;
+        assert!(wrong_url.is_err());
+
+        let client = replay(&interactions());
+        let wrong_body = client
+            .execute(request(RequestMethod::Post, "/session", Some(json!({}))))
+            .await;
+        assert!(wrong_body.is_err());
+    }
+
+    #[test]
+    fn cassette_lines_round_trip() {
+        for interaction in interactions() {
+            let line = serde_json::to_string(&interaction).unwrap();
+            assert!(!line.contains('\n'));
+            let read: Interaction = serde_json::from_str(&line).unwrap();
+            assert_eq!(read, interaction);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use tokio::time::sleep;
+
+/// Port the launched chromedriver listens on
+pub const CHROMEDRIVER_PORT: u16 = 9515;
+
+/// Where downloaded chromedrivers are kept, one directory per Chrome major version
+const CACHE_DIR: &str = ".chromedriver";
+
+const DOWNLOAD_URL: &str = "https://chromedriver.storage.googleapis.com";
+
+/// How long to wait for a launched chromedriver to start accepting connections
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Ways Chrome may be installed, tried in order to find its version
+const CHROME_BINARIES: [&str; 5] = [
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+];
+
+/// A chromedriver running as a child process, killed when dropped
+pub struct ChromeDriver {
+    process: Child,
+    port: u16,
+}
+
+impl ChromeDriver {
+    /// Find a chromedriver matching the installed Chrome, downloading one if needed, then
+    /// launch it and wait until it is ready for connections
+    pub async fn launch() -> anyhow::Result<Self> {
+        let path = find_or_download().await?;
+        log::info!("Launching {}", path.display());
+        let process = Command::new(&path)
+            .arg(format!("--port={}", CHROMEDRIVER_PORT))
+            .stdout(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Could not launch {}", path.display()))?;
+        let driver = Self {
+            process,
+            port: CHROMEDRIVER_PORT,
+        };
+        driver.wait_until_ready().await?;
+        Ok(driver)
+    }
+
+    /// URL to connect the WebDriver client to
+    pub fn url(&self) -> String {
+        format!("http://localhost:{}", self.port)
+    }
+
+    async fn wait_until_ready(&self) -> anyhow::Result<()> {
+        let status_url = format!("{}/status", self.url());
+        let start = Instant::now();
+        while start.elapsed() < STARTUP_TIMEOUT {
+            if let Ok(response) = reqwest::get(&status_url).await {
+                if response.status().is_success() {
+                    return Ok(());
+                }
+            }
+            sleep(Duration::from_millis(250)).await;
+        }
+        Err(anyhow!(
+            "chromedriver did not start within {:?}",
+            STARTUP_TIMEOUT
+        ))
+    }
+}
+
+impl Drop for ChromeDriver {
+    fn drop(&mut self) {
+        if let Err(e) = self.process.kill() {
+            log::warn!("Could not stop chromedriver: {}", e);
+        }
+        let _ = self.process.wait();
+    }
+}
+
+/// Locate a chromedriver for the installed Chrome: `CHROMEDRIVER` if set, then one on the
+/// `PATH` with the same major version, then a cached download, downloading it if missing
+async fn find_or_download() -> anyhow::Result<PathBuf> {
+    if let Ok(path) = std::env::var("CHROMEDRIVER") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let major = major_version(&chrome_version()?).to_string();
+    if let Ok(version) = binary_version(Path::new(binary_name())) {
+        if major_version(&version) == major {
+            return Ok(PathBuf::from(binary_name()));
+        }
+        log::info!(
+            "chromedriver {} on the PATH does not match Chrome {}",
+            version,
+            major
+        );
+    }
+
+    let cached = Path::new(CACHE_DIR).join(&major).join(binary_name());
+    if !cached.exists() {
+        download(&major, &cached).await?;
+    }
+    Ok(cached)
+}
+
+/// Version of the installed Chrome, e.g. 99.0.4844.51
+fn chrome_version() -> anyhow::Result<String> {
+    CHROME_BINARIES
+        .iter()
+        .find_map(|binary| binary_version(Path::new(binary)).ok())
+        .ok_or_else(|| anyhow!("Could not find Chrome, set CHROMEDRIVER to a chromedriver instead"))
+}
+
+/// Run a binary with `--version` and pull the version number out of what it prints
+fn binary_version(binary: &Path) -> anyhow::Result<String> {
+    let output = Command::new(binary).arg("--version").output()?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("No version printed by {}", binary.display()))
+}
+
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+fn binary_name() -> &'static str {
+    if cfg!(windows) {
+        "chromedriver.exe"
+    } else {
+        "chromedriver"
+    }
+}
+
+fn platform() -> &'static str {
+    if cfg!(windows) {
+        "win32"
+    } else if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        "mac64_m1"
+    } else if cfg!(target_os = "macos") {
+        "mac64"
+    } else {
+        "linux64"
+    }
+}
+
+/// Download the latest chromedriver for a Chrome major version and unpack it to `dest`
+async fn download(major: &str, dest: &Path) -> anyhow::Result<()> {
+    let version = reqwest::get(format!("{}/LATEST_RELEASE_{}", DOWNLOAD_URL, major))
+        .await?
+        .error_for_status()
+        .with_context(|| format!("No chromedriver release for Chrome {}", major))?
+        .text()
+        .await?;
+    let url = format!(
+        "{}/{}/chromedriver_{}.zip",
+        DOWNLOAD_URL,
+        version.trim(),
+        platform()
+    );
+    log::info!("Downloading chromedriver from {}", url);
+    let archive = reqwest::get(&url)
+        .await?
+        .error_for_status()
+        .context("Could not download chromedriver")?
+        .bytes()
+        .await?;
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(archive))?;
+    let mut binary = archive.by_name(binary_name())?;
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::File::create(dest)?;
+    std::io::copy(&mut binary, &mut file)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thirtyfour::error::WebDriverResult;
+use thirtyfour::http::connection_async::{HttpClientCreateParams, WebDriverHttpClientAsync};
+use thirtyfour::http::reqwest_async::ReqwestDriverAsync;
+use thirtyfour::{Capabilities, DesiredCapabilities, RequestData, RequestMethod, WebDriver};
+
+use crate::cassette::Cassette;
+
+/// Path a Selenium server serves the WebDriver protocol under
+const HUB_PATH: &str = "/wd/hub";
+
+/// Where the browser session left open for the next run is recorded
+pub const SAVED_SESSION_PATH: &str = ".webdriver_session.json";
+
+/// What kind of WebDriver server to connect to
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum DriverKind {
+    /// A Selenium server, which drives Chrome
+    Selenium,
+    /// A chromedriver, talked to directly
+    Chromedriver,
+    /// A geckodriver, which drives Firefox
+    Geckodriver,
+}
+
+impl DriverKind {
+    /// Where each kind of server listens when started without options
+    pub fn default_endpoint(&self) -> &'static str {
+        match self {
+            DriverKind::Selenium => "http://localhost:4444",
+            DriverKind::Chromedriver => "http://localhost:9515",
+            DriverKind::Geckodriver => "http://localhost:4444",
+        }
+    }
+
+    /// URL WebDriver commands are sent to for an endpoint. Only a Selenium server serves them
+    /// under the hub path, while drivers serve them from the root.
+    pub fn session_url(&self, endpoint: &str) -> String {
+        let endpoint = endpoint.trim_end_matches('/');
+        if *self == DriverKind::Selenium && !endpoint.ends_with(HUB_PATH) {
+            format!("{}{}", endpoint, HUB_PATH)
+        } else {
+            endpoint.to_string()
+        }
+    }
+}
+
+/// Extra settings for the browser, passed through as they are
+#[derive(Debug, Clone, Default)]
+pub struct BrowserOptions {
+    /// Command line arguments for the browser, e.g. `--window-size=1920,1080`
+    pub args: Vec<String>,
+    /// Capabilities to request on top of the defaults, e.g. `goog:chromeOptions`
+    pub capabilities: Map<String, Value>,
+    /// Record the session's WebDriver traffic, or replay it instead of talking to the server
+    pub cassette: Option<Cassette>,
+}
+
+impl BrowserOptions {
+    /// Read extra capabilities from a JSON file holding an object of capability names to values
+    pub fn load_capabilities(&mut self, path: &Path) -> anyhow::Result<()> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Could not read capabilities {}", path.display()))?;
+        self.capabilities = serde_json::from_str(&data)
+            .with_context(|| format!("{} should hold a JSON object", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Start a browser session on a WebDriver server, Firefox for geckodriver and Chrome otherwise
+pub async fn connect(
+    kind: DriverKind,
+    endpoint: &str,
+    options: &BrowserOptions,
+) -> anyhow::Result<WebDriver> {
+    let url = kind.session_url(endpoint);
+    log::info!("Connecting to {}", url);
+    let driver = match kind {
+        DriverKind::Geckodriver => {
+            let mut caps = DesiredCapabilities::firefox();
+            add_capabilities(&mut caps, options)?;
+            if !options.args.is_empty() {
+                caps.add("moz:firefoxOptions", json!({ "args": options.args }))?;
+            }
+            open_session(&url, &caps, options).await?
+        }
+        DriverKind::Selenium | DriverKind::Chromedriver => {
+            let mut caps = DesiredCapabilities::chrome();
+            add_capabilities(&mut caps, options)?;
+            // Added after the capabilities so they extend any arguments given there
+            for arg in &options.args {
+                caps.add_chrome_arg(arg)?;
+            }
+            open_session(&url, &caps, options).await?
+        }
+    };
+    Ok(driver)
+}
+
+/// Ask the server for a new session, through the cassette when there is one
+async fn open_session<C: Serialize>(
+    url: &str,
+    caps: &C,
+    options: &BrowserOptions,
+) -> anyhow::Result<WebDriver> {
+    Ok(match &options.cassette {
+        Some(cassette) => WebDriver::new_with_client(cassette.client(url)?, caps).await?,
+        None => WebDriver::new(url, caps).await?,
+    })
+}
+
+/// A browser session left open on a WebDriver server
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct SavedSession {
+    url: String,
+    session_id: String,
+}
+
+impl SavedSession {
+    fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?,
+        );
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// Like [`connect`], but pick up the session a previous run left open on the same server
+/// when it is still alive, saving the session so the next run can do the same. The browser
+/// keeps its cookies and page between runs, so the session shouldn't be quit afterwards.
+pub async fn connect_reusing(
+    kind: DriverKind,
+    endpoint: &str,
+    options: &BrowserOptions,
+) -> anyhow::Result<WebDriver> {
+    let url = kind.session_url(endpoint);
+    if let Ok(saved) = SavedSession::load(SAVED_SESSION_PATH) {
+        if saved.url == url {
+            match attach(&url, &saved.session_id).await {
+                Ok(driver) => {
+                    log::info!("Reusing browser session {} on {}", saved.session_id, url);
+                    return Ok(driver);
+                }
+                Err(e) => log::info!(
+                    "Could not reuse browser session {}, starting a new one: {}",
+                    saved.session_id,
+                    e
+                ),
+            }
+        }
+    }
+    let driver = connect(kind, endpoint, options).await?;
+    let session_id = driver.session_id().await?.to_string();
+    SavedSession { url, session_id }.save(SAVED_SESSION_PATH)?;
+    Ok(driver)
+}
+
+/// Take over an existing session instead of asking the server for a new one
+async fn attach(url: &str, session_id: &str) -> anyhow::Result<WebDriver> {
+    let client = AttachClient {
+        inner: ReqwestDriverAsync::create(HttpClientCreateParams {
+            server_url: url.to_string(),
+            timeout: None,
+        })?,
+        session_id: session_id.to_string(),
+    };
+    let driver = WebDriver::new_with_client(Box::new(client), json!({})).await?;
+    // Attaching doesn't touch the browser, so make sure it is still there
+    driver.current_url().await?;
+    Ok(driver)
+}
+
+/// HTTP client which answers the new session request with an existing session and sends
+/// every other command on to the server
+#[derive(Debug)]
+struct AttachClient {
+    inner: ReqwestDriverAsync,
+    session_id: String,
+}
+
+#[async_trait]
+impl WebDriverHttpClientAsync for AttachClient {
+    fn create(params: HttpClientCreateParams) -> WebDriverResult<Self> {
+        Ok(Self {
+            inner: ReqwestDriverAsync::create(params)?,
+            session_id: String::new(),
+        })
+    }
+
+    fn set_request_timeout(&mut self, timeout: Duration) {
+        self.inner.set_request_timeout(timeout);
+    }
+
+    async fn execute(&self, request_data: RequestData) -> WebDriverResult<Value> {
+        match request_data.method {
+            RequestMethod::Post if request_data.url == "/session" => Ok(json!({
+                "value": { "sessionId": self.session_id, "capabilities": {} }
+            })),
+            _ => self.inner.execute(request_data).await,
+        }
+    }
+}
+
+fn add_capabilities(caps: &mut impl Capabilities, options: &BrowserOptions) -> anyhow::Result<()> {
+    for (key, value) in &options.capabilities {
+        caps.add(key, value.clone())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anyhow::anyhow;
+use serde::Serialize;
+
+use crate::probabilities::{sharpen, ProbabilityMatrix};
+use crate::rng::{RngKind, Sampler, Sampling};
+use crate::series::play_series;
+use crate::summary::SimulationSummary;
+use crate::tournament::{RoundKind, Tournament};
+
+/// Play out a bracket by drawing each undecided game from the head-to-head probabilities,
+/// game by game through each series when the field plays them. Games which already have a
+/// winner are kept as they are.
+pub fn sample_bracket(
+    start: &Tournament,
+    matrix: &ProbabilityMatrix,
+    sharpen_factor: f64,
+    sampler: &mut Sampler,
+) -> anyhow::Result<Tournament> {
+    let mut bracket = start.clone();
+    for round_num in 1..=bracket.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        for ind in 0..bracket.field.matchup_count(round_num) {
+            let matchup = &bracket.rounds[&round].matchups[ind];
+            if matchup.completed() {
+                continue;
+            }
+            let [team1, team2] = matchup
+                .try_teams()
+                .map(|teams| teams.map(str::to_string))
+                .ok_or_else(|| anyhow!("The {} is missing a team", bracket.round_name(round)))?;
+            let prob = matrix
+                .get(&team1, &team2)
+                .ok_or_else(|| anyhow!("No probability for {} vs {}", team1, team2))?;
+            let best_of = bracket.field.best_of;
+            let (winner, games) =
+                play_series(sharpen(prob, sharpen_factor), best_of, || sampler.draw());
+            bracket.advance_team([&team1, &team2][winner.to_ind()], round);
+            if best_of > 1 {
+                bracket.get_round_mut(round).matchups[ind].set_series_score(games);
+            }
+        }
+    }
+    Ok(bracket)
+}
+
+/// Simulate the rest of the tournament `runs` times from the probabilities alone, without a
+/// browser
+pub fn simulate_offline(
+    start: &Tournament,
+    matrix: &ProbabilityMatrix,
+    runs: usize,
+    sharpen_factor: f64,
+    sampler: &mut Sampler,
+) -> anyhow::Result<SimulationSummary> {
+    let mut summary = SimulationSummary::new(sampler.sampling());
+    for _ in 0..runs {
+        sampler.start_run();
+        summary.add(&sample_bracket(start, matrix, sharpen_factor, sampler)?);
+    }
+    Ok(summary)
+}
+
+/// z score of a two sided 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// How often each team won one matchup played over and over
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchupSplit {
+    /// Games won by each team
+    pub wins: [usize; 2],
+    /// Share of the games the first team won
+    pub share: f64,
+    /// 95% confidence interval on the first team's chance of winning, from the games played
+    pub interval: (f64, f64),
+}
+
+/// Play a game the first team wins with `prob` `runs` times
+pub fn simulate_matchup(prob: f64, runs: usize, sampler: &mut Sampler) -> MatchupSplit {
+    let mut wins = [0, 0];
+    for _ in 0..runs {
+        sampler.start_run();
+        let winner = if sampler.draw() < prob { 0 } else { 1 };
+        wins[winner] += 1;
+    }
+    let share = wins[0] as f64 / runs.max(1) as f64;
+    let margin = Z_95 * (share * (1. - share) / runs.max(1) as f64).sqrt();
+    MatchupSplit {
+        wins,
+        share,
+        interval: ((share - margin).max(0.), (share + margin).min(1.)),
+    }
+}
+
+/// An outcome to condition simulations on: `team` wins its game in round `round_num`, so
+/// round 4 assumes it reaches the Final Four
+#[derive(Debug, Clone)]
+pub struct Condition {
+    pub team: String,
+    pub round_num: usize,
+}
+
+impl Condition {
+    pub fn holds(&self, bracket: &Tournament) -> bool {
+        bracket.rounds[&RoundKind::Round(self.round_num)]
+            .matchups
+            .iter()
+            .any(|matchup| matchup.winning_team() == Some(self.team.as_str()))
+    }
+}
+
+/// Simulate the rest of the tournament `runs` times like [`simulate_offline`], summarizing
+/// every run and then only the runs where `condition` held. The other runs are thrown away
+/// rather than forcing the team through, since the team winning also says something about who
+/// it played: it more likely met weaker opponents.
+pub fn simulate_conditional(
+    start: &Tournament,
+    matrix: &ProbabilityMatrix,
+    runs: usize,
+    sharpen_factor: f64,
+    sampler: &mut Sampler,
+    condition: &Condition,
+) -> anyhow::Result<(SimulationSummary, SimulationSummary)> {
+    let mut all = SimulationSummary::default();
+    let mut given = SimulationSummary::default();
+    for _ in 0..runs {
+        sampler.start_run();
+        let bracket = sample_bracket(start, matrix, sharpen_factor, sampler)?;
+        all.add(&bracket);
+        if condition.holds(&bracket) {
+            given.add(&bracket);
+        }
+    }
+    if given.runs == 0 {
+        return Err(anyhow!(
+            "{} never won in round {} over {} runs, so there is nothing to condition on",
+            condition.team,
+            condition.round_num,
+            runs
+        ));
+    }
+    Ok((all, given))
+}
+
+/// Entry point for front ends: take a bracket and a probability file as JSON, in the same
+/// formats they are saved in, and return the summary of `runs` simulations as JSON. The seed
+/// is required since there may be no OS randomness to fall back on.
+pub fn simulate_json(
+    bracket: &str,
+    probabilities: &str,
+    runs: usize,
+    seed: u64,
+) -> anyhow::Result<String> {
+    let start: Tournament = serde_json::from_str(bracket)?;
+    let matrix: ProbabilityMatrix = serde_json::from_str(probabilities)?;
+    let mut sampler = Sampler::new(RngKind::Chacha, Some(seed), Sampling::Independent, runs);
+    let summary = simulate_offline(&start, &matrix, runs, 1., &mut sampler)?;
+    Ok(serde_json::to_string(&summary)?)
+}
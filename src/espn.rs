@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use crate::teams::{find_team, Team};
+use crate::tournament::{RoundKind, Tournament};
+
+/// One row of an ESPN Tournament Challenge group export: `entry` picked `team` to win their
+/// game in `round` (1 for the round of 64 through 6 for the championship)
+#[derive(Debug, Deserialize)]
+struct PickRecord {
+    entry: String,
+    round: usize,
+    team: String,
+}
+
+/// A competitor's bracket in the pool
+#[derive(Debug)]
+pub struct Entry {
+    /// Entry name as shown by ESPN
+    pub name: String,
+    /// The picks of this entry
+    pub bracket: Tournament,
+}
+
+/// Parse the picks of every entry in an ESPN group export. The export is a CSV with an
+/// `entry,round,team` header and one row per pick. Team names are matched loosely against
+/// the 538 names in `teams`.
+pub fn import_entries(path: &Path, mut teams: Vec<Team>) -> anyhow::Result<Vec<Entry>> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Could not open ESPN export {}", path.display()))?;
+    // Each entry's picks, with the line of the export they're on
+    let mut picks: BTreeMap<String, Vec<(RoundKind, String, usize)>> = BTreeMap::new();
+    for (ind, record) in reader.deserialize().enumerate() {
+        let record: PickRecord = record?;
+        // After the header
+        let line = ind + 2;
+        if !(1..=6).contains(&record.round) {
+            return Err(anyhow!("Unexpected round {} in ESPN export", record.round));
+        }
+        let team = find_team(&teams, &record.team)
+            .with_context(|| format!("No 538 team matches ESPN team {}", record.team))?;
+        picks.entry(record.entry).or_default().push((
+            RoundKind::Round(record.round),
+            team.name().to_string(),
+            line,
+        ));
+    }
+
+    let picked: HashSet<&str> = picks
+        .values()
+        .flatten()
+        .map(|(_, team, _)| team.as_str())
+        .collect();
+    retain_first_four_winners(&mut teams, &picked)?;
+
+    let mut entries = vec![];
+    for (name, mut entry_picks) in picks {
+        entry_picks.sort_by_key(|(round, _, _)| round.number());
+        let mut bracket = Tournament::new(&mut teams, Default::default())?;
+        for (round, team, line) in entry_picks {
+            let matchup = bracket.rounds[&round]
+                .matchups
+                .iter()
+                .find(|m| m.includes_team(&team))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Entry {} picks {} in the {} on line {} without advancing them there",
+                        name,
+                        team,
+                        round,
+                        line
+                    )
+                })?;
+            if let Some(winner) = matchup.winning_team() {
+                return Err(anyhow!(
+                    "Entry {} picks {} in the {} on line {}, but already picked {} in that game",
+                    name,
+                    team,
+                    round,
+                    line,
+                    winner
+                ));
+            }
+            let next_full = bracket.next_round(round).map(|next_round| {
+                bracket.rounds[&next_round].matchups[matchup.index() / 2]
+                    .try_teams()
+                    .is_some()
+            });
+            if next_full == Some(true) {
+                return Err(anyhow!(
+                    "Entry {} picks {} in the {} on line {}, but their next game is already full",
+                    name,
+                    team,
+                    round,
+                    line
+                ));
+            }
+            bracket.advance_team(&team, round);
+        }
+        entries.push(Entry { name, bracket });
+    }
+    Ok(entries)
+}
+
+/// ESPN brackets start after the First Four, so keep whichever team of each First Four pair
+/// was picked by anyone. If neither was picked, every entry has that slot losing in round 1
+/// and either team can stand in.
+fn retain_first_four_winners(teams: &mut Vec<Team>, picked: &HashSet<&str>) -> anyhow::Result<()> {
+    let mut dropped = HashSet::new();
+    for team in teams.iter() {
+        if let Some(opponent) = &team.first_four_opponent {
+            if dropped.contains(team.name()) || dropped.contains(opponent.as_str()) {
+                continue;
+            }
+            match (
+                picked.contains(team.name()),
+                picked.contains(opponent.as_str()),
+            ) {
+                (true, true) => {
+                    return Err(anyhow!(
+                        "Both {} and {} are picked, but only one can win the First Four",
+                        team.name(),
+                        opponent
+                    ))
+                }
+                (true, false) | (false, false) => dropped.insert(opponent.clone()),
+                (false, true) => dropped.insert(team.name().to_string()),
+            };
+        }
+    }
+    teams.retain(|team| !dropped.contains(team.name()));
+    Ok(())
+}
+
+/// Import an ESPN group export and write each entry's bracket to a JSON file in `dir`,
+/// returning the files written
+pub fn write_entries(path: &Path, teams: Vec<Team>, dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut written = vec![];
+    for entry in import_entries(path, teams)? {
+        let file_name: String = entry
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let out = dir.join(format!("{}.json", file_name));
+        entry.bracket.save(&out)?;
+        log::info!("Wrote bracket for {} to {}", entry.name, out.display());
+        written.push(out);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tournament::Region;
+
+    fn teams() -> Vec<Team> {
+        let regions = [Region::West, Region::East, Region::South, Region::Midwest];
+        regions
+            .iter()
+            .flat_map(|region| {
+                (1..=16).map(move |seed| {
+                    serde_json::from_value(json!({
+                        "name": format!("{} {}", region, seed),
+                        "region": region,
+                        "seed": seed,
+                    }))
+                    .unwrap()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rejects_two_picks_from_one_game() {
+        let dir = std::env::temp_dir().join(format!("march-madness-espn-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let import = |name: &str, rows: &str| {
+            let path = dir.join(name);
+            fs::write(&path, format!("entry,round,team\n{}", rows)).unwrap();
+            import_entries(&path, teams()).map_err(|err| err.to_string())
+        };
+
+        let entries = import("ok.csv", "Ann,1,West 1\nAnn,2,West 1\n").unwrap();
+        assert_eq!(
+            entries[0].bracket.rounds[&RoundKind::Round(2)].matchups[0].winning_team(),
+            Some("West 1")
+        );
+        let twice = import("twice.csv", "Ann,1,West 1\nAnn,1,West 16\n").unwrap_err();
+        assert_eq!(
+            twice,
+            "Entry Ann picks West 16 in the Round of 64 on line 3, but already picked West 1 in \
+             that game"
+        );
+        let early = import("early.csv", "Ann,2,West 1\n").unwrap_err();
+        assert!(early.contains("without advancing them there"), "{}", early);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
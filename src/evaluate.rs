@@ -0,0 +1,132 @@
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+
+/// Probabilities are kept this far from 0 and 1 so a confident miss doesn't make log loss
+/// infinite
+const MIN_PROB: f64 = 1e-6;
+
+/// Which predictions to evaluate
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum Predictions {
+    /// Head-to-head probabilities scraped from 538
+    Matrix,
+    /// Probabilities from the efficiency ratings given by --efficiency
+    Kenpom,
+    /// Moneylines given by --odds
+    Odds,
+    /// Historical win rates between the teams' seeds
+    Seeds,
+}
+
+/// How well predictions matched the results of a set of games
+#[derive(Debug, Clone, Default)]
+pub struct Evaluation {
+    /// Games with both a result and a prediction
+    pub games: usize,
+    /// Games with a result but no prediction
+    pub missing: usize,
+    brier_total: f64,
+    log_loss_total: f64,
+    /// Surprisal of the results and its expected value under the predictions, in bits
+    surprisal_total: f64,
+    entropy_total: f64,
+}
+
+impl Evaluation {
+    fn add(&mut self, prob: f64, won: bool) {
+        let outcome = if won { 1. } else { 0. };
+        self.entropy_total += entropy(prob);
+        let prob = prob.clamp(MIN_PROB, 1. - MIN_PROB);
+        self.games += 1;
+        self.brier_total += (prob - outcome).powi(2);
+        self.log_loss_total -= if won { prob.ln() } else { (1. - prob).ln() };
+        self.surprisal_total -= if won { prob.log2() } else { (1. - prob).log2() };
+    }
+
+    /// Mean squared error of the probabilities, 0.25 for always saying 50%
+    pub fn brier(&self) -> f64 {
+        self.brier_total / self.games.max(1) as f64
+    }
+
+    /// Mean negative log likelihood of the results, about 0.693 for always saying 50%
+    pub fn log_loss(&self) -> f64 {
+        self.log_loss_total / self.games.max(1) as f64
+    }
+
+    /// Total surprisal of the results in bits: each game adds -log2 of the predicted chance of
+    /// what happened, so a coin flip adds 1 and a 1 in 8 upset adds 3
+    pub fn surprisal(&self) -> f64 {
+        self.surprisal_total
+    }
+
+    /// Surprisal the predictions expected these games to have
+    pub fn expected_surprisal(&self) -> f64 {
+        self.entropy_total
+    }
+
+    /// How much more surprising the results were than expected, in bits. Above 0 means a
+    /// madder tournament than the predictions allowed for.
+    pub fn madness(&self) -> f64 {
+        self.surprisal_total - self.entropy_total
+    }
+}
+
+/// Expected surprisal in bits of a game the first team wins with chance `prob`. A certain
+/// game expects none, taking 0 log 0 as 0.
+fn entropy(prob: f64) -> f64 {
+    [prob, 1. - prob]
+        .iter()
+        .filter(|p| **p > 0.)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+/// Score predictions for every decided game in `actual`, round by round. `predict` gives the
+/// chance of the first team beating the second, if there is one. Returns an evaluation for
+/// each round with games, followed by one over all games.
+pub fn evaluate(
+    actual: &Tournament,
+    predict: impl Fn(&str, &str) -> Option<f64>,
+) -> (Vec<(RoundKind, Evaluation)>, Evaluation) {
+    let mut rounds = vec![];
+    let mut overall = Evaluation::default();
+    for round_num in 1..=actual.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        let mut evaluation = Evaluation::default();
+        for matchup in &actual.rounds[&round].matchups {
+            let (teams, winner) = match (matchup.try_teams(), matchup.winner()) {
+                (Some(teams), Some(winner)) => (teams, winner),
+                _ => continue,
+            };
+            match predict(teams[0], teams[1]) {
+                Some(prob) => {
+                    let won = winner == MatchupInd::Team1;
+                    evaluation.add(prob, won);
+                    overall.add(prob, won);
+                }
+                None => {
+                    evaluation.missing += 1;
+                    overall.missing += 1;
+                }
+            }
+        }
+        if evaluation.games + evaluation.missing > 0 {
+            rounds.push((round, evaluation));
+        }
+    }
+    (rounds, overall)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn certain_games_expect_no_surprisal() {
+        assert_eq!(entropy(0.5), 1.);
+        let mut evaluation = Evaluation::default();
+        evaluation.add(1., true);
+        evaluation.add(0., false);
+        assert_eq!(evaluation.expected_surprisal(), 0.);
+        assert!(evaluation.madness().is_finite());
+    }
+}
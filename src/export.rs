@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Float64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::analytic::RoundProbabilities;
+use crate::scoring::ScoringSystem;
+
+/// Names of the columns holding the chance of winning in each round
+const ROUND_COLUMNS: [&str; 6] = [
+    "round_of_32",
+    "sweet_16",
+    "elite_8",
+    "final_four",
+    "title_game",
+    "champion",
+];
+
+/// Every team's chance of winning in each round and expected points under `scoring`, one row
+/// per team. Round probabilities can come from the exact calculation, the 538 forecast or
+/// [`RoundProbabilities::from_summary`] for simulations.
+pub fn advancement_batch(
+    probs: &RoundProbabilities,
+    scoring: &ScoringSystem,
+) -> anyhow::Result<RecordBatch> {
+    let mut fields = vec![Field::new("team", DataType::Utf8, false)];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from(
+        probs.probs.keys().map(String::as_str).collect::<Vec<_>>(),
+    ))];
+    for (round_ind, name) in ROUND_COLUMNS.into_iter().enumerate() {
+        fields.push(Field::new(name, DataType::Float64, false));
+        columns.push(Arc::new(Float64Array::from(
+            probs
+                .probs
+                .values()
+                .map(|p| p[round_ind])
+                .collect::<Vec<_>>(),
+        )));
+    }
+    fields.push(Field::new("expected_points", DataType::Float64, false));
+    columns.push(Arc::new(Float64Array::from(
+        probs
+            .probs
+            .values()
+            .map(|p| scoring.expected_points(p))
+            .collect::<Vec<_>>(),
+    )));
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
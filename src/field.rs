@@ -0,0 +1,403 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::tournament::{RoundKind, Seed};
+
+/// Seeds of an NCAA tournament region from the top of the bracket down
+const NCAA_ORDER: [u8; 16] = [1, 16, 8, 9, 5, 12, 4, 13, 6, 11, 3, 14, 7, 10, 2, 15];
+
+/// Most regions a bracket can be split into, one for each [`crate::tournament::Region`]
+const MAX_REGIONS: usize = 4;
+
+/// Longest series a matchup can be played as
+const MAX_SERIES_GAMES: usize = 9;
+
+/// How the seeds of a region are placed against each other in the first round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Seeding {
+    /// The NCAA tournament's order for regions of 16: 1-16, 8-9, 5-12, 4-13, 6-11, 3-14,
+    /// 7-10 and 2-15
+    Ncaa,
+    /// The best seed plays the worst, and the top two seeds can only meet in the region's
+    /// final, e.g. 1-8, 4-5, 2-7, 3-6 for a region of 8
+    Standard,
+}
+
+/// Shape of a single elimination bracket: how many teams play, how many regions they are
+/// seeded in, how each region is laid out and how long each matchup is. Rounds are numbered
+/// from 1 with the title game last, so a 16 team bracket plays rounds 1 to 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Field {
+    /// Number of teams in the first round
+    pub teams: usize,
+    /// Number of regions, whose winners meet in the last rounds
+    pub regions: usize,
+    pub seeding: Seeding,
+    /// Games in each matchup's series, 1 for single games as in March Madness
+    #[serde(default = "single_game")]
+    pub best_of: usize,
+}
+
+fn single_game() -> usize {
+    1
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Self::NCAA
+    }
+}
+
+impl Field {
+    /// The NCAA tournament once the First Four is played: four regions of 16
+    pub const NCAA: Field = Field {
+        teams: 64,
+        regions: 4,
+        seeding: Seeding::Ncaa,
+        best_of: 1,
+    };
+
+    /// A field of `teams` split evenly into `regions`, both of which must be powers of two.
+    /// Regions can hold up to 16 seeds.
+    pub fn new(teams: usize, regions: usize, seeding: Seeding) -> anyhow::Result<Self> {
+        if !teams.is_power_of_two() || !(4..=64).contains(&teams) {
+            return Err(anyhow!(
+                "A bracket needs 4, 8, 16, 32 or 64 teams, not {}",
+                teams
+            ));
+        }
+        if !regions.is_power_of_two() || regions > MAX_REGIONS || regions > teams / 2 {
+            return Err(anyhow!(
+                "{} teams can't be split into {} regions",
+                teams,
+                regions
+            ));
+        }
+        let field = Self {
+            teams,
+            regions,
+            seeding,
+            best_of: 1,
+        };
+        let seeds = field.seeds_per_region();
+        if seeds > 16 {
+            return Err(anyhow!(
+                "Regions can hold up to 16 seeds, use more regions for {} teams",
+                teams
+            ));
+        }
+        if seeding == Seeding::Ncaa && seeds != 16 {
+            return Err(anyhow!("NCAA seeding needs regions of 16, not {}", seeds));
+        }
+        Ok(field)
+    }
+
+    /// The same field with every matchup played as a best-of series, e.g. 7 for playoffs
+    pub fn with_best_of(self, best_of: usize) -> anyhow::Result<Self> {
+        if best_of % 2 != 1 || best_of > MAX_SERIES_GAMES {
+            return Err(anyhow!(
+                "Series need an odd number of games up to {}, not {}",
+                MAX_SERIES_GAMES,
+                best_of
+            ));
+        }
+        Ok(Self { best_of, ..self })
+    }
+
+    /// Number of rounds, the last being the title game
+    pub fn round_count(&self) -> usize {
+        self.teams.trailing_zeros() as usize
+    }
+
+    /// Name of a round in a bracket of this field, see [`RoundKind::name`]
+    pub fn round_name(&self, round: RoundKind) -> String {
+        round.name(self.round_count())
+    }
+
+    /// Number of games in a round 1..=round_count
+    pub fn matchup_count(&self, round_num: usize) -> usize {
+        self.teams >> round_num
+    }
+
+    pub fn seeds_per_region(&self) -> usize {
+        self.teams / self.regions
+    }
+
+    /// Seeds of a region in bracket order from the top, so each pair plays in the first round
+    pub fn seed_order(&self) -> Vec<u8> {
+        match self.seeding {
+            Seeding::Ncaa => NCAA_ORDER.to_vec(),
+            Seeding::Standard => {
+                let mut order = vec![1];
+                while order.len() < self.seeds_per_region() {
+                    let size = order.len() as u8 * 2;
+                    order = order.iter().flat_map(|&s| [s, size + 1 - s]).collect();
+                }
+                order
+            }
+        }
+    }
+
+    /// First round game of the given seed in the region with this index, None if the field
+    /// has no such seed or region
+    pub fn first_round_matchup(&self, region_ind: usize, seed: Seed) -> Option<usize> {
+        if region_ind >= self.regions {
+            return None;
+        }
+        let position = self.seed_order().iter().position(|&s| s == seed.0)?;
+        Some(region_ind * self.seeds_per_region() / 2 + position / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::analytic;
+    use crate::engine::{sample_bracket, simulate_offline};
+    use crate::probabilities::ProbabilityMatrix;
+    use crate::rng::{RngKind, Sampler, Sampling};
+    use crate::scoring::{self, ScoringSystem};
+    use crate::series::series_win_probability;
+    use crate::strategy::{build_bracket, Strategy};
+    use crate::teams::Team;
+    use crate::tournament::{MatchupInd, Region, RoundKind, Tournament};
+
+    fn teams(field: &Field) -> Vec<Team> {
+        let regions = [Region::West, Region::East, Region::South, Region::Midwest];
+        regions[..field.regions]
+            .iter()
+            .flat_map(|region| {
+                (1..=field.seeds_per_region()).map(move |seed| {
+                    serde_json::from_value(json!({
+                        "name": format!("{} {}", region, seed),
+                        "region": region,
+                        "seed": seed,
+                    }))
+                    .unwrap()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn standard_seed_orders() {
+        let order = |teams| {
+            Field::new(teams, 1, Seeding::Standard)
+                .unwrap()
+                .seed_order()
+        };
+        assert_eq!(order(4), [1, 4, 2, 3]);
+        assert_eq!(order(8), [1, 8, 4, 5, 2, 7, 3, 6]);
+        assert_eq!(
+            order(16),
+            [1, 16, 8, 9, 4, 13, 5, 12, 2, 15, 7, 10, 3, 14, 6, 11]
+        );
+    }
+
+    #[test]
+    fn ncaa_field_matches_the_ncaa_bracket() {
+        let field = Field::new(64, 4, Seeding::Ncaa).unwrap();
+        assert_eq!(field, Field::NCAA);
+        assert_eq!(field.round_count(), 6);
+        assert_eq!(field.first_round_matchup(0, Seed(1)), Some(0));
+        assert_eq!(field.first_round_matchup(0, Seed(5)), Some(2));
+        assert_eq!(field.first_round_matchup(1, Seed(15)), Some(15));
+        assert_eq!(field.first_round_matchup(4, Seed(1)), None);
+    }
+
+    #[test]
+    fn bad_fields_are_rejected() {
+        assert!(Field::new(12, 1, Seeding::Standard).is_err());
+        assert!(Field::new(128, 8, Seeding::Standard).is_err());
+        assert!(Field::new(64, 2, Seeding::Standard).is_err());
+        assert!(Field::new(8, 8, Seeding::Standard).is_err());
+        assert!(Field::new(32, 4, Seeding::Ncaa).is_err());
+        for best_of in [0, 2, 11] {
+            assert!(Field::NCAA.with_best_of(best_of).is_err(), "{}", best_of);
+        }
+    }
+
+    #[test]
+    fn repeated_seeds_are_rejected() {
+        let field = Field::new(8, 1, Seeding::Standard).unwrap();
+        let mut teams = teams(&field);
+        // The second 1 seed would have taken the 8 seed's slot in the first game
+        teams[1].seed = Seed(1);
+        let err = Tournament::with_field(field, &mut teams).unwrap_err();
+        assert_eq!(err.to_string(), "The West has two 1 seeds");
+    }
+
+    #[test]
+    fn retracting_a_team_not_in_the_round_does_nothing() {
+        let field = Field::new(8, 1, Seeding::Standard).unwrap();
+        let mut bracket = Tournament::with_field(field, &mut teams(&field)).unwrap();
+        bracket.advance_team("West 1", RoundKind::Round(1));
+        bracket.advance_team("West 1", RoundKind::Round(2));
+        // West 8 lost in round 1, so it isn't in round 2 at all
+        bracket.retract_team("West 8", RoundKind::Round(2));
+        bracket.retract_team("Nobody", RoundKind::Round(1));
+        assert_eq!(
+            bracket.rounds[&RoundKind::Round(2)].matchups[0].winning_team(),
+            Some("West 1")
+        );
+        bracket.retract_team("West 1", RoundKind::Round(1));
+        assert!(bracket.rounds[&RoundKind::Round(2)].matchups[0]
+            .try_teams()
+            .is_none());
+        assert!(bracket.rounds[&RoundKind::Round(3)].matchups[0]
+            .team(MatchupInd::Team1)
+            .is_none());
+    }
+
+    #[test]
+    fn brackets_of_every_size_play_out() {
+        for (size, regions) in [(4, 1), (8, 1), (8, 2), (16, 1), (32, 2), (32, 4), (64, 4)] {
+            let field = Field::new(size, regions, Seeding::Standard).unwrap();
+            let mut teams = teams(&field);
+            let mut bracket = Tournament::with_field(field, &mut teams).unwrap();
+            assert_eq!(bracket.rounds.len(), field.round_count());
+            let round1 = &bracket.rounds[&RoundKind::Round(1)].matchups;
+            let placed = round1
+                .iter()
+                .flat_map(|m| m.teams())
+                .collect::<HashSet<_>>();
+            assert_eq!(placed.len(), size);
+
+            // The better seed wins every game, so each region's top seed reaches its final
+            for round_num in 1..=field.round_count() {
+                let round = RoundKind::Round(round_num);
+                for ind in 0..field.matchup_count(round_num) {
+                    let matchup = &bracket.rounds[&round].matchups[ind];
+                    let winner =
+                        if matchup.seed(MatchupInd::Team1) <= matchup.seed(MatchupInd::Team2) {
+                            matchup.teams()[0].clone()
+                        } else {
+                            matchup.teams()[1].clone()
+                        };
+                    bracket.advance_team(&winner, round);
+                }
+            }
+            assert_eq!(bracket.champion(), Some("West 1"), "{:?}", field);
+        }
+    }
+
+    #[test]
+    fn a_sixteen_team_field_plays_and_scores() {
+        let field = Field::new(16, 2, Seeding::Standard).unwrap();
+        let mut teams = teams(&field);
+        let start = Tournament::with_field(field, &mut teams).unwrap();
+        // Better seeds are stronger, and the West a little stronger than the East, so no game
+        // is a toss-up
+        let ratings = teams
+            .iter()
+            .map(|team| {
+                let rating = -(team.seed.0 as f64) - team.region.to_ind() as f64 / 10.;
+                (team.name(), rating)
+            })
+            .collect::<Vec<_>>();
+        let matrix = ProbabilityMatrix::from_ratings(&ratings);
+        let scoring = ScoringSystem::default();
+
+        let chalk = build_bracket(Strategy::Chalk, &start, &matrix, &scoring).unwrap();
+        let likely = build_bracket(Strategy::MaxLikelihood, &start, &matrix, &scoring).unwrap();
+        assert_eq!(chalk.champion(), likely.champion());
+        assert_eq!(chalk.final_four().len(), 4);
+        let mut sampler = Sampler::new(RngKind::Std, Some(1), Sampling::Independent, 1);
+        let played = sample_bracket(&start, &matrix, 1., &mut sampler).unwrap();
+        assert!(played.champion().is_some());
+
+        // Four rounds of 8, 4, 2 and 1 games worth 10, 20, 40 and 80
+        assert_eq!(scoring.max_points(&field), 320);
+        let perfect = scoring::score_bracket(&chalk, &chalk, &scoring);
+        assert_eq!(perfect.points, 320);
+        let score = scoring::score_bracket(&chalk, &played, &scoring);
+        assert!(score.points + score.max_remaining <= 320);
+        assert_eq!(scoring::chalk_score(&chalk, Some(&matrix)).upsets, 0);
+        assert_eq!(scoring::similarity(&chalk, &likely, &scoring).overall, 1.);
+
+        let probs = analytic::round_probabilities(&start, &matrix).unwrap();
+        let title = |team| probs.win_probability(team, field.round_count());
+        let total: f64 = teams.iter().map(|team| title(team.name())).sum();
+        assert!((total - 1.).abs() < 1e-9);
+        let path = analytic::title_path(&start, &matrix, "West 1").unwrap();
+        assert_eq!(path.len(), 4);
+        assert!(
+            (path.iter().map(|r| r.win_if_reached).product::<f64>() - title("West 1")).abs() < 1e-9
+        );
+
+        // Rounds are named for the teams left, so the field starts at the Sweet 16
+        assert_eq!(start.round_name(RoundKind::Round(1)), "Sweet 16");
+        assert_eq!(start.round_name(RoundKind::Round(4)), "Championship");
+        assert!(start.to_string().starts_with("Sweet 16\n"));
+        assert_eq!(RoundKind::Round(1).to_string(), "Round of 64");
+        assert_eq!(RoundKind::Round(1).name(7), "Round of 128");
+    }
+
+    #[test]
+    fn analytic_chances_play_series_like_the_engine() {
+        let field = Field::new(4, 1, Seeding::Standard)
+            .unwrap()
+            .with_best_of(7)
+            .unwrap();
+        let mut teams = teams(&field);
+        let start = Tournament::with_field(field, &mut teams).unwrap();
+        let ratings = teams
+            .iter()
+            .map(|team| (team.name(), -(team.seed.0 as f64)))
+            .collect::<Vec<_>>();
+        let matrix = ProbabilityMatrix::from_ratings(&ratings);
+        let series = |team1, team2| series_win_probability(matrix.get(team1, team2).unwrap(), 7);
+
+        let probs = analytic::round_probabilities(&start, &matrix).unwrap();
+        let game = matrix.get("West 1", "West 4").unwrap();
+        assert!(series("West 1", "West 4") > game);
+        assert!((probs.win_probability("West 1", 1) - series("West 1", "West 4")).abs() < 1e-9);
+        let mut sampler = Sampler::new(RngKind::Std, Some(1), Sampling::Independent, 1);
+        let summary = simulate_offline(&start, &matrix, 20_000, 1., &mut sampler).unwrap();
+        for team in ["West 1", "West 2", "West 3"] {
+            let sampled = summary.win_probability(team, 2);
+            assert!(
+                (sampled - probs.win_probability(team, 2)).abs() < 0.01,
+                "{}",
+                team
+            );
+        }
+
+        let path = analytic::title_path(&start, &matrix, "West 1").unwrap();
+        assert!((path[0].opponents[0].win - series("West 1", "West 4")).abs() < 1e-9);
+        let (likely, chance) = analytic::most_probable_bracket(&start, &matrix).unwrap();
+        assert_eq!(likely.champion(), Some("West 1"));
+        let expected =
+            series("West 1", "West 4") * series("West 2", "West 3") * series("West 1", "West 2");
+        assert!((chance - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn antithetic_errors_come_from_the_pairs() {
+        let field = Field::new(4, 1, Seeding::Standard).unwrap();
+        let mut teams = teams(&field);
+        let start = Tournament::with_field(field, &mut teams).unwrap();
+        let ratings = teams
+            .iter()
+            .map(|team| (team.name(), 0.))
+            .collect::<Vec<_>>();
+        let matrix = ProbabilityMatrix::from_ratings(&ratings);
+        let summary = |sampling| {
+            let mut sampler = Sampler::new(RngKind::Std, Some(1), sampling, 100);
+            simulate_offline(&start, &matrix, 100, 1., &mut sampler).unwrap()
+        };
+
+        // Each pair of runs splits an even game, so its chance is known exactly
+        let antithetic = summary(Sampling::Antithetic);
+        assert_eq!(antithetic.win_probability("West 1", 1), 0.5);
+        assert_eq!(antithetic.standard_error("West 1", 1), 0.);
+        assert!(!antithetic.independent_errors());
+        let independent = summary(Sampling::Independent);
+        assert!(independent.standard_error("West 1", 1) > 0.04);
+        assert!(summary(Sampling::Stratified).independent_errors());
+    }
+}
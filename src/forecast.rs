@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use crate::analytic::RoundProbabilities;
+use crate::gender::gender;
+#[cfg(feature = "driver")]
+use crate::season;
+
+/// 538's published forecast for every team in the year's tournaments, updated as games finish
+pub fn forecast_url(year: u32) -> String {
+    format!(
+        "https://projects.fivethirtyeight.com/march-madness-api/{}/fivethirtyeight_ncaa_forecasts.csv",
+        year
+    )
+}
+
+/// Where each team's chances of winning in each round, as read from the teams table, are saved
+pub const FORECAST_TABLE_PATH: &str = "forecast_table.json";
+
+/// One row of the forecast CSV. `rd1_win` is the chance of getting through the First Four and
+/// `rd7_win` the chance of winning the title.
+#[derive(Debug, Deserialize)]
+struct ForecastRecord {
+    gender: String,
+    forecast_date: String,
+    team_name: String,
+    rd2_win: f64,
+    rd3_win: f64,
+    rd4_win: f64,
+    rd5_win: f64,
+    rd6_win: f64,
+    rd7_win: f64,
+}
+
+/// Download the forecast CSV
+#[cfg(feature = "driver")]
+pub async fn download_forecast() -> anyhow::Result<String> {
+    let response = reqwest::get(forecast_url(season::year()))
+        .await?
+        .error_for_status()
+        .context("Could not download the 538 forecast")?;
+    Ok(response.text().await?)
+}
+
+/// Parse each team's chance of winning in each round from the current tournament in the
+/// latest forecast in the CSV
+pub fn parse_forecast(data: &str) -> anyhow::Result<RoundProbabilities> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    let mut records = vec![];
+    for record in reader.deserialize() {
+        let record: ForecastRecord = record.context("Unexpected forecast format")?;
+        if record.gender == gender().key() {
+            records.push(record);
+        }
+    }
+    let latest = records
+        .iter()
+        .map(|r| r.forecast_date.clone())
+        .max()
+        .ok_or_else(|| anyhow!("No {} forecast found", gender()))?;
+
+    let probs: BTreeMap<String, [f64; 6]> = records
+        .into_iter()
+        .filter(|r| r.forecast_date == latest)
+        .map(|r| {
+            let round_probs = [
+                r.rd2_win, r.rd3_win, r.rd4_win, r.rd5_win, r.rd6_win, r.rd7_win,
+            ];
+            (r.team_name, round_probs)
+        })
+        .collect();
+    log::info!(
+        "Loaded 538 forecast from {} for {} teams",
+        latest,
+        probs.len()
+    );
+    Ok(RoundProbabilities { probs })
+}
@@ -0,0 +1,200 @@
+use std::fs::File;
+use std::path::Path;
+#[cfg(feature = "parquet-output")]
+use std::sync::Arc;
+
+#[cfg(feature = "parquet-output")]
+use anyhow::Context;
+#[cfg(feature = "parquet-output")]
+use arrow::array::{ArrayRef, Float64Array, StringArray, UInt64Array};
+#[cfg(feature = "parquet-output")]
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+#[cfg(feature = "parquet-output")]
+use arrow::record_batch::RecordBatch;
+#[cfg(feature = "parquet-output")]
+use parquet::arrow::ArrowWriter;
+use serde::{Deserialize, Serialize};
+
+use crate::season;
+
+/// One simulated game, as written to the games log
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GameRecord {
+    pub run: usize,
+    pub round: usize,
+    pub matchup: usize,
+    pub team1: String,
+    pub team2: String,
+    /// Chance of `team1` winning
+    pub win_prob: f64,
+    /// Random draw deciding the game, `team1` wins if it is below `win_prob`
+    pub draw: f64,
+    pub winner: String,
+}
+
+/// Where every simulated game is logged as CSV
+pub const GAMES_PATH: &str = "simulated_games.csv";
+
+/// Where every simulated game is logged as Parquet
+pub const GAMES_PARQUET_PATH: &str = "simulated_games.parquet";
+
+/// File format for the log of simulated games
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum GamesFormat {
+    /// CSV, easy to read but large for long runs
+    Csv,
+    /// Parquet, compact and quick to load into analytics tools. Needs the parquet-output
+    /// feature
+    Parquet,
+}
+
+impl GamesFormat {
+    /// Where games are logged in this format
+    pub fn path(&self) -> String {
+        match self {
+            GamesFormat::Csv => season::data_path(GAMES_PATH),
+            GamesFormat::Parquet => season::data_path(GAMES_PARQUET_PATH),
+        }
+    }
+}
+
+/// Read back the games logged as CSV by the last simulation
+pub fn load_games(path: impl AsRef<Path>) -> anyhow::Result<Vec<GameRecord>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    Ok(reader.deserialize().collect::<Result<_, _>>()?)
+}
+
+/// Writer for the log of simulated games
+pub enum GamesLog {
+    Csv(csv::Writer<File>),
+    #[cfg(feature = "parquet-output")]
+    Parquet(ParquetGames),
+}
+
+impl GamesLog {
+    /// Start a new log in the given format, replacing any earlier one
+    pub fn create(format: GamesFormat) -> anyhow::Result<Self> {
+        let path = format.path();
+        let log = match format {
+            GamesFormat::Csv => csv::Writer::from_path(path).map(GamesLog::Csv)?,
+            #[cfg(feature = "parquet-output")]
+            GamesFormat::Parquet => ParquetGames::create(path).map(GamesLog::Parquet)?,
+            #[cfg(not(feature = "parquet-output"))]
+            GamesFormat::Parquet => {
+                return Err(anyhow::anyhow!(
+                    "Parquet output needs building with the parquet-output feature"
+                ))
+            }
+        };
+        Ok(log)
+    }
+
+    pub fn write(&mut self, record: GameRecord) -> anyhow::Result<()> {
+        match self {
+            GamesLog::Csv(writer) => writer.serialize(record)?,
+            #[cfg(feature = "parquet-output")]
+            GamesLog::Parquet(writer) => writer.write(record)?,
+        }
+        Ok(())
+    }
+
+    /// Make sure everything written so far is on disk, or for Parquet will be once a full
+    /// batch has been collected
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        match self {
+            GamesLog::Csv(writer) => writer.flush()?,
+            #[cfg(feature = "parquet-output")]
+            GamesLog::Parquet(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Write out anything left and close the log
+    pub fn finish(self) -> anyhow::Result<()> {
+        match self {
+            GamesLog::Csv(mut writer) => writer.flush()?,
+            #[cfg(feature = "parquet-output")]
+            GamesLog::Parquet(writer) => writer.finish()?,
+        }
+        Ok(())
+    }
+}
+
+/// Games are written in row groups of this many
+#[cfg(feature = "parquet-output")]
+const BATCH_SIZE: usize = 64 * 1024;
+
+/// Parquet log of simulated games, buffering games until there are enough for a row group
+#[cfg(feature = "parquet-output")]
+pub struct ParquetGames {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    pending: Vec<GameRecord>,
+}
+
+#[cfg(feature = "parquet-output")]
+impl ParquetGames {
+    fn create(path: &str) -> anyhow::Result<Self> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("run", DataType::UInt64, false),
+            Field::new("round", DataType::UInt64, false),
+            Field::new("matchup", DataType::UInt64, false),
+            Field::new("team1", DataType::Utf8, false),
+            Field::new("team2", DataType::Utf8, false),
+            Field::new("win_prob", DataType::Float64, false),
+            Field::new("draw", DataType::Float64, false),
+            Field::new("winner", DataType::Utf8, false),
+        ]));
+        let file = File::create(path).with_context(|| format!("Could not create {}", path))?;
+        Ok(Self {
+            writer: ArrowWriter::try_new(file, schema.clone(), None)?,
+            schema,
+            pending: Vec::with_capacity(BATCH_SIZE),
+        })
+    }
+
+    fn write(&mut self, record: GameRecord) -> anyhow::Result<()> {
+        self.pending.push(record);
+        if self.pending.len() >= BATCH_SIZE {
+            self.write_batch()?;
+        }
+        Ok(())
+    }
+
+    fn write_batch(&mut self) -> anyhow::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let games = std::mem::take(&mut self.pending);
+        let counts = |f: fn(&GameRecord) -> usize| -> ArrayRef {
+            Arc::new(UInt64Array::from(
+                games.iter().map(|g| f(g) as u64).collect::<Vec<_>>(),
+            ))
+        };
+        let names = |f: fn(&GameRecord) -> &str| -> ArrayRef {
+            Arc::new(StringArray::from(games.iter().map(f).collect::<Vec<_>>()))
+        };
+        let probs = |f: fn(&GameRecord) -> f64| -> ArrayRef {
+            Arc::new(Float64Array::from(games.iter().map(f).collect::<Vec<_>>()))
+        };
+        let columns = vec![
+            counts(|g| g.run),
+            counts(|g| g.round),
+            counts(|g| g.matchup),
+            names(|g| &g.team1),
+            names(|g| &g.team2),
+            probs(|g| g.win_prob),
+            probs(|g| g.draw),
+            names(|g| &g.winner),
+        ];
+        self.writer
+            .write(&RecordBatch::try_new(self.schema.clone(), columns)?)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> anyhow::Result<()> {
+        self.write_batch()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{season, year_url};
+
+/// Whether scrapes follow the women's tournament instead of the men's
+static WOMENS: AtomicBool = AtomicBool::new(false);
+
+/// Which of 538's two tournaments to follow
+#[derive(PartialEq, Eq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum Gender {
+    Mens,
+    Womens,
+}
+
+impl Gender {
+    pub const ALL: [Gender; 2] = [Gender::Mens, Gender::Womens];
+
+    /// How 538 tags the tournament's rows in its forecast data
+    pub fn key(self) -> &'static str {
+        match self {
+            Gender::Mens => "mens",
+            Gender::Womens => "womens",
+        }
+    }
+
+    /// The 538 page covering the tournament
+    pub fn url(self) -> String {
+        match self {
+            Gender::Mens => year_url(season::year()),
+            Gender::Womens => format!("{}womens/", year_url(season::year())),
+        }
+    }
+}
+
+impl fmt::Display for Gender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gender::Mens => write!(f, "men's"),
+            Gender::Womens => write!(f, "women's"),
+        }
+    }
+}
+
+pub fn set_gender(gender: Gender) {
+    WOMENS.store(gender == Gender::Womens, Ordering::Relaxed);
+}
+
+/// The tournament being scraped and simulated
+pub fn gender() -> Gender {
+    if WOMENS.load(Ordering::Relaxed) {
+        Gender::Womens
+    } else {
+        Gender::Mens
+    }
+}
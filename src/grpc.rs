@@ -0,0 +1,168 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tonic::{Request, Response, Status};
+
+use crate::probabilities::{ProbabilityMatrix, PROBABILITIES_PATH};
+use crate::season;
+use crate::session::Session;
+use crate::simulate::{simulate, SimulateOptions, BRACKET_PATH};
+use crate::summary::{SimulationSummary, SUMMARY_PATH};
+use crate::teams::{find_team, load_teams, normalize_name};
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+
+/// Types and service generated from proto/bracket.proto
+pub mod proto {
+    tonic::include_proto!("march_madness");
+}
+
+use proto::bracket_engine_server::{BracketEngine, BracketEngineServer};
+
+/// The engine behind the gRPC service. The driver is shared with the HTTP server, and the
+/// options sit behind a mutex since a request can change the runs and seed of its simulation.
+struct Engine {
+    driver: Arc<Mutex<Session>>,
+    options: Mutex<SimulateOptions>,
+}
+
+fn internal(e: anyhow::Error) -> Status {
+    Status::internal(format!("{:#}", e))
+}
+
+/// Serve the `BracketEngine` service of proto/bracket.proto over gRPC until Ctrl-C is pressed
+pub async fn serve(
+    driver: Arc<Mutex<Session>>,
+    options: SimulateOptions,
+    port: u16,
+) -> anyhow::Result<()> {
+    let engine = Engine {
+        driver,
+        options: Mutex::new(options),
+    };
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    log::info!("Serving gRPC on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(BracketEngineServer::new(engine))
+        .serve_with_shutdown(addr, async {
+            tokio::signal::ctrl_c().await.ok();
+            log::info!("Shutting down gRPC server");
+        })
+        .await?;
+    Ok(())
+}
+
+#[tonic::async_trait]
+impl BracketEngine for Engine {
+    async fn simulate(
+        &self,
+        request: Request<proto::SimulationRequest>,
+    ) -> Result<Response<proto::SimulationResponse>, Status> {
+        let request = request.into_inner();
+        if request.runs == Some(0) {
+            return Err(Status::invalid_argument("At least one run is required"));
+        }
+        let driver = self.driver.lock().await;
+        let mut options = self.options.lock().await;
+        let defaults = (options.runs, options.seed);
+        options.runs = request.runs.map_or(options.runs, |runs| runs as usize);
+        options.seed = request.seed.or(options.seed);
+        let res = simulate(&driver, &options).await;
+        (options.runs, options.seed) = defaults;
+        let (bracket, summary) = res.map_err(internal)?;
+        Ok(Response::new(proto::SimulationResponse {
+            bracket: Some(bracket.into()),
+            odds: summary
+                .wins
+                .keys()
+                .map(|team| team_odds(&summary, team))
+                .collect(),
+        }))
+    }
+
+    async fn get_bracket(
+        &self,
+        _: Request<proto::BracketRequest>,
+    ) -> Result<Response<proto::Tournament>, Status> {
+        let bracket = Tournament::load(season::data_path(BRACKET_PATH)).map_err(internal)?;
+        Ok(Response::new(bracket.into()))
+    }
+
+    async fn get_odds(
+        &self,
+        request: Request<proto::OddsRequest>,
+    ) -> Result<Response<proto::TeamOdds>, Status> {
+        let team = request.into_inner().team;
+        let summary = SimulationSummary::load(season::data_path(SUMMARY_PATH)).map_err(internal)?;
+        let target = normalize_name(&team);
+        let name = summary
+            .wins
+            .keys()
+            .find(|name| normalize_name(name) == target)
+            .ok_or_else(|| Status::not_found(format!("No simulated odds for {}", team)))?;
+        Ok(Response::new(team_odds(&summary, name)))
+    }
+
+    async fn get_matchup_odds(
+        &self,
+        request: Request<proto::MatchupRequest>,
+    ) -> Result<Response<proto::MatchupOdds>, Status> {
+        let request = request.into_inner();
+        let teams = load_teams().map_err(internal)?;
+        let no_team = |name: &str| Status::not_found(format!("No team named {}", name));
+        let team1 = find_team(&teams, &request.team1).ok_or_else(|| no_team(&request.team1))?;
+        let team2 = find_team(&teams, &request.team2).ok_or_else(|| no_team(&request.team2))?;
+        let (team1, team2) = (team1.name().to_string(), team2.name().to_string());
+        let matrix =
+            ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH)).map_err(internal)?;
+        let probability = matrix.get(&team1, &team2).ok_or_else(|| {
+            Status::not_found(format!(
+                "No scraped probability for {} vs {}, run scrape-probabilities",
+                team1, team2
+            ))
+        })?;
+        Ok(Response::new(proto::MatchupOdds {
+            team1,
+            team2,
+            probability,
+        }))
+    }
+}
+
+fn team_odds(summary: &SimulationSummary, team: &str) -> proto::TeamOdds {
+    proto::TeamOdds {
+        team: team.to_string(),
+        runs: summary.runs as u32,
+        win_probability: (1..=6)
+            .map(|round_num| summary.win_probability(team, round_num))
+            .collect(),
+    }
+}
+
+impl From<Tournament> for proto::Tournament {
+    fn from(bracket: Tournament) -> Self {
+        let mut matchups = vec![];
+        for round_num in 1..=bracket.field.round_count() {
+            for matchup in &bracket.rounds[&RoundKind::Round(round_num)].matchups {
+                let team = |ind| {
+                    Some(proto::Team {
+                        name: matchup.team(ind)?.to_string(),
+                        seed: matchup.seed(ind).map_or(0, |seed| seed.0 as u32),
+                    })
+                };
+                matchups.push(proto::Matchup {
+                    round: round_num as u32,
+                    index: matchup.index() as u32,
+                    team1: team(MatchupInd::Team1),
+                    team2: team(MatchupInd::Team2),
+                    winner: matchup.winning_team().map(str::to_string),
+                    win_probability: matchup.win_probability(),
+                });
+            }
+        }
+        Self {
+            matchups,
+            champion: bracket.champion().map(str::to_string),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+pub mod adjustments;
+pub mod analytic;
+pub mod audit;
+pub mod backtest;
+pub mod blend;
+pub mod cache;
+#[cfg(feature = "driver")]
+pub mod cassette;
+#[cfg(feature = "driver")]
+pub mod chromedriver;
+#[cfg(feature = "driver")]
+pub mod endpoint;
+pub mod engine;
+pub mod espn;
+pub mod evaluate;
+#[cfg(feature = "arrow-export")]
+pub mod export;
+pub mod field;
+pub mod forecast;
+pub mod games_log;
+pub mod gender;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "driver")]
+pub mod live;
+pub mod log_file;
+pub mod manifest;
+#[cfg(feature = "driver")]
+pub mod metrics;
+#[cfg(feature = "driver")]
+pub mod notify;
+pub mod odds;
+pub mod output;
+pub mod overrides;
+pub mod parse;
+#[cfg(feature = "driver")]
+pub mod payload;
+pub mod pick_export;
+pub mod pool;
+pub mod portfolio;
+#[cfg(feature = "driver")]
+pub mod preflight;
+pub mod probabilities;
+pub mod profile;
+pub mod public_picks;
+pub mod ratings;
+pub mod report;
+pub mod results;
+pub mod rng;
+pub mod scoring;
+pub mod season;
+pub mod second_chance;
+pub mod seed_history;
+pub mod selectors;
+pub mod series;
+#[cfg(feature = "driver")]
+pub mod server;
+#[cfg(feature = "driver")]
+pub mod session;
+#[cfg(feature = "driver")]
+pub mod simulate;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod strategy;
+#[cfg(feature = "driver")]
+pub mod submit;
+pub mod summary;
+pub mod teams;
+#[cfg(feature = "driver")]
+pub mod throttle;
+pub mod tiebreaker;
+pub mod timeouts;
+pub mod timings;
+pub mod tournament;
+
+/// 538's page for the men's tournament, with the women's under it
+pub const URL: &str = "https://projects.fivethirtyeight.com/2022-march-madness-predictions/";
+
+/// 538's page for the men's tournament of the given year, laid out like [`URL`]
+pub fn year_url(year: u32) -> String {
+    format!(
+        "https://projects.fivethirtyeight.com/{}-march-madness-predictions/",
+        year
+    )
+}
+
+/// Environment variable pointing scrapes at another copy of the 538 page, such as a saved
+/// page served locally by the integration tests
+pub const URL_VAR: &str = "MARCH_MADNESS_URL";
+
+/// Page to scrape: the current tournament's page, unless overridden by `URL_VAR`
+pub fn page_url() -> String {
+    std::env::var(URL_VAR).unwrap_or_else(|_| gender::gender().url())
+}
+
+/// Year of the tournament covered by `URL`
+pub const YEAR: u32 = 2022;
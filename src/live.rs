@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use scraper::{Html, Selector};
+use serde::Serialize;
+
+use crate::parse::parse_win_probability;
+use crate::selectors::selectors;
+use crate::session::Session;
+use crate::teams::{find_team, Team};
+use crate::{cache, preflight, throttle};
+
+/// Cache key for the HTML of the live games
+const LIVE_CACHE_KEY: &str = "live_games";
+
+/// A game in progress with the live chance of its first team winning
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct LiveGame {
+    pub teams: [String; 2],
+    pub win_prob: f64,
+}
+
+/// A large move in a live game's win probability since it was last reported
+#[derive(PartialEq, Debug, Clone)]
+pub struct Swing {
+    pub teams: [String; 2],
+    /// The first team's chance when last reported
+    pub from: f64,
+    pub to: f64,
+}
+
+impl fmt::Display for Swing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} swung from {:.1}% to {:.1}% against {}",
+            self.teams[0],
+            self.from * 100.,
+            self.to * 100.,
+            self.teams[1]
+        )
+    }
+}
+
+/// Load the 538 page and read the games in progress, none outside of game days. Reuses the
+/// cached games while they're fresh, so the cache TTL should be kept below the polling
+/// interval.
+pub async fn live_games(driver: &Session, teams: &[Team]) -> anyhow::Result<Vec<LiveGame>> {
+    let html = match cache::get(LIVE_CACHE_KEY) {
+        Some(html) => html,
+        None => {
+            throttle::rate_limit().await;
+            preflight::load_page(driver).await?;
+            let html = driver.page_source().await?;
+            cache::put(LIVE_CACHE_KEY, &html);
+            html
+        }
+    };
+    parse_live_games(&html, teams)
+}
+
+/// Read the live games from the page's HTML. Games whose teams aren't in the teams file, or
+/// which show no win probability yet, are skipped with a warning.
+pub fn parse_live_games(html: &str, teams: &[Team]) -> anyhow::Result<Vec<LiveGame>> {
+    let parsed = Html::parse_document(html);
+    let selectors = selectors();
+    let game_sel = Selector::parse(&selectors.live_game).unwrap();
+    let team_sel = Selector::parse(&selectors.live_team).unwrap();
+    let prob_sel = Selector::parse(&selectors.live_win_prob).unwrap();
+    let mut games = vec![];
+    for game in parsed.select(&game_sel) {
+        let names: Vec<String> = game
+            .select(&team_sel)
+            .map(|name| name.text().collect::<String>().trim().to_string())
+            .collect();
+        let (name1, name2) = match names.as_slice() {
+            [name1, name2] => (name1, name2),
+            _ => {
+                log::warn!("Skipping a live game with {} teams", names.len());
+                continue;
+            }
+        };
+        let (team1, team2) = match (find_team(teams, name1), find_team(teams, name2)) {
+            (Some(team1), Some(team2)) => (team1, team2),
+            _ => {
+                log::warn!(
+                    "Skipping live game {} vs {}, not in the teams file",
+                    name1,
+                    name2
+                );
+                continue;
+            }
+        };
+        // The chances are shown in the same order as the teams, so the first is team1's
+        let label = match game.select(&prob_sel).next() {
+            Some(label) => label,
+            None => {
+                log::warn!(
+                    "Skipping live game {} vs {}, no win probability shown",
+                    name1,
+                    name2
+                );
+                continue;
+            }
+        };
+        let win_prob = parse_win_probability(label.text().collect::<String>().trim())?;
+        games.push(LiveGame {
+            teams: [team1.name().to_string(), team2.name().to_string()],
+            win_prob,
+        });
+    }
+    Ok(games)
+}
+
+/// Keeps each live game's win probability as of the last time it was reported, to find
+/// swings since then
+#[derive(Debug, Default)]
+pub struct SwingTracker {
+    reported: BTreeMap<[String; 2], f64>,
+}
+
+impl SwingTracker {
+    /// Swings of at least `threshold` in the latest check of the live games. A game's first
+    /// check sets where it starts from, and after a swing it's measured from the new
+    /// probability, so a steady drift is reported once per `threshold`. Games no longer in
+    /// progress are forgotten.
+    pub fn update(&mut self, games: &[LiveGame], threshold: f64) -> Vec<Swing> {
+        self.reported
+            .retain(|teams, _| games.iter().any(|game| &game.teams == teams));
+        let mut swings = vec![];
+        for game in games {
+            let from = *self
+                .reported
+                .entry(game.teams.clone())
+                .or_insert(game.win_prob);
+            if (game.win_prob - from).abs() >= threshold {
+                swings.push(Swing {
+                    teams: game.teams.clone(),
+                    from,
+                    to: game.win_prob,
+                });
+                self.reported.insert(game.teams.clone(), game.win_prob);
+            }
+        }
+        swings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tournament::Region;
+
+    fn game(win_prob: f64) -> LiveGame {
+        LiveGame {
+            teams: ["Gonzaga".to_string(), "Memphis".to_string()],
+            win_prob,
+        }
+    }
+
+    #[test]
+    fn reports_swings_from_the_last_reported_probability() {
+        let mut tracker = SwingTracker::default();
+        assert!(tracker.update(&[game(0.8)], 0.2).is_empty());
+        assert!(tracker.update(&[game(0.65)], 0.2).is_empty());
+        let swings = tracker.update(&[game(0.55)], 0.2);
+        assert_eq!(swings.len(), 1);
+        assert_eq!((swings[0].from, swings[0].to), (0.8, 0.55));
+        assert!(tracker.update(&[game(0.4)], 0.2).is_empty());
+        // Once a game is over, seeing it again starts afresh
+        assert!(tracker.update(&[], 0.2).is_empty());
+        assert!(tracker.update(&[game(0.9)], 0.2).is_empty());
+    }
+
+    #[test]
+    fn parses_live_games_skipping_unknown_teams_and_missing_chances() {
+        let teams: Vec<Team> = [("Gonzaga", 1), ("Memphis", 9), ("Baylor", 1)]
+            .iter()
+            .map(|(name, seed)| {
+                serde_json::from_value(json!({"name": name, "region": Region::West, "seed": seed}))
+                    .unwrap()
+            })
+            .collect();
+        let games =
+            parse_live_games(include_str!("../tests/fixtures/live_games.html"), &teams).unwrap();
+        assert_eq!(
+            games,
+            vec![LiveGame {
+                teams: ["Gonzaga".to_string(), "Memphis".to_string()],
+                win_prob: 0.62,
+            }]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+/// A log file which is rotated once it grows past a size: `run.log` moves to `run.log.1`,
+/// `run.log.1` to `run.log.2` and so on, dropping the oldest beyond the number kept
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    /// Bytes in the current file
+    len: u64,
+    max_bytes: u64,
+    /// Rotated files kept besides the current one
+    keep: usize,
+}
+
+impl RotatingFile {
+    /// Open the log file, appending to what an earlier run left in it
+    pub fn open(path: &Path, max_bytes: u64, keep: usize) -> anyhow::Result<Self> {
+        let file =
+            append(path).with_context(|| format!("Could not open log file {}", path.display()))?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            len,
+            max_bytes,
+            keep,
+        })
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    /// Move each file along by one and start an empty one
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for n in (1..self.keep).rev() {
+                let from = self.rotated(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated(1))?;
+            self.file = append(&self.path)?;
+        }
+        self.len = 0;
+        Ok(())
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Each log line comes in one write, so lines are never split between files
+        if self.len > 0 && self.len + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Log output written to stderr as usual and mirrored to a log file. A failed write to the
+/// file doesn't stop the line reaching stderr.
+pub struct Mirrored(pub RotatingFile);
+
+impl Write for Mirrored {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stderr().write_all(buf)?;
+        self.0.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()?;
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_past_the_size_and_keeps_the_newest_files() {
+        let dir = std::env::temp_dir().join(format!("march-madness-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.log");
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "fourth\n");
+        assert_eq!(read(file.rotated(1)), "third\n");
+        assert_eq!(read(file.rotated(2)), "second\n");
+        assert!(!file.rotated(3).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,49 +1,2926 @@
-mod simulate;
-mod teams;
-mod tournament;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{env, fs, slice};
 
-use clap::Parser;
-use thirtyfour::{DesiredCapabilities, WebDriver};
+use anyhow::{anyhow, Context};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use march_madness::adjustments::{Adjustments, Boost};
+use march_madness::analytic::{RoundProbabilities, MOST_LIKELY_PATH};
+use march_madness::audit::AuditEntry;
+use march_madness::blend::SourceWeights;
+use march_madness::cassette::Cassette;
+use march_madness::chromedriver::ChromeDriver;
+use march_madness::endpoint::{self, BrowserOptions, DriverKind};
+use march_madness::engine::Condition;
+use march_madness::evaluate::{Evaluation, Predictions};
+use march_madness::games_log::GamesFormat;
+#[cfg(feature = "sqlite")]
+use march_madness::games_log::{self, GAMES_PATH};
+use march_madness::gender::{self, Gender};
+#[cfg(feature = "grpc")]
+use march_madness::grpc;
+use march_madness::live::{self, SwingTracker};
+use march_madness::log_file::{Mirrored, RotatingFile};
+use march_madness::manifest::{self, MANIFEST_FILE};
+use march_madness::odds::{Futures, Odds};
+use march_madness::output::{self, OutputFormat, ResultMarkers};
+use march_madness::overrides::MatchupOverrides;
+use march_madness::pick_export::{self, PickFormat};
+use march_madness::probabilities::{ProbabilityMatrix, PROBABILITIES_PATH};
+use march_madness::profile::{self, Profile};
+use march_madness::public_picks::{self, PublicPicks, PUBLIC_PICKS_PATH};
+use march_madness::ratings::{self, RatingFormula};
+use march_madness::report::CalcuttaPayouts;
+use march_madness::results::{self, RESULTS_HISTORY_PATH};
+use march_madness::rng::{RngKind, Sampler, Sampling};
+use march_madness::scoring::ScoringSystem;
+use march_madness::second_chance::{self, SECOND_CHANCE_BRACKET_PATH};
+use march_madness::seed_history::{SeedHistory, SeedPrior};
+use march_madness::session::Session;
+use march_madness::simulate::{SimulateOptions, BRACKET_PATH};
+#[cfg(feature = "sqlite")]
+use march_madness::store::Store;
+use march_madness::strategy::Strategy;
+use march_madness::submit::PoolSite;
+use march_madness::summary::{SimulationSummary, SUMMARY_PATH};
+use march_madness::teams::{find_team, load_teams, scrape_round_chances, write_teams, Team};
+use march_madness::tiebreaker::{self, Efficiencies};
+use march_madness::tournament::{Region, RoundKind, Tournament};
+use march_madness::{
+    analytic, audit, backtest, cache, engine, espn, evaluate, forecast, notify, payload, pool,
+    portfolio, report, scoring, season, selectors, server, simulate, strategy, submit, throttle,
+    timeouts, timings,
+};
+use serde_json::json;
+use thirtyfour::error::WebDriverError;
+use tokio::sync::Mutex;
 
-use crate::teams::write_teams;
+/// How many possible next opponents team-info lists
+const NEXT_OPPONENTS_SHOWN: usize = 3;
 
-const SELENIUM_SERVER_URL: &str = "http://localhost:4444/wd/hub";
-const URL: &str = "https://projects.fivethirtyeight.com/2022-march-madness-predictions/";
+/// How many possible opponents title-path lists in each round
+const PATH_OPPONENTS_SHOWN: usize = 5;
+
+/// Prints per round and overall evaluations, for evaluate or madness
+type EvaluationReport = fn(&[(RoundKind, Evaluation)], &Evaluation);
 
 /// What task to run
-#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
-pub enum Task {
+#[derive(Subcommand)]
+enum Task {
     /// Write out team information using 538 names (for using later within
     /// CSS selectors)
     WriteTeamsTable,
-    /// Simulate the tournament using 538 predictions  
-    Simulate,
+    /// Simulate the tournament using 538 predictions, or historical seed matchups alone with
+    /// --model seeds
+    Simulate(SimulateArgs),
+    /// Simulate a second chance bracket of the 16 teams left once the Sweet 16 is set, saved
+    /// apart from the full bracket and scored with the pool's own points per round
+    SecondChance(SecondChanceArgs),
+    /// Convert an ESPN Tournament Challenge group export into bracket files
+    ImportEspn(ImportEspnArgs),
+    /// Enter a saved bracket into a bracket pool site
+    #[clap(alias = "submit-espn")]
+    SubmitBracket(SubmitArgs),
+    /// Write a saved bracket's picks in a format pool sites import, to enter them by hand
+    /// instead of through the browser
+    ExportPicks(ExportPicksArgs),
+    /// Compare simulated Final Four and title chances against sportsbook futures
+    FuturesReport(FuturesArgs),
+    /// Suggest single bets and parlays on upcoming games which the simulations give better
+    /// chances than the sportsbook's moneylines
+    Parlays(ParlayArgs),
+    /// Size bets on upcoming games by the Kelly criterion, from the simulations' chances and
+    /// the sportsbook's moneylines
+    Kelly(KellyArgs),
+    /// Serve simulations and odds over HTTP, keeping the browser open between requests
+    Serve(ServeArgs),
+    /// Score a directory of brackets against the real results so far
+    Leaderboard(LeaderboardArgs),
+    /// Play out the rest of the tournament --runs times from scraped probabilities and report
+    /// each bracket in --brackets-dir's chances of finishing in the top three
+    PoolOdds(PoolOddsArgs),
+    /// Show the most likely path to the title for a team
+    ChampionPath(TeamArgs),
+    /// Break down a team's way to the title round by round: its chance of reaching each
+    /// round, who it could meet there and its chance of beating each
+    TitlePath(TeamArgs),
+    /// Simulate the rest of the tournament assuming a team wins a given round, and show how
+    /// every other team's chances shift
+    Conditional(ConditionalArgs),
+    /// Find the swing games of the bracket: nudge each game's probability either way and show
+    /// which move the contenders' title chances the most
+    Sensitivity(SensitivityArgs),
+    /// Sample many brackets from scraped probabilities, with a manifest of the seed each was
+    /// drawn with so any of them can be drawn again exactly
+    GenerateBrackets(GenerateBracketsArgs),
+    /// Scrape head-to-head probabilities for every pair of teams which can still meet
+    ScrapeProbabilities(ScrapeArgs),
+    /// Read each team's chance of reaching every round from the 538 teams table, without
+    /// hovering, and save them to forecast_table.json
+    ScrapeForecastTable,
+    /// Check the scraped hover probabilities against the teams table, flagging each team's
+    /// chance of reaching a round where the two disagree
+    Calibration(CalibrationArgs),
+    /// Compute each team's exact chance of reaching each round from scraped probabilities
+    Advancement(AdvancementArgs),
+    /// Find the single most likely complete bracket from scraped probabilities
+    MostLikely,
+    /// Fill in a bracket from scraped probabilities with --strategy, saved as the bracket to
+    /// submit
+    BuildBracket(BuildBracketArgs),
+    /// List where a bracket goes against the most likely bracket from scraped probabilities
+    Stands(StandsArgs),
+    /// Report the chance of one team beating another if they were to meet
+    Odds(OddsArgs),
+    /// Play one matchup --runs times with its chance under --model, and report how often each
+    /// team won with a 95% confidence interval
+    H2h(H2hArgs),
+    /// Show a team's seed, status, chances in each remaining round and likely next opponents
+    TeamInfo(TeamArgs),
+    /// List teams with their current status, narrowed down by the team filters
+    ListTeams(ListTeamsArgs),
+    /// Print an empty bracket with seeds from the teams table, ready to fill in
+    BlankBracket,
+    /// Value teams for a Calcutta auction from the last simulation summary
+    Calcutta(CalcuttaArgs),
+    /// List the double-digit seeds which reached the Sweet 16 or further in the simulated
+    /// bracket, or in the real results with --actual, with their chance of each upset
+    Cinderellas(CinderellaArgs),
+    /// Compare how chalky a bracket, or every bracket in --brackets-dir, is
+    ChalkScore(ChalkScoreArgs),
+    /// Compare a bracket with a consensus bracket
+    Similarity(SimilarityArgs),
+    /// Scrape how often ESPN entries picked each team in each round
+    ScrapePublicPicks,
+    /// Find the picks most underrated by the public, comparing 538's chances with the scraped
+    /// public picks
+    Leverage(LeverageArgs),
+    /// Build --entries brackets for a pool with different champions and varied Final Fours,
+    /// saved to --brackets-dir
+    Portfolio(PortfolioArgs),
+    /// Score brackets built from past seasons' probabilities against what really happened,
+    /// for every season in --history-dir
+    Backtest(BacktestArgs),
+    /// Score --predictions against the results in --input, or on the 538 page, with Brier
+    /// score and log loss per round
+    Evaluate(EvaluateArgs),
+    /// Total up how surprising the results in --input, or on the 538 page, were under
+    /// --predictions, round by round
+    Madness(EvaluateArgs),
+    /// Append any new real results from the 538 page to the results history with the time
+    /// they were first seen
+    RecordResults,
+    /// Keep running, and every --every-hours record new results, scrape fresh probabilities
+    /// and print advancement chances, until interrupted
+    Daemon(DaemonArgs),
+    /// List every bracket node id on the 538 page with the team and round read from it,
+    /// flagging ids which can't be read, to diagnose changes to the page
+    NodeIds,
+    /// During game days, keep checking the live win probabilities of games in progress and
+    /// warn, and post to --webhook-url, when one swings by at least --swing points, until
+    /// interrupted
+    Live(LiveArgs),
+}
+
+/// When to color output
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+/// Where round by round probabilities come from
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum Source {
+    /// Head-to-head probabilities scraped by hovering on the 538 page
+    Hover,
+    /// 538's published forecast CSV, downloaded unless --input points to a copy
+    Csv,
 }
 
+/// What simulate draws games from
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum Model {
+    /// 538's predictions on the page, blended with any other prediction sources
+    #[clap(name = "538")]
+    FiveThirtyEight,
+    /// Historical win rates between seeds alone, for the seeds saved by write-teams-table,
+    /// without loading the page. A baseline for other models
+    Seeds,
+}
+
+/// How a bracket is printed as text
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum BracketDisplay {
+    /// Only the games the worse seed won, round by round
+    Upsets,
+    /// Every game, round by round across the whole bracket
+    Rounds,
+    /// Every game of each region in turn up to its winner, then the Final Four and
+    /// championship
+    Regions,
+}
+
+/// How scrape-probabilities gets head-to-head probabilities
+#[derive(PartialEq, Debug, Clone)]
+pub enum ScrapeSource {
+    /// Hover over every pair of teams on the 538 page
+    Hover,
+    /// Team ratings embedded in the 538 page, turned into head-to-head probabilities in one
+    /// page load
+    Payload,
+    /// Power ratings saved by write-teams-table, turned into head-to-head probabilities
+    /// without loading the page
+    Ratings,
+    /// Ratings of any model from a `team,rating` CSV, turned into head-to-head probabilities
+    /// with a --rating-formula
+    RatingsFile(PathBuf),
+    /// Historical win rates between seeds, for the seeds saved by write-teams-table
+    Seeds,
+}
+
+impl FromStr for ScrapeSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hover" => Ok(ScrapeSource::Hover),
+            "payload" => Ok(ScrapeSource::Payload),
+            "ratings" => Ok(ScrapeSource::Ratings),
+            "seeds" => Ok(ScrapeSource::Seeds),
+            _ => match s.strip_prefix("ratings:") {
+                Some(path) if !path.is_empty() => Ok(ScrapeSource::RatingsFile(path.into())),
+                _ => Err(anyhow!(
+                    "Expected hover, payload, ratings, ratings:<file> or seeds, got {}",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Fill in, simulate and follow March Madness brackets with 538's predictions
 #[derive(Parser)]
+#[clap(args_override_self = true)]
 struct Opts {
-    #[clap(arg_enum)]
+    #[clap(subcommand)]
     task: Task,
+    #[clap(flatten)]
+    global: GlobalOpts,
+}
+
+/// Options for every task, mostly about driving the browser
+#[derive(Args)]
+struct GlobalOpts {
+    /// Whether to color winners and losers in bracket output
+    #[clap(long, arg_enum, default_value = "auto", global = true)]
+    color: ColorChoice,
+    /// How to tell winners from losers in printed brackets. Symbols mark them with ✓ and ✗ and
+    /// bold or strike them through, for when red and green are hard to tell apart
+    #[clap(long, arg_enum, default_value = "color", global = true)]
+    result_markers: ResultMarkers,
+    /// Print results as text, or as one JSON document per task run for scripts. Only
+    /// warnings and errors are logged, to stderr, with JSON
+    #[clap(long, arg_enum, default_value = "text", global = true)]
+    output: OutputFormat,
+    /// Download and launch a chromedriver matching the installed Chrome instead of connecting
+    /// to an already running WebDriver server
+    #[clap(long, global = true)]
+    manage_driver: bool,
+    /// Kind of WebDriver server to connect to, which decides the URL path and browser used
+    #[clap(long, arg_enum, default_value = "selenium", global = true)]
+    driver: DriverKind,
+    /// Address of the WebDriver server, defaulting to where the chosen kind usually listens
+    #[clap(long, global = true)]
+    driver_endpoint: Option<String>,
+    /// Argument to start the browser with, e.g. --chrome-arg=--window-size=1920,1080. Can be
+    /// given more than once, and is passed to Firefox as well when using geckodriver
+    #[clap(
+        long,
+        multiple_occurrences = true,
+        allow_hyphen_values = true,
+        global = true
+    )]
+    chrome_arg: Vec<String>,
+    /// JSON file of extra WebDriver capabilities to request, e.g. goog:chromeOptions
+    #[clap(long, global = true)]
+    capabilities: Option<PathBuf>,
+    /// Leave the browser open after the task and pick it up again on the next run with this
+    /// flag, instead of starting a fresh session each time. Not available with --manage-driver
+    #[clap(long, global = true)]
+    reuse_session: bool,
+    /// Write every WebDriver command of the run and the server's response to this cassette
+    /// file, to replay the run later without a browser
+    #[clap(long, global = true, conflicts_with_all = &["replay", "reuse-session"])]
+    record: Option<PathBuf>,
+    /// Answer WebDriver commands from a cassette written by --record instead of a server.
+    /// With the same task, options and --seed the run goes exactly as the recorded one did
+    #[clap(long, global = true, conflicts_with_all = &["manage-driver", "reuse-session"])]
+    replay: Option<PathBuf>,
+    /// Pause after each click or hover on a page, in milliseconds
+    #[clap(long, default_value = "0", global = true)]
+    action_delay_ms: u64,
+    /// Limit on WebDriver calls per second, to go easy on 538 during long scrapes
+    #[clap(long, global = true)]
+    max_calls_per_sec: Option<f64>,
+    /// How long the WebDriver itself waits for elements before reporting them missing, in
+    /// milliseconds
+    #[clap(long, default_value = "0", global = true)]
+    implicit_wait_ms: u64,
+    /// JSON file of the selectors to find things on the 538 page with, for following changes
+    /// to its markup without a new build. Start from a copy of data/selectors.json
+    #[clap(long, global = true)]
+    selectors: Option<PathBuf>,
+    /// How long to keep polling for elements on the 538 page, which can be slow to render
+    #[clap(long, default_value = "5000", global = true)]
+    element_timeout_ms: u64,
+    /// How long to wait after hovering a team for its win percentage to render, the element
+    /// timeout unless given
+    #[clap(long, global = true)]
+    hover_timeout_ms: Option<u64>,
+    /// How often to check whether a hovered team's win percentage has rendered
+    #[clap(long, default_value = "250", global = true)]
+    hover_poll_ms: u64,
+    /// Reuse the bracket and teams table scraped within this many seconds instead of loading
+    /// the page again, for tasks which only read them. 0 turns the cache off
+    #[clap(long, default_value = "0", global = true)]
+    cache_ttl_secs: u64,
+    /// Tournament to scrape and simulate, the men's unless given
+    #[clap(long, arg_enum, global = true)]
+    gender: Option<Gender>,
+    /// Year of the tournament to scrape and simulate. Given this or --gender, data files are
+    /// named for the year and tournament, e.g. teams-2023-mens.json, so that files for other
+    /// tournaments in the same directory are never mixed in
+    #[clap(long, global = true)]
+    year: Option<u32>,
+    /// Run the task for the men's and then the women's tournament on one browser session,
+    /// writing each one's files in a mens/ or womens/ directory. Paths given to the task are
+    /// read from within each directory too, so inputs such as odds can differ between them
+    #[clap(long, global = true, conflicts_with_all = &["gender", "watch"])]
+    all: bool,
+    /// Run the task in profiles/<name>/, keeping the entry's saved brackets and other files
+    /// apart from other profiles'. Paths given to the task are read from there too. Its
+    /// profile.json holds options to run tasks with unless given, such as a --strategy, under
+    /// "global" for every task and under "tasks" by task name
+    #[clap(long, global = true)]
+    profile: Option<String>,
+    /// Also write everything logged, including each simulated game, to this file, which is
+    /// rotated as it grows so that long watch runs don't fill the disk
+    #[clap(long, global = true)]
+    log_file: Option<PathBuf>,
+    /// Size in megabytes at which the log file is rotated
+    #[clap(long, default_value = "10", global = true)]
+    log_file_max_mb: u64,
+    /// How many rotated log files to keep besides the current one
+    #[clap(long, default_value = "5", global = true)]
+    log_files_kept: usize,
+    /// Record every probability scraped or computed in this file as JSON lines, with its
+    /// source, the text it was read from and any changes made to it before use
+    #[clap(long, global = true)]
+    audit_log: Option<PathBuf>,
+    /// Also write the time spent loading the 538 page, reading the teams table, hovering and
+    /// clicking, which is logged at the end of the run, to this JSON file
+    #[clap(long, global = true)]
+    timings_file: Option<PathBuf>,
+    /// Keep polling the 538 page and rerun the task whenever new real results are posted
+    #[clap(long, global = true)]
+    watch: bool,
+    /// How often to check for new results in watch mode, in seconds
+    #[clap(long, default_value = "300", global = true)]
+    watch_interval_secs: u64,
+    /// Port to serve Prometheus metrics on at /metrics while watching or running the daemon.
+    /// The serve task has them on its own port.
+    #[clap(long, global = true)]
+    metrics_port: Option<u16>,
+    /// SQLite database to also collect the teams, probabilities, simulated games and real
+    /// results written by tasks in, e.g. march_madness.sqlite
+    #[cfg(feature = "sqlite")]
+    #[clap(long, global = true, conflicts_with = "all")]
+    store: Option<PathBuf>,
+}
+
+/// Prediction sources for simulated games
+#[derive(Args)]
+struct PredictionArgs {
+    /// CSV of sportsbook moneylines to use for the games it covers
+    #[clap(long)]
+    odds: Option<PathBuf>,
+    /// Relative weight of each prediction source (538, kenpom from --efficiency, odds and
+    /// seeds from historical seed matchups) when blending them, e.g. 538=2,kenpom=1,seeds=1.
+    /// Sources without a probability for a game are skipped, falling back to 538 alone.
+    #[clap(long, default_value = "odds=1")]
+    weights: SourceWeights,
+    /// Deprecated, use --weights. Weight of the moneyline odds when blending with 538 (1 uses
+    /// only the odds), the same as --weights 538=<1 - weight>,odds=<weight>
+    #[clap(long, conflicts_with = "weights")]
+    odds_weight: Option<f64>,
+    /// File of explicit game probabilities taking precedence over everything else, one per
+    /// line like `Gonzaga vs Memphis in round 2 => 0.55`
+    #[clap(long)]
+    overrides: Option<PathBuf>,
+    /// CSV of per-team adjustments in points per game (team,points), e.g. for injuries,
+    /// applied on top of 538 and the odds
+    #[clap(long)]
+    adjustments: Option<PathBuf>,
+    /// Add percentage points to a team's chance of winning each of its games, e.g.
+    /// --boost "Duke:+5" or --boost "Kentucky:-10". Can be given more than once
+    #[clap(long, multiple_occurrences = true, allow_hyphen_values = true)]
+    boost: Vec<Boost>,
+    /// CSV of team tempo and efficiency ratings (team,tempo,offense,defense) to predict the
+    /// title game score and, when weighted, games from
+    #[clap(long)]
+    efficiency: Option<PathBuf>,
+}
+
+/// How simulated games are drawn
+#[derive(Args)]
+struct SamplingArgs {
+    /// Number of times to simulate the tournament
+    #[clap(long, default_value = "1")]
+    runs: usize,
+    /// Random number generator deciding simulated games
+    #[clap(long, arg_enum, default_value = "std")]
+    rng: RngKind,
+    /// Seed for the random number generator, so simulations can be repeated exactly
+    #[clap(long)]
+    seed: Option<u64>,
+    /// Whether runs draw independently, in mirrored antithetic pairs or stratified across all
+    /// the runs, the last two of which settle averages over runs in fewer of them
+    #[clap(long, arg_enum, default_value = "independent")]
+    sampling: Sampling,
+}
+
+impl SamplingArgs {
+    fn sampler(&self) -> Sampler {
+        Sampler::new(self.rng, self.seed, self.sampling, self.runs)
+    }
+}
+
+#[derive(Args)]
+struct ScoringArgs {
+    /// Points for a correct pick in each round, comma separated
+    #[clap(
+        long,
+        use_value_delimiter = true,
+        default_value = "10,20,40,80,160,320"
+    )]
+    round_points: Vec<u32>,
+}
+
+impl ScoringArgs {
+    fn scoring(&self) -> anyhow::Result<ScoringSystem> {
+        ScoringSystem::from_points(&self.round_points)
+    }
+}
+
+#[derive(Args)]
+struct SimulateArgs {
+    /// Where game probabilities come from
+    #[clap(long, arg_enum, default_value = "538")]
+    model: Model,
+    #[clap(flatten)]
+    predictions: PredictionArgs,
+    #[clap(flatten)]
+    sampling: SamplingArgs,
+    /// Scale game probabilities on the logit scale before simulating, above 1 to make
+    /// favorites even stronger than 538 thinks and below 1 for more upsets
+    #[clap(long, default_value = "1")]
+    sharpen: f64,
+    /// First round to simulate (3 is the Sweet 16), keeping results and the picks from
+    /// --picks for earlier rounds
+    #[clap(long, default_value = "1")]
+    start_round: usize,
+    /// Bracket of existing picks to simulate on from with --start-round
+    #[clap(long)]
+    picks: Option<PathBuf>,
+    /// Last round to simulate and click, e.g. 2 to only fill in the first weekend. Later
+    /// rounds are left empty for another run with --start-round or for picking by hand
+    #[clap(long, default_value = "6")]
+    through_round: usize,
+    /// Also write the simulated bracket as a Markdown document to this file
+    #[clap(long)]
+    markdown_out: Option<PathBuf>,
+    /// Slack or Discord webhook to post the simulated bracket to
+    #[clap(long)]
+    webhook_url: Option<String>,
+    /// File format for the log of every simulated game. Only CSV logs are collected by --store
+    #[clap(long, arg_enum, default_value = "csv")]
+    games_format: GamesFormat,
+    /// Also print every game on stdout as a line of JSON as soon as it is drawn, with the
+    /// same fields as the games log, for following long runs as they go
+    #[clap(long)]
+    stream_games: bool,
+    /// Keep the picks in the bracket saved by the last simulation instead of drawing and
+    /// clicking those games again, so a run which stopped partway can be picked up safely
+    #[clap(long)]
+    resume: bool,
+    /// When a game's win percentage can't be read or its pick can't be clicked in, pick it
+    /// from the other prediction sources or the seeds and carry on, listing every such game
+    /// at the end, instead of stopping the run
+    #[clap(long)]
+    best_effort: bool,
+    /// How to print the simulated bracket
+    #[clap(long, arg_enum, default_value = "upsets")]
+    display: BracketDisplay,
+}
+
+#[derive(Args)]
+struct SecondChanceArgs {
+    #[clap(flatten)]
+    predictions: PredictionArgs,
+    #[clap(flatten)]
+    sampling: SamplingArgs,
+    /// Scale game probabilities on the logit scale before simulating, see simulate
+    #[clap(long, default_value = "1")]
+    sharpen: f64,
+    /// Points for a correct pick in the Sweet 16, Elite Eight, Final Four and title game,
+    /// comma separated
+    #[clap(long, use_value_delimiter = true, default_value = "10,20,40,80")]
+    round_points: Vec<u32>,
+    /// Also enter the picks on this pool site, only clicking the rounds from the Sweet 16 on
+    #[clap(long, arg_enum)]
+    submit: Option<PoolSite>,
+    /// File format for the log of every simulated game. Only CSV logs are collected by --store
+    #[clap(long, arg_enum, default_value = "csv")]
+    games_format: GamesFormat,
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    #[clap(flatten)]
+    simulate: SimulateArgs,
+    /// Port to listen on
+    #[clap(long, default_value = "3000")]
+    port: u16,
+    /// Also serve the simulation engine over gRPC on this port, as defined in
+    /// proto/bracket.proto, sharing the browser session with the HTTP endpoints
+    #[cfg(feature = "grpc")]
+    #[clap(long)]
+    grpc_port: Option<u16>,
+}
+
+#[derive(Args)]
+struct ImportEspnArgs {
+    /// The ESPN group export
+    #[clap(long)]
+    input: PathBuf,
+    /// Directory to write a bracket file per entry to
+    #[clap(long, default_value = "brackets")]
+    brackets_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct SubmitArgs {
+    /// Bracket to submit, the last simulated or built bracket unless given
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+    /// Which site to submit the bracket to
+    #[clap(long, arg_enum, default_value = "espn")]
+    pool_site: PoolSite,
+    /// First round to pick, e.g. 3 to enter a second chance bracket from the Sweet 16 on
+    #[clap(long, default_value = "1")]
+    first_round: usize,
+}
+
+#[derive(Args)]
+struct ExportPicksArgs {
+    /// Bracket to export, the last simulated or built bracket unless given
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+    #[clap(long, arg_enum, default_value = "espn-csv")]
+    format: PickFormat,
+    /// Entry name for formats which hold one
+    #[clap(long, default_value = "538 simulation")]
+    entry: String,
+    /// File to write the picks to, printing them to paste in if not given
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct FuturesArgs {
+    /// CSV of futures odds to compare against
+    #[clap(long)]
+    input: PathBuf,
+}
+
+#[derive(Args)]
+struct ParlayArgs {
+    /// CSV of moneylines for the upcoming games, with a team1,team2,moneyline1,moneyline2
+    /// header
+    #[clap(long)]
+    input: PathBuf,
+    /// Most games to combine in a parlay
+    #[clap(long, default_value = "3")]
+    max_legs: usize,
+}
+
+#[derive(Args)]
+struct KellyArgs {
+    /// CSV of moneylines for the upcoming games, with a team1,team2,moneyline1,moneyline2
+    /// header
+    #[clap(long)]
+    input: PathBuf,
+    /// Fraction of the full Kelly stake to bet, e.g. 0.5 for half Kelly
+    #[clap(long, default_value = "0.25")]
+    fraction: f64,
+    /// Bankroll to size the stakes out of, to show amounts as well as shares
+    #[clap(long)]
+    bankroll: Option<f64>,
+}
+
+#[derive(Args)]
+struct LeaderboardArgs {
+    #[clap(flatten)]
+    scoring: ScoringArgs,
+    /// Directory of the pool's bracket files
+    #[clap(long, default_value = "brackets")]
+    brackets_dir: PathBuf,
+    /// Slack or Discord webhook to post the standings to
+    #[clap(long)]
+    webhook_url: Option<String>,
+}
+
+#[derive(Args)]
+struct PoolOddsArgs {
+    #[clap(flatten)]
+    scoring: ScoringArgs,
+    #[clap(flatten)]
+    sampling: SamplingArgs,
+    /// Directory of the pool's bracket files
+    #[clap(long, default_value = "brackets")]
+    brackets_dir: PathBuf,
+    /// Another bracket to enter in the pool, e.g. your own
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct TeamArgs {
+    /// Team to look at
+    team: String,
+}
+
+#[derive(Args)]
+struct ConditionalArgs {
+    /// Team assumed to win
+    team: String,
+    /// Round the team is assumed to win its game in, e.g. 4 for reaching the Final Four
+    #[clap(long)]
+    wins_round: usize,
+    #[clap(flatten)]
+    sampling: SamplingArgs,
+}
+
+#[derive(Args)]
+struct SensitivityArgs {
+    /// Percentage points to nudge each game's probability by in either direction
+    #[clap(long, default_value = "5")]
+    delta: f64,
+    /// How many of the likeliest champions to look at
+    #[clap(long, default_value = "8")]
+    contenders: usize,
+    /// How many swing games to list for each contender
+    #[clap(long, default_value = "5")]
+    games: usize,
+}
+
+#[derive(Args)]
+struct GenerateBracketsArgs {
+    /// Number of brackets to sample
+    #[clap(long, default_value = "10")]
+    count: usize,
+    /// Scale game probabilities on the logit scale before sampling, see simulate
+    #[clap(long, default_value = "1")]
+    sharpen: f64,
+    /// Random number generator to sample with. ChaCha's output stays the same across
+    /// versions of its crate, which the others don't promise
+    #[clap(long, arg_enum, default_value = "chacha")]
+    rng: RngKind,
+    /// Seed of the first bracket, each after it taking the next seed. Random if not given
+    #[clap(long)]
+    seed: Option<u64>,
+    /// Directory to save the brackets and their manifest to
+    #[clap(long, default_value = "brackets")]
+    brackets_dir: PathBuf,
+    /// Instead of sampling new brackets, draw this bracket from the manifest in
+    /// --brackets-dir again, numbered from 1
+    #[clap(long)]
+    regenerate: Option<usize>,
+}
+
+#[derive(Args)]
+struct OddsArgs {
+    /// Team to give the chance of winning for
+    team1: String,
+    /// Their opponent
+    team2: String,
+    /// Round the teams are expected to meet in, defaulting to the earliest they can
+    #[clap(long)]
+    round: Option<usize>,
+}
+
+#[derive(Args)]
+struct H2hArgs {
+    #[clap(flatten)]
+    teams: OddsArgs,
+    /// Where the chance of each game comes from
+    #[clap(long, arg_enum, default_value = "538")]
+    model: Model,
+    #[clap(flatten)]
+    sampling: SamplingArgs,
+}
+
+#[derive(Args)]
+struct CalibrationArgs {
+    /// Largest gap in percentage points between the two that isn't flagged, allowing for the
+    /// rounding of hover labels
+    #[clap(long, default_value = "2")]
+    tolerance: f64,
+}
+
+#[derive(Args)]
+struct ScrapeArgs {
+    /// Where the probabilities come from: hover over every pair on the 538 page, the payload
+    /// of team ratings embedded in it, the ratings or seeds saved by write-teams-table, or
+    /// ratings:<file> for a CSV of any model's ratings with a team,rating header
+    #[clap(long, default_value = "hover")]
+    source: ScrapeSource,
+    /// How the gap between two teams' ratings from ratings:<file> becomes a win probability
+    #[clap(long, arg_enum, default_value = "logistic")]
+    rating_formula: RatingFormula,
+    /// Rating gap at which the formula gives the better team a 10 in 11 chance for elo, or
+    /// e to 1 odds for logistic, 400 for elo and 6 for logistic unless given
+    #[clap(long)]
+    rating_scale: Option<f64>,
+    /// Browser sessions to split the work of hovering between
+    #[clap(long, default_value = "1")]
+    sessions: usize,
+}
+
+#[derive(Args)]
+struct AdvancementArgs {
+    /// Where the round by round probabilities come from
+    #[clap(long, arg_enum, default_value = "hover")]
+    source: Source,
+    /// Saved copy of the forecast CSV to read with --source csv instead of downloading it
+    #[clap(long)]
+    input: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct BuildBracketArgs {
+    #[clap(flatten)]
+    scoring: ScoringArgs,
+    /// How to fill in the bracket
+    #[clap(long, arg_enum, default_value = "max-likelihood")]
+    strategy: Strategy,
+    /// How to print the bracket
+    #[clap(long, arg_enum, default_value = "upsets")]
+    display: BracketDisplay,
+}
+
+#[derive(Args)]
+struct StandsArgs {
+    #[clap(flatten)]
+    scoring: ScoringArgs,
+    /// Bracket to compare, the last simulated or built bracket unless given
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ListTeamsArgs {
+    /// Only list teams from this region
+    #[clap(long)]
+    region: Option<Region>,
+    /// Only list teams seeded in this range, e.g. 1-4 or 12
+    #[clap(long, parse(try_from_str = parse_seed_range))]
+    seed_range: Option<RangeInclusive<u8>>,
+    /// Only list teams from this conference
+    #[clap(long)]
+    conference: Option<String>,
+    /// Only list teams still alive
+    #[clap(long)]
+    alive: bool,
+}
+
+#[derive(Args)]
+struct CalcuttaArgs {
+    /// Percent of the pot paid per game won in each round, comma separated
+    #[clap(long, use_value_delimiter = true, default_value = "0.5,1,2,4,8,20")]
+    calcutta_payouts: Vec<f64>,
+    /// Size of the pot
+    #[clap(long, default_value = "100")]
+    pot: f64,
+}
+
+#[derive(Args)]
+struct CinderellaArgs {
+    /// Bracket to look for Cinderellas in, the last simulated or built bracket unless given
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+    /// Look in the real results on the 538 page instead of a bracket
+    #[clap(long)]
+    actual: bool,
+    /// Best seed counted as a Cinderella
+    #[clap(long, default_value = "10")]
+    min_seed: u8,
+}
+
+#[derive(Args)]
+struct ChalkScoreArgs {
+    /// Bracket to score, instead of every bracket in --brackets-dir
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+    /// Directory of bracket files
+    #[clap(long, default_value = "brackets")]
+    brackets_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct SimilarityArgs {
+    #[clap(flatten)]
+    scoring: ScoringArgs,
+    /// Bracket to compare, the last simulated or built bracket unless given
+    #[clap(long)]
+    bracket: Option<PathBuf>,
+    /// Consensus or public bracket to compare against
+    #[clap(long)]
+    consensus: PathBuf,
+}
+
+#[derive(Args)]
+struct LeverageArgs {
+    /// Where the round by round probabilities come from
+    #[clap(long, arg_enum, default_value = "hover")]
+    source: Source,
+    /// Saved copy of the forecast CSV to read with --source csv instead of downloading it
+    #[clap(long)]
+    input: Option<PathBuf>,
+    /// Only show picks in this round
+    #[clap(long)]
+    round: Option<usize>,
+}
+
+#[derive(Args)]
+struct PortfolioArgs {
+    /// Number of pool entries to build brackets for
+    #[clap(long, default_value = "3")]
+    entries: usize,
+    /// Directory to save the entries' brackets to
+    #[clap(long, default_value = "brackets")]
+    brackets_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct BacktestArgs {
+    #[clap(flatten)]
+    scoring: ScoringArgs,
+    #[clap(flatten)]
+    sampling: SamplingArgs,
+    /// Scale game probabilities on the logit scale before sampling brackets
+    #[clap(long, default_value = "1")]
+    sharpen: f64,
+    /// Directory with a directory per past season, each holding probabilities.json and
+    /// results.json
+    #[clap(long, default_value = "history")]
+    history_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct EvaluateArgs {
+    /// Which predictions to score
+    #[clap(long, arg_enum, default_value = "matrix")]
+    predictions: Predictions,
+    /// Bracket of real results to score against instead of the 538 page
+    #[clap(long)]
+    input: Option<PathBuf>,
+    /// CSV of sportsbook moneylines, for --predictions odds
+    #[clap(long)]
+    odds: Option<PathBuf>,
+    /// CSV of team efficiency ratings, for --predictions kenpom
+    #[clap(long)]
+    efficiency: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct DaemonArgs {
+    /// Hours between cycles
+    #[clap(long, default_value = "6")]
+    every_hours: f64,
+}
+
+#[derive(Args)]
+struct LiveArgs {
+    /// Seconds between checks of the live games
+    #[clap(long, default_value = "60")]
+    interval_secs: u64,
+    /// Percentage points a game's win probability has to move since it was last reported
+    /// to be reported again
+    #[clap(long, default_value = "20")]
+    swing: f64,
+    /// Slack or Discord webhook to post each swing to
+    #[clap(long)]
+    webhook_url: Option<String>,
+}
+
+/// Why a task failed, which decides the exit code. Bad arguments exit with 2 from clap.
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Failure {
+    Other = 1,
+    /// Talking to the browser or reading the 538 page failed
+    Scrape = 3,
+    /// Probabilities were missing or invalid while simulating or analyzing brackets
+    Simulation = 4,
+    /// Reading or writing a file failed
+    Io = 5,
+}
+
+impl Failure {
+    /// Decide what kind of failure an error from running `task` was, going by its causes
+    /// first and then by the task
+    fn classify(e: &anyhow::Error, task: &Task) -> Self {
+        if e.chain().any(|cause| cause.is::<WebDriverError>()) {
+            return Failure::Scrape;
+        }
+        if e.chain().any(|cause| cause.is::<std::io::Error>()) {
+            return Failure::Io;
+        }
+        match task {
+            Task::WriteTeamsTable
+            | Task::ScrapeProbabilities(_)
+            | Task::ScrapePublicPicks
+            | Task::RecordResults
+            | Task::NodeIds
+            | Task::Live(_) => Failure::Scrape,
+            Task::Simulate(_)
+            | Task::SecondChance(_)
+            | Task::Serve(_)
+            | Task::PoolOdds(_)
+            | Task::Advancement(_)
+            | Task::TitlePath(_)
+            | Task::Conditional(_)
+            | Task::Sensitivity(_)
+            | Task::MostLikely
+            | Task::BuildBracket(_)
+            | Task::Portfolio(_)
+            | Task::Backtest(_) => Failure::Simulation,
+            _ => Failure::Other,
+        }
+    }
+}
+
+/// Log to stderr, and to the log file as well if one is given
+fn init_logging(global: &GlobalOpts) -> anyhow::Result<()> {
+    let path = match &global.log_file {
+        Some(path) => path,
+        None => {
+            env_logger::init();
+            return Ok(());
+        }
+    };
+    let file = RotatingFile::open(
+        path,
+        global.log_file_max_mb * 1024 * 1024,
+        global.log_files_kept,
+    )?;
+    env_logger::Builder::from_default_env()
+        .target(env_logger::Target::Pipe(Box::new(Mirrored(file))))
+        .init();
+    Ok(())
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    std::env::set_var("RUST_LOG", "info");
-    env_logger::init();
+async fn main() {
+    let args = match parse_opts() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(Failure::Other as i32);
+        }
+    };
+    let global = &args.global;
+    output::set_format(global.output);
+    output::set_result_markers(global.result_markers);
+    let level = if output::json() { "warn" } else { "info" };
+    std::env::set_var("RUST_LOG", level);
+    if let Err(e) = init_logging(global) {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(Failure::Other as i32);
+    }
 
-    let args = Opts::parse();
+    match global.color {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+        ColorChoice::Auto => {}
+    }
+    throttle::configure(
+        Duration::from_millis(global.action_delay_ms),
+        global.max_calls_per_sec,
+    );
+    timeouts::set_element_timeout(Duration::from_millis(global.element_timeout_ms));
+    timeouts::set_hover_wait(
+        Duration::from_millis(global.hover_timeout_ms.unwrap_or(global.element_timeout_ms)),
+        Duration::from_millis(global.hover_poll_ms),
+    );
+    cache::set_ttl(Duration::from_secs(global.cache_ttl_secs));
+    gender::set_gender(global.gender.unwrap_or(Gender::Mens));
+    if let Some(year) = global.year {
+        season::set_year(year);
+    }
+    season::set_scoped(global.year.is_some() || global.gender.is_some());
+    if let Some(name) = &global.profile {
+        log::info!("Using profile {} in {}", name, profile::dir(name).display());
+    }
+    if let Some(path) = &global.selectors {
+        if let Err(e) = selectors::load(path) {
+            log::error!("{:#}", e);
+            std::process::exit(Failure::Other as i32);
+        }
+    }
+    if let Some(path) = &global.audit_log {
+        if let Err(e) = audit::open(path) {
+            log::error!("{:#}", e);
+            std::process::exit(Failure::Io as i32);
+        }
+    }
 
-    let caps = DesiredCapabilities::chrome();
-    let driver = WebDriver::new(SELENIUM_SERVER_URL, &caps).await?;
-    let res = match args.task {
-        Task::WriteTeamsTable => write_teams(&driver).await,
-        Task::Simulate => simulate::simulate(&driver).await,
+    let res = match &args.task {
+        _ if needs_driver(&args.task) => run_with_driver(&args).await,
+        _ if global.all => Err(anyhow!(
+            "--all is only for tasks which load the 538 page in one browser session"
+        )),
+        Task::ImportEspn(task) => import_espn(task),
+        Task::ExportPicks(task) => export_picks(task),
+        Task::FuturesReport(task) => futures_report(task),
+        Task::Parlays(task) => parlays(task),
+        Task::Kelly(task) => kelly(task),
+        Task::Calcutta(task) => calcutta(task),
+        Task::Cinderellas(task) if !task.actual => cinderellas(task),
+        Task::H2h(task) => seed_h2h(task),
+        Task::ChalkScore(task) => chalk_score(task),
+        Task::Similarity(task) => similarity(task),
+        Task::Backtest(task) => backtest(task),
+        Task::Evaluate(task) if task.input.is_some() => {
+            evaluate_results(task, report::evaluation_report)
+        }
+        Task::Madness(task) if task.input.is_some() => {
+            evaluate_results(task, report::madness_report)
+        }
+        Task::Serve(task) => serve(global, task).await,
+        Task::ScrapeProbabilities(task) => match &task.source {
+            ScrapeSource::Ratings => rating_probabilities(),
+            ScrapeSource::RatingsFile(path) => rating_file_probabilities(path, task),
+            ScrapeSource::Seeds => seed_probabilities(),
+            ScrapeSource::Hover | ScrapeSource::Payload => {
+                scrape_in_parallel(global, task.sessions).await
+            }
+        },
+        Task::BlankBracket => blank_bracket(),
+        Task::Simulate(task) => seed_simulation(task),
+        Task::Advancement(task) => forecast_advancement(task.input.as_deref()).await,
+        Task::Leverage(task) => forecast_leverage(task).await,
+        _ => unreachable!(),
     };
+    #[cfg(feature = "sqlite")]
+    let res = res.and_then(|_| store_outputs(&args));
 
     if let Err(e) = res {
         log::error!("{}", e);
+        // Any browser has been closed by now
+        std::process::exit(Failure::classify(&e, &args.task) as i32);
+    }
+}
+
+/// Parse the command line, with the options from any --profile's settings before the given
+/// ones, and move into the profile's directory
+fn parse_opts() -> anyhow::Result<Opts> {
+    let args = Opts::parse();
+    let name = match &args.global.profile {
+        Some(name) => name.clone(),
+        None => return Ok(args),
+    };
+    let profile = Profile::load(&name)?;
+    let given: Vec<OsString> = env::args_os().collect();
+    let matches = Opts::command().get_matches_from(&given);
+    let task = matches.subcommand_name().unwrap_or_default();
+    let args = Opts::parse_from(profile.apply(&given, task));
+    let dir = profile::dir(&name);
+    env::set_current_dir(&dir).with_context(|| format!("Could not move into {}", dir.display()))?;
+    Ok(args)
+}
+
+/// Whether the task runs on a single browser session, through [`run_with_driver`]
+fn needs_driver(task: &Task) -> bool {
+    match task {
+        Task::Evaluate(task) | Task::Madness(task) => task.input.is_none(),
+        Task::Cinderellas(task) => task.actual,
+        Task::ScrapeProbabilities(task) => match task.source {
+            ScrapeSource::Ratings | ScrapeSource::RatingsFile(_) | ScrapeSource::Seeds => false,
+            ScrapeSource::Hover => task.sessions <= 1,
+            ScrapeSource::Payload => true,
+        },
+        Task::Advancement(task) => task.source != Source::Csv,
+        Task::Leverage(task) => task.source != Source::Csv,
+        Task::Simulate(task) => task.model != Model::Seeds,
+        Task::H2h(task) => task.model != Model::Seeds,
+        Task::ImportEspn(_)
+        | Task::ExportPicks(_)
+        | Task::FuturesReport(_)
+        | Task::Parlays(_)
+        | Task::Kelly(_)
+        | Task::Calcutta(_)
+        | Task::ChalkScore(_)
+        | Task::Similarity(_)
+        | Task::Backtest(_)
+        | Task::Serve(_)
+        | Task::BlankBracket => false,
+        _ => true,
+    }
+}
+
+/// Copy what the task wrote into the database given with --store
+#[cfg(feature = "sqlite")]
+fn store_outputs(args: &Opts) -> anyhow::Result<()> {
+    let path = match &args.global.store {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let save: fn(&mut Store) -> anyhow::Result<()> = match &args.task {
+        Task::WriteTeamsTable => |store| store.save_teams(&load_teams()?),
+        Task::ScrapeProbabilities(_) => |store| {
+            store.save_probabilities(&ProbabilityMatrix::load(season::data_path(
+                PROBABILITIES_PATH,
+            ))?)
+        },
+        Task::Simulate(SimulateArgs {
+            model: Model::FiveThirtyEight,
+            games_format: GamesFormat::Csv,
+            ..
+        })
+        | Task::SecondChance(SecondChanceArgs {
+            games_format: GamesFormat::Csv,
+            ..
+        }) => |store| {
+            let id =
+                store.add_simulation(&games_log::load_games(season::data_path(GAMES_PATH))?)?;
+            log::info!("Stored the simulated games as simulation {}", id);
+            Ok(())
+        },
+        Task::RecordResults | Task::Daemon(_) => |store| {
+            store.add_results(&results::load_history(season::data_path(
+                RESULTS_HISTORY_PATH,
+            ))?)
+        },
+        _ => return Ok(()),
+    };
+    save(&mut Store::open(path)?)?;
+    log::info!("Updated {}", path.display());
+    Ok(())
+}
+
+/// Launch our own chromedriver if asked to, otherwise the WebDriver server is expected to be
+/// running already
+async fn launch_chromedriver(global: &GlobalOpts) -> anyhow::Result<Option<ChromeDriver>> {
+    if global.manage_driver {
+        Ok(Some(ChromeDriver::launch().await?))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn connect(
+    global: &GlobalOpts,
+    chromedriver: Option<&ChromeDriver>,
+) -> anyhow::Result<Session> {
+    let mut browser = BrowserOptions {
+        args: global.chrome_arg.clone(),
+        ..Default::default()
+    };
+    if let Some(path) = &global.capabilities {
+        browser.load_capabilities(path)?;
+    }
+    browser.cassette = match (&global.record, &global.replay) {
+        (Some(path), _) => Some(Cassette::Record(path.clone())),
+        (_, Some(path)) => Some(Cassette::Replay(path.clone())),
+        (None, None) => None,
+    };
+    let driver = match chromedriver {
+        // Our chromedriver exits along with us, taking its sessions with it
+        Some(_) if global.reuse_session => {
+            return Err(anyhow!(
+                "--reuse-session needs an already running WebDriver server"
+            ))
+        }
+        Some(chromedriver) => {
+            endpoint::connect(DriverKind::Chromedriver, &chromedriver.url(), &browser).await?
+        }
+        None => {
+            let url = global
+                .driver_endpoint
+                .as_deref()
+                .unwrap_or_else(|| global.driver.default_endpoint());
+            if global.reuse_session {
+                endpoint::connect_reusing(global.driver, url, &browser).await?
+            } else {
+                endpoint::connect(global.driver, url, &browser).await?
+            }
+        }
+    };
+    driver
+        .set_implicit_wait_timeout(Duration::from_millis(global.implicit_wait_ms))
+        .await?;
+    Ok(Session::new(driver))
+}
+
+/// Run a task which needs a browser, making sure the driver is closed afterwards
+async fn run_with_driver(args: &Opts) -> anyhow::Result<()> {
+    let global = &args.global;
+    let start = Instant::now();
+    let chromedriver = launch_chromedriver(global).await?;
+    let driver = connect(global, chromedriver.as_ref()).await?;
+    let daemon = matches!(args.task, Task::Daemon(_) | Task::Live(_));
+    let metrics = match global.metrics_port {
+        Some(port) if global.watch || daemon => Some(tokio::spawn(async move {
+            if let Err(e) = server::serve_metrics(port).await {
+                log::error!("Metrics server failed: {}", e);
+            }
+        })),
+        _ => None,
+    };
+    let res = if global.all {
+        run_both(&driver, args).await
+    } else if global.watch {
+        watch(&driver, args).await
+    } else {
+        run_task(&driver, args).await
+    };
+    if let Some(metrics) = metrics {
+        metrics.abort();
+    }
+    // The task's own error is the more useful one to report
+    let closed = close(driver, global).await;
+    let timings = timings::timings(start.elapsed());
+    timings::report(&timings);
+    let saved = match &global.timings_file {
+        Some(path) => timings::save(&timings, path),
+        None => Ok(()),
+    };
+    res.and(closed).and(saved)
+}
+
+/// Run the task for the men's and then the women's tournament on the same session, each in
+/// its own directory so their files don't overwrite each other
+async fn run_both(driver: &Session, args: &Opts) -> anyhow::Result<()> {
+    if matches!(args.task, Task::Daemon(_) | Task::Live(_)) {
+        return Err(anyhow!("--all can't be used with the daemon or live"));
+    }
+    let home = env::current_dir()?;
+    for gender in Gender::ALL {
+        let dir = home.join(gender.key());
+        fs::create_dir_all(&dir).with_context(|| format!("Could not create {}", dir.display()))?;
+        env::set_current_dir(&dir)?;
+        gender::set_gender(gender);
+        log::info!("Running for the {} tournament in {}", gender, dir.display());
+        let res = run_task(driver, args).await;
+        env::set_current_dir(&home)?;
+        res.with_context(|| format!("The {} tournament's run failed", gender))?;
+    }
+    Ok(())
+}
+
+/// Quit the browser, unless it is being kept open for the next run
+async fn close(driver: Session, global: &GlobalOpts) -> anyhow::Result<()> {
+    if !global.reuse_session {
+        driver.quit().await?;
+    }
+    Ok(())
+}
+
+/// Run the task, then keep polling the 538 page and run it again whenever new real results
+/// show up, until interrupted
+async fn watch(driver: &Session, args: &Opts) -> anyhow::Result<()> {
+    run_task(driver, args).await?;
+    let mut known = results::decided_games(&simulate::current_bracket(driver).await?);
+    let interval = Duration::from_secs(args.global.watch_interval_secs);
+    loop {
+        log::info!("Checking for new results in {}s", interval.as_secs());
+        tokio::select! {
+            _ = shutdown_signal() => {
+                log::info!("Stopped watching");
+                return Ok(());
+            }
+            _ = tokio::time::sleep(interval) => {}
+        }
+        let results = results::decided_games(&simulate::current_bracket(driver).await?);
+        let new = results.difference(&known).collect::<Vec<_>>();
+        if new.is_empty() {
+            continue;
+        }
+        for (round_num, winner, loser) in new {
+            log::info!(
+                "New result in the {}: {} beat {}",
+                RoundKind::Round(*round_num),
+                winner,
+                loser
+            );
+        }
+        known = results;
+        // Keep watching when a run fails, the next results may fix it
+        if let Err(e) = run_task(driver, args).await {
+            log::error!("{}", e);
+        }
+    }
+}
+
+/// Run a snapshot, scrape and report cycle on a schedule. Shutting down interrupts a cycle
+/// in progress, after which the driver is closed as usual.
+async fn daemon(driver: &Session, args: &DaemonArgs) -> anyhow::Result<()> {
+    if args.every_hours <= 0. {
+        return Err(anyhow!("--every-hours must be positive"));
+    }
+    let interval = Duration::from_secs_f64(args.every_hours * 3600.);
+    loop {
+        tokio::select! {
+            res = daemon_cycle(driver) => {
+                // A failed cycle may well work next time, e.g. if the site was down
+                if let Err(e) = res {
+                    log::error!("{}", e);
+                }
+            }
+            _ = shutdown_signal() => break,
+        }
+        log::info!("Next cycle in {:.1} hours", args.every_hours);
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown_signal() => break,
+        }
+    }
+    log::info!("Shutting down");
+    Ok(())
+}
+
+async fn daemon_cycle(driver: &Session) -> anyhow::Result<()> {
+    let actual = simulate::current_bracket(driver).await?;
+    let new = results::record_results(&actual, season::data_path(RESULTS_HISTORY_PATH))?;
+    log::info!("Recorded {} new results", new.len());
+
+    // Ratings move between cycles, so every pair is scraped afresh
+    let mut matrix = ProbabilityMatrix::default();
+    simulate::scrape_probabilities(slice::from_ref(driver), &mut matrix).await?;
+    print_advancement(&analytic::round_probabilities(&actual, &matrix)?)
+}
+
+/// Check the live games every --interval-secs, logging each and reporting large swings,
+/// until interrupted
+async fn live(driver: &Session, args: &LiveArgs) -> anyhow::Result<()> {
+    if !(args.swing > 0. && args.swing <= 100.) {
+        return Err(anyhow!("--swing must be between 0 and 100 points"));
+    }
+    let teams = load_teams()?;
+    let interval = Duration::from_secs(args.interval_secs);
+    let mut tracker = SwingTracker::default();
+    loop {
+        // A failed check may well work next time, e.g. if the page was mid-update
+        match live::live_games(driver, &teams).await {
+            Ok(games) => {
+                if games.is_empty() {
+                    log::info!("No games in progress");
+                }
+                for game in &games {
+                    log::info!(
+                        "{} {:.1}% vs {}",
+                        game.teams[0],
+                        game.win_prob * 100.,
+                        game.teams[1]
+                    );
+                }
+                for swing in tracker.update(&games, args.swing / 100.) {
+                    log::warn!("{}", swing);
+                    if let Some(url) = &args.webhook_url {
+                        if let Err(e) = notify::post_webhook(url, &swing.to_string()).await {
+                            log::warn!("Could not send webhook notification: {}", e);
+                        }
+                    }
+                }
+                if output::json() {
+                    output::emit(&games)?;
+                }
+            }
+            Err(e) => log::error!("{}", e),
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown_signal() => break,
+        }
+    }
+    log::info!("Stopped following live games");
+    Ok(())
+}
+
+/// Wait for Ctrl-C, or on Unix for SIGTERM as sent by service managers
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                tokio::signal::ctrl_c().await.ok();
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
+
+/// Run a task which needs a browser on an open session
+async fn run_task(driver: &Session, args: &Opts) -> anyhow::Result<()> {
+    match &args.task {
+        Task::WriteTeamsTable => write_teams_table(driver).await,
+        Task::Simulate(task) => run_simulation(driver, task).await,
+        Task::SecondChance(task) => second_chance(driver, task).await,
+        Task::SubmitBracket(task) => submit_bracket(driver, task).await,
+        Task::Leaderboard(task) => leaderboard(driver, task).await,
+        Task::PoolOdds(task) => pool_odds(driver, task).await,
+        Task::ChampionPath(task) => champion_path(driver, task).await,
+        Task::TitlePath(task) => title_path(driver, task).await,
+        Task::Conditional(task) => conditional(driver, task).await,
+        Task::Sensitivity(task) => sensitivity(driver, task).await,
+        Task::GenerateBrackets(task) => generate_brackets(driver, task).await,
+        Task::ScrapeProbabilities(task) => scrape_probabilities(driver, task).await,
+        Task::Advancement(_) => advancement(driver).await,
+        Task::MostLikely => most_likely(driver).await,
+        Task::Stands(task) => stands(driver, task).await,
+        Task::BuildBracket(task) => build_bracket(driver, task).await,
+        Task::Odds(task) => head_to_head(driver, task).await,
+        Task::H2h(task) => h2h(driver, task).await,
+        Task::TeamInfo(task) => team_info(driver, task).await,
+        Task::ListTeams(task) => list_teams(driver, task).await,
+        Task::ScrapePublicPicks => scrape_public_picks(driver).await,
+        Task::Leverage(task) => leverage(driver, task).await,
+        Task::Portfolio(task) => portfolio(driver, task).await,
+        Task::Evaluate(task) => evaluate(driver, task, report::evaluation_report).await,
+        Task::Madness(task) => evaluate(driver, task, report::madness_report).await,
+        Task::RecordResults => record_results(driver).await,
+        Task::Daemon(task) => daemon(driver, task).await,
+        Task::NodeIds => node_ids(driver).await,
+        Task::Live(task) => live(driver, task).await,
+        Task::ScrapeForecastTable => scrape_forecast_table(driver).await,
+        Task::Calibration(task) => calibration(driver, task).await,
+        Task::Cinderellas(task) => {
+            let actual = simulate::cached_bracket(driver).await?;
+            print_cinderellas(&actual, task)
+        }
+        _ => unreachable!(),
+    }
+}
+
+async fn write_teams_table(driver: &Session) -> anyhow::Result<()> {
+    write_teams(driver).await?;
+    if output::json() {
+        output::emit(&load_teams()?)?;
+    }
+    Ok(())
+}
+
+fn blank_bracket() -> anyhow::Result<()> {
+    let teams = load_teams()?;
+    if output::json() {
+        output::emit(&teams)
+    } else {
+        print!("{}", report::blank_bracket(&teams));
+        Ok(())
+    }
+}
+
+async fn run_simulation(driver: &Session, args: &SimulateArgs) -> anyhow::Result<()> {
+    let options = simulate_options(args)?;
+    let (bracket, summary) = simulate::simulate(driver, &options).await?;
+    let tiebreaker = tiebreaker::predict_title_score(&bracket, options.efficiencies.as_ref());
+    if output::json() {
+        output::emit(&json!({
+            "bracket": bracket,
+            "summary": summary,
+            "tiebreaker": tiebreaker,
+        }))?;
+    } else {
+        print_bracket(&bracket, args.display);
+        if summary.runs > 1 {
+            report::convergence_report(&summary);
+        }
+        if let Some(score) = &tiebreaker {
+            println!("Tiebreaker: {}", score);
+        }
+    }
+    if let Some(path) = &args.markdown_out {
+        let markdown = report::bracket_markdown(
+            &bracket,
+            &load_teams()?,
+            Some(&summary),
+            tiebreaker.as_ref(),
+        );
+        fs::write(path, markdown)?;
+        log::info!("Wrote Markdown bracket to {}", path.display());
+    }
+    if let Some(url) = &args.webhook_url {
+        let extra = tiebreaker
+            .iter()
+            .map(|score| format!("Tiebreaker: {}", score))
+            .collect::<Vec<_>>();
+        let message = notify::bracket_message(&bracket, &load_teams()?, &extra);
+        if let Err(e) = notify::post_webhook(url, &message).await {
+            log::warn!("Could not send webhook notification: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Simulate the tournament from the first round with the built-in seed history as the only
+/// prediction source. Nothing is saved, so the brackets and summary of other models are kept.
+fn seed_simulation(args: &SimulateArgs) -> anyhow::Result<()> {
+    let predictions = &args.predictions;
+    let other_sources = predictions.odds.is_some()
+        || predictions.overrides.is_some()
+        || predictions.adjustments.is_some()
+        || !predictions.boost.is_empty()
+        || predictions.efficiency.is_some();
+    if other_sources {
+        return Err(anyhow!(
+            "--model seeds uses nothing but the seed history, without other prediction sources"
+        ));
+    }
+    if args.start_round != 1
+        || args.through_round != 6
+        || args.webhook_url.is_some()
+        || args.stream_games
+        || args.resume
+    {
+        return Err(anyhow!(
+            "--model seeds simulates every round at once, without --start-round, --through-round, \
+             --webhook-url, --stream-games or --resume"
+        ));
+    }
+    if args.sharpen <= 0. {
+        return Err(anyhow!("--sharpen must be positive"));
+    }
+    let mut teams = load_teams()?;
+    let matrix = SeedHistory::default().matrix(&teams);
+    record_seeds(&matrix, &teams);
+    let start = Tournament::new(&mut teams, Default::default())?;
+    let mut sampler = args.sampling.sampler();
+    let mut summary = SimulationSummary::new(sampler.sampling());
+    let mut last = None;
+    for _ in 0..args.sampling.runs {
+        sampler.start_run();
+        let bracket = engine::sample_bracket(&start, &matrix, args.sharpen, &mut sampler)?;
+        summary.add(&bracket);
+        last = Some(bracket);
+    }
+    let bracket = last.context("At least one run is required")?;
+    if output::json() {
+        output::emit(&json!({
+            "bracket": bracket,
+            "summary": summary,
+        }))?;
+    } else {
+        print_bracket(&bracket, args.display);
+        if summary.runs > 1 {
+            report::convergence_report(&summary);
+        }
+    }
+    if let Some(path) = &args.markdown_out {
+        let markdown = report::bracket_markdown(&bracket, &teams, Some(&summary), None);
+        fs::write(path, markdown)?;
+        log::info!("Wrote Markdown bracket to {}", path.display());
+    }
+    Ok(())
+}
+
+fn print_bracket(bracket: &Tournament, display: BracketDisplay) {
+    match display {
+        BracketDisplay::Upsets => print!("{}", report::upset_summary(bracket)),
+        BracketDisplay::Rounds => print!("{}", bracket),
+        BracketDisplay::Regions => print!("{}", report::region_bracket(bracket)),
+    }
+}
+
+async fn second_chance(driver: &Session, args: &SecondChanceArgs) -> anyhow::Result<()> {
+    let scoring = ScoringSystem::second_chance(&args.round_points)?;
+    let survivors = second_chance::survivors(&simulate::current_bracket(driver).await?)?;
+    log::info!("Second chance bracket of {}", survivors.join(", "));
+    let options = SimulateOptions {
+        start_round: second_chance::FIRST_ROUND,
+        bracket_path: PathBuf::from(season::data_path(SECOND_CHANCE_BRACKET_PATH)),
+        ..prediction_options(
+            &args.predictions,
+            &args.sampling,
+            args.sharpen,
+            args.games_format,
+        )?
+    };
+    let (bracket, summary) = simulate::simulate(driver, &options).await?;
+    let score = second_chance::score(&bracket, &summary, &scoring);
+    if output::json() {
+        output::emit(&json!({
+            "bracket": bracket,
+            "summary": summary,
+            "score": score,
+        }))?;
+    } else {
+        for round_num in second_chance::FIRST_ROUND..=6 {
+            print!("{}", bracket.rounds[&RoundKind::Round(round_num)]);
+        }
+        println!(
+            "Expected points: {:.1} of {}",
+            score.expected_points, score.max_points
+        );
+    }
+    if let Some(site) = args.submit {
+        submit::submit_bracket(driver, site, &bracket, second_chance::FIRST_ROUND).await?;
     }
-    driver.quit().await?;
+    Ok(())
+}
 
+async fn leaderboard(driver: &Session, args: &LeaderboardArgs) -> anyhow::Result<()> {
+    let scoring = args.scoring.scoring()?;
+    let brackets = scoring::load_brackets(&args.brackets_dir)?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let scores = report::leaderboard(&brackets, &actual, &scoring);
+    let lines = report::leaderboard_lines(&scores);
+    if output::json() {
+        let entries = scores
+            .iter()
+            .map(|(name, score)| json!({ "name": name, "score": score }))
+            .collect::<Vec<_>>();
+        output::emit(&entries)?;
+    } else {
+        for line in &lines {
+            println!("{}", line);
+        }
+    }
+    if let Some(url) = &args.webhook_url {
+        let message = format!("Pool standings\n```\n{}\n```", lines.join("\n"));
+        if let Err(e) = notify::post_webhook(url, &message).await {
+            log::warn!("Could not send webhook notification: {}", e);
+        }
+    }
     Ok(())
 }
+
+async fn pool_odds(driver: &Session, args: &PoolOddsArgs) -> anyhow::Result<()> {
+    let scoring = args.scoring.scoring()?;
+    let mut brackets = scoring::load_brackets(&args.brackets_dir)?;
+    if let Some(path) = &args.bracket {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("mine")
+            .to_string();
+        let bracket = Tournament::load(path)
+            .with_context(|| format!("Could not load bracket {}", path.display()))?;
+        brackets.push((name, bracket));
+    }
+    if brackets.is_empty() {
+        return Err(anyhow!("No brackets to simulate the pool with"));
+    }
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let mut sampler = args.sampling.sampler();
+    let odds = pool::simulate_pool(
+        &brackets,
+        &actual,
+        &matrix,
+        &scoring,
+        args.sampling.runs,
+        &mut sampler,
+    )?;
+    if output::json() {
+        return output::emit(&odds);
+    }
+    report::pool_report(&odds);
+    Ok(())
+}
+
+async fn champion_path(driver: &Session, args: &TeamArgs) -> anyhow::Result<()> {
+    let name = &args.team;
+    let teams = load_teams()?;
+    let team = find_team(&teams, name).with_context(|| format!("Unknown team {}", name))?;
+    let path = simulate::champion_path(driver, team.name()).await?;
+    if output::json() {
+        return output::emit(&json!({ "team": team.name(), "path": path }));
+    }
+
+    println!("Most likely path to the title for {}", team.name());
+    // One game a round, ending with the title game
+    let round_count = path.len();
+    let mut title_prob = 1.;
+    for step in path {
+        match step.win_prob {
+            Some(prob) => {
+                title_prob *= prob;
+                println!(
+                    "{:<12} vs {:<24} {:>5.1}%",
+                    step.round.name(round_count),
+                    step.opponent,
+                    prob * 100.
+                );
+            }
+            None => println!(
+                "{:<12} vs {:<24}   won",
+                step.round.name(round_count),
+                step.opponent
+            ),
+        }
+    }
+    println!(
+        "Chance of winning out along this path: {:.1}%",
+        title_prob * 100.
+    );
+    Ok(())
+}
+
+async fn title_path(driver: &Session, args: &TeamArgs) -> anyhow::Result<()> {
+    let name = &args.team;
+    let teams = load_teams()?;
+    let team = find_team(&teams, name).with_context(|| format!("Unknown team {}", name))?;
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let bracket = simulate::cached_bracket(driver).await?;
+    let path = analytic::title_path(&bracket, &matrix, team.name())?;
+    if output::json() {
+        return output::emit(&json!({ "team": team.name(), "path": path }));
+    }
+
+    println!("Path to the title for {}", team.name());
+    for step in path {
+        println!(
+            "{:<14} reached {:>5.1}%, wins {:>5.1}% of games played",
+            bracket.round_name(RoundKind::Round(step.round)),
+            step.reach * 100.,
+            step.win_if_reached * 100.
+        );
+        for opponent in step.opponents.iter().take(PATH_OPPONENTS_SHOWN) {
+            println!(
+                "    vs {:<24} {:>5.1}% of the time, wins {:>5.1}%",
+                opponent.team,
+                opponent.chance * 100.,
+                opponent.win * 100.
+            );
+        }
+        let others = step.opponents.len().saturating_sub(PATH_OPPONENTS_SHOWN);
+        if others > 0 {
+            println!("    and {} others", others);
+        }
+    }
+    Ok(())
+}
+
+async fn conditional(driver: &Session, args: &ConditionalArgs) -> anyhow::Result<()> {
+    if !(1..=6).contains(&args.wins_round) {
+        return Err(anyhow!("--wins-round must be between 1 and 6"));
+    }
+    let name = &args.team;
+    let teams = load_teams()?;
+    let team = find_team(&teams, name).with_context(|| format!("Unknown team {}", name))?;
+    let condition = Condition {
+        team: team.name().to_string(),
+        round_num: args.wins_round,
+    };
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let mut sampler = args.sampling.sampler();
+    let (all, given) = engine::simulate_conditional(
+        &actual,
+        &matrix,
+        args.sampling.runs,
+        1.,
+        &mut sampler,
+        &condition,
+    )?;
+    if output::json() {
+        return output::emit(&json!({
+            "team": condition.team,
+            "round": condition.round_num,
+            "runs": all.runs,
+            "conditioned_runs": given.runs,
+            "shifts": report::conditional_shifts(&all, &given, &condition),
+        }));
+    }
+    report::conditional_report(&all, &given, &condition);
+    Ok(())
+}
+
+async fn sensitivity(driver: &Session, args: &SensitivityArgs) -> anyhow::Result<()> {
+    if !(args.delta > 0. && args.delta < 100.) {
+        return Err(anyhow!(
+            "--delta must be between 0 and 100 percentage points"
+        ));
+    }
+    let delta = args.delta / 100.;
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let swings = analytic::swing_games(&actual, &matrix, delta, args.contenders, args.games)?;
+    if output::json() {
+        return output::emit(&swings);
+    }
+    let probs = analytic::round_probabilities(&actual, &matrix)?;
+    report::sensitivity_report(&probs, &swings, delta);
+    Ok(())
+}
+
+async fn generate_brackets(driver: &Session, args: &GenerateBracketsArgs) -> anyhow::Result<()> {
+    if args.sharpen <= 0. {
+        return Err(anyhow!("--sharpen must be positive"));
+    }
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    if let Some(number) = args.regenerate {
+        let manifest_path = args.brackets_dir.join(MANIFEST_FILE);
+        let path = manifest::regenerate_bracket(&actual, &matrix, &manifest_path, number)?;
+        log::info!("Drew bracket {} again to {}", number, path.display());
+        if output::json() {
+            output::emit(&json!({ "regenerated": path }))?;
+        }
+        return Ok(());
+    }
+    let manifest = manifest::generate_brackets(
+        &actual,
+        &matrix,
+        &args.brackets_dir,
+        args.count,
+        args.rng,
+        args.seed.unwrap_or_else(rand::random),
+        args.sharpen,
+    )?;
+    if output::json() {
+        return output::emit(&manifest);
+    }
+    for (ind, entry) in manifest.brackets.iter().enumerate() {
+        println!(
+            "Bracket {}: seed {} -> {}",
+            ind + 1,
+            entry.seed,
+            entry.path.display()
+        );
+    }
+    Ok(())
+}
+
+async fn head_to_head(driver: &Session, args: &OddsArgs) -> anyhow::Result<()> {
+    let (name1, name2) = (&args.team1, &args.team2);
+    let teams = load_teams()?;
+    let team1 = find_team(&teams, name1).with_context(|| format!("Unknown team {}", name1))?;
+    let team2 = find_team(&teams, name2).with_context(|| format!("Unknown team {}", name2))?;
+    let (round_num, prob) =
+        simulate::head_to_head(driver, team1.name(), team2.name(), args.round).await?;
+    if output::json() {
+        return output::emit(&json!({
+            "team1": team1.name(),
+            "team2": team2.name(),
+            "round": round_num,
+            "probability": prob,
+        }));
+    }
+    println!(
+        "{} beats {} {:.1}% of the time if they meet in the {}",
+        team1.name(),
+        team2.name(),
+        prob * 100.,
+        RoundKind::Round(round_num)
+    );
+    Ok(())
+}
+
+async fn h2h(driver: &Session, args: &H2hArgs) -> anyhow::Result<()> {
+    let (name1, name2) = (&args.teams.team1, &args.teams.team2);
+    let teams = load_teams()?;
+    let team1 = find_team(&teams, name1).with_context(|| format!("Unknown team {}", name1))?;
+    let team2 = find_team(&teams, name2).with_context(|| format!("Unknown team {}", name2))?;
+    let (round_num, prob) =
+        simulate::head_to_head(driver, team1.name(), team2.name(), args.teams.round).await?;
+    print_h2h(team1, team2, Some(round_num), prob, args)
+}
+
+/// Play a matchup with its chance from the seed history alone, without loading the page
+fn seed_h2h(args: &H2hArgs) -> anyhow::Result<()> {
+    let (name1, name2) = (&args.teams.team1, &args.teams.team2);
+    let teams = load_teams()?;
+    let team1 = find_team(&teams, name1).with_context(|| format!("Unknown team {}", name1))?;
+    let team2 = find_team(&teams, name2).with_context(|| format!("Unknown team {}", name2))?;
+    let prob = SeedHistory::default().win_probability(team1.seed, team2.seed);
+    audit::record(
+        &AuditEntry::new("seeds", team1.name(), team2.name(), args.teams.round, prob)
+            .with_raw(format!("seed {} vs seed {}", team1.seed.0, team2.seed.0)),
+    );
+    print_h2h(team1, team2, args.teams.round, prob, args)
+}
+
+fn print_h2h(
+    team1: &Team,
+    team2: &Team,
+    round_num: Option<usize>,
+    prob: f64,
+    args: &H2hArgs,
+) -> anyhow::Result<()> {
+    if team1.name() == team2.name() {
+        return Err(anyhow!("A team can't play itself"));
+    }
+    if args.sampling.runs == 0 {
+        return Err(anyhow!("--runs must be at least 1"));
+    }
+    let mut sampler = args.sampling.sampler();
+    let split = engine::simulate_matchup(prob, args.sampling.runs, &mut sampler);
+    if output::json() {
+        return output::emit(&json!({
+            "team1": team1.name(),
+            "team2": team2.name(),
+            "round": round_num,
+            "probability": prob,
+            "split": split,
+        }));
+    }
+    let meeting = round_num
+        .map(|round_num| format!(" in the {}", RoundKind::Round(round_num)))
+        .unwrap_or_default();
+    println!(
+        "{} vs {}{}, {:.1}% for {} under the model",
+        team1.name(),
+        team2.name(),
+        meeting,
+        prob * 100.,
+        team1.name()
+    );
+    println!(
+        "{} won {} of {} ({:.1}%, 95% CI {:.1}% to {:.1}%), {} won {}",
+        team1.name(),
+        split.wins[0],
+        args.sampling.runs,
+        split.share * 100.,
+        split.interval.0 * 100.,
+        split.interval.1 * 100.,
+        team2.name(),
+        split.wins[1]
+    );
+    Ok(())
+}
+
+async fn team_info(driver: &Session, args: &TeamArgs) -> anyhow::Result<()> {
+    let name = &args.team;
+    let teams = load_teams()?;
+    let team = find_team(&teams, name).with_context(|| format!("Unknown team {}", name))?;
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let bracket = simulate::cached_bracket(driver).await?;
+
+    // First Four losers never make it into the bracket
+    let in_bracket = bracket.rounds[&RoundKind::Round(1)]
+        .matchups
+        .iter()
+        .any(|m| m.includes_team(team.name()));
+    let lost = if in_bracket {
+        bracket.rounds.values().find_map(|round| {
+            round
+                .matchups
+                .iter()
+                .find(|m| m.losing_team() == Some(team.name()))
+                .map(|m| (round.round, m.winning_team().unwrap_or_default()))
+        })
+    } else {
+        let winner = team
+            .first_four_opponent
+            .as_deref()
+            .with_context(|| format!("{} isn't in the bracket", team.name()))?;
+        Some((RoundKind::PlayIn, winner))
+    };
+    let next_round = (1..=bracket.field.round_count()).find(|round_num| {
+        bracket.rounds[&RoundKind::Round(*round_num)]
+            .matchups
+            .iter()
+            .any(|m| m.includes_team(team.name()) && !m.completed())
+    });
+    let (chances, opponents) = match next_round {
+        Some(next_round) => {
+            let probs = analytic::round_probabilities(&bracket, &matrix)?;
+            let chances = (next_round..=bracket.field.round_count())
+                .map(|round_num| (round_num, probs.win_probability(team.name(), round_num)))
+                .collect::<Vec<_>>();
+            let mut opponents = analytic::next_opponents(&bracket, &matrix, team.name())?;
+            opponents.truncate(NEXT_OPPONENTS_SHOWN);
+            (chances, opponents)
+        }
+        None => (vec![], vec![]),
+    };
+
+    if output::json() {
+        return output::emit(&json!({
+            "team": team,
+            "lost_to": lost.map(|(round, winner)| json!({ "round": round, "winner": winner })),
+            "next_round": next_round,
+            "chances": chances
+                .iter()
+                .map(|(round_num, prob)| json!({ "round": round_num, "probability": prob }))
+                .collect::<Vec<_>>(),
+            "next_opponents": opponents
+                .iter()
+                .map(|(opponent, prob)| json!({ "team": opponent, "probability": prob }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    println!("{}", team.name());
+    println!("Seed {} in the {} region", team.seed.0, team.region);
+    if let Some(rating) = team.rating {
+        println!("538 power rating {:.1}", rating);
+    }
+    match (lost, next_round) {
+        (Some((round, winner)), _) => {
+            println!("Lost to {} in the {}", winner, bracket.round_name(round))
+        }
+        (None, Some(round_num)) => println!(
+            "Alive, next game in the {}",
+            bracket.round_name(RoundKind::Round(round_num))
+        ),
+        (None, None) => println!("Won the title"),
+    }
+    if next_round.is_some() {
+        println!("Chance of winning in");
+        for (round_num, prob) in &chances {
+            println!(
+                "  {:<12} {:>5.1}%",
+                bracket.round_name(RoundKind::Round(*round_num)),
+                prob * 100.
+            );
+        }
+        println!("Most likely next opponents");
+        for (opponent, prob) in &opponents {
+            println!("  {:<24} {:>5.1}%", opponent, prob * 100.);
+        }
+    }
+    Ok(())
+}
+
+async fn list_teams(driver: &Session, args: &ListTeamsArgs) -> anyhow::Result<()> {
+    let teams = load_teams()?;
+    if args.conference.is_some() && teams.iter().all(|team| team.conference.is_none()) {
+        log::warn!("No conferences in the teams table, re-run write-teams-table to add them");
+    }
+    let bracket = simulate::cached_bracket(driver).await?;
+    let eliminated = bracket.eliminated_teams();
+    // First Four losers never make it into the bracket
+    let in_bracket = bracket.rounds[&RoundKind::Round(1)]
+        .matchups
+        .iter()
+        .flat_map(|m| m.teams())
+        .collect::<HashSet<_>>();
+
+    let mut matching = teams
+        .iter()
+        .filter(|team| matches_filters(team, args))
+        .map(|team| {
+            let alive = in_bracket.contains(team.name()) && !eliminated.contains(team.name());
+            (team, alive)
+        })
+        .filter(|(_, alive)| !args.alive || *alive)
+        .collect::<Vec<_>>();
+    matching.sort_by_key(|(team, _)| (team.region.to_ind(), team.seed));
+
+    println!(
+        "{:<24} {:>4} {:<8} {:<16} {:>6} Status",
+        "Team", "Seed", "Region", "Conference", "Rating"
+    );
+    for (team, alive) in &matching {
+        let rating = team
+            .rating
+            .map(|rating| format!("{:.1}", rating))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<24} {:>4} {:<8} {:<16} {:>6} {}",
+            team.name(),
+            team.seed.0,
+            team.region.to_string(),
+            team.conference.as_deref().unwrap_or("-"),
+            rating,
+            if *alive { "Alive" } else { "Out" }
+        );
+    }
+    log::info!("{} matching teams", matching.len());
+    Ok(())
+}
+
+/// Whether a team passes the region, seed and conference filters
+fn matches_filters(team: &Team, args: &ListTeamsArgs) -> bool {
+    if let Some(region) = args.region {
+        if team.region != region {
+            return false;
+        }
+    }
+    if let Some(range) = &args.seed_range {
+        if !range.contains(&team.seed.0) {
+            return false;
+        }
+    }
+    match (&args.conference, &team.conference) {
+        (Some(wanted), Some(conference)) => conference.eq_ignore_ascii_case(wanted),
+        (Some(_), None) => false,
+        (None, _) => true,
+    }
+}
+
+/// Parse a seed range like `1-4`, or a single seed like `12`
+fn parse_seed_range(s: &str) -> anyhow::Result<RangeInclusive<u8>> {
+    let (low, high) = s.split_once('-').unwrap_or((s, s));
+    let (low, high) = (low.trim().parse()?, high.trim().parse()?);
+    if !(1..=high).contains(&low) || high > 16 {
+        return Err(anyhow!("Seed range must be within 1-16"));
+    }
+    Ok(low..=high)
+}
+
+async fn scrape_public_picks(driver: &Session) -> anyhow::Result<()> {
+    let picks = public_picks::scrape_public_picks(driver, &load_teams()?).await?;
+    let path = season::data_path(PUBLIC_PICKS_PATH);
+    picks.save(&path)?;
+    log::info!(
+        "Saved public picks for {} teams to {}",
+        picks.picks.len(),
+        path
+    );
+    if output::json() {
+        output::emit(&picks)?;
+    }
+    Ok(())
+}
+
+async fn scrape_probabilities(driver: &Session, args: &ScrapeArgs) -> anyhow::Result<()> {
+    if args.source != ScrapeSource::Payload {
+        return resume_scrape(slice::from_ref(driver)).await;
+    }
+    let matrix = payload::scrape_payload_probabilities(driver).await?;
+    save_probabilities(&matrix)
+}
+
+/// Save probabilities for every pair of teams from their ratings in the teams table
+fn rating_probabilities() -> anyhow::Result<()> {
+    let teams = load_teams()?;
+    let ratings = teams
+        .iter()
+        .filter_map(|team| team.rating.map(|rating| (team.name(), rating)))
+        .collect::<Vec<_>>();
+    if ratings.len() < teams.len() {
+        return Err(anyhow!(
+            "Only {} of {} teams have a rating, re-run write-teams-table to add them",
+            ratings.len(),
+            teams.len()
+        ));
+    }
+    let matrix = ProbabilityMatrix::from_ratings(&ratings);
+    audit::record_ratings("ratings", &matrix, &ratings);
+    save_probabilities(&matrix)
+}
+
+/// Save probabilities for every pair of teams from a file of ratings of their own model
+fn rating_file_probabilities(path: &Path, args: &ScrapeArgs) -> anyhow::Result<()> {
+    let scale = args
+        .rating_scale
+        .unwrap_or_else(|| args.rating_formula.default_scale());
+    if scale <= 0. {
+        return Err(anyhow!("--rating-scale must be positive"));
+    }
+    let teams = load_teams()?;
+    let ratings = ratings::load_ratings(path, &teams)?;
+    let matrix = ratings::rating_matrix(&ratings, args.rating_formula, scale);
+    audit::record_ratings(&format!("ratings:{}", path.display()), &matrix, &ratings);
+    save_probabilities(&matrix)
+}
+
+fn seed_probabilities() -> anyhow::Result<()> {
+    let teams = load_teams()?;
+    let matrix = SeedHistory::default().matrix(&teams);
+    record_seeds(&matrix, &teams);
+    save_probabilities(&matrix)
+}
+
+/// Record probabilities from the seed history in any audit log, with the seeds they came from
+fn record_seeds(matrix: &ProbabilityMatrix, teams: &[Team]) {
+    audit::record_matrix("seeds", matrix, |name| match find_team(teams, name) {
+        Some(team) => format!("seed {}", team.seed.0),
+        None => name.to_string(),
+    });
+}
+
+async fn resume_scrape(drivers: &[Session]) -> anyhow::Result<()> {
+    let mut matrix =
+        ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH)).unwrap_or_default();
+    if !matrix.is_empty() {
+        log::info!("Resuming with {} pairs already scraped", matrix.len());
+    }
+    simulate::scrape_probabilities(drivers, &mut matrix).await?;
+    if output::json() {
+        output::emit(&matrix)?;
+    }
+    Ok(())
+}
+
+fn save_probabilities(matrix: &ProbabilityMatrix) -> anyhow::Result<()> {
+    let path = season::data_path(PROBABILITIES_PATH);
+    matrix.save(&path)?;
+    log::info!("Saved {} pairs to {}", matrix.len(), path);
+    if output::json() {
+        output::emit(matrix)?;
+    }
+    Ok(())
+}
+
+/// Scrape probabilities with several browser sessions at once, closing them all afterwards
+async fn scrape_in_parallel(global: &GlobalOpts, sessions: usize) -> anyhow::Result<()> {
+    if global.reuse_session {
+        return Err(anyhow!(
+            "--reuse-session keeps a single session, use --sessions 1"
+        ));
+    }
+    if global.record.is_some() || global.replay.is_some() {
+        return Err(anyhow!(
+            "A cassette holds a single session, use --sessions 1 with --record or --replay"
+        ));
+    }
+    let chromedriver = launch_chromedriver(global).await?;
+    let mut drivers = vec![];
+    for _ in 0..sessions {
+        match connect(global, chromedriver.as_ref()).await {
+            Ok(driver) => drivers.push(driver),
+            Err(e) => {
+                log::error!("Could not open {} sessions: {}", sessions, e);
+                break;
+            }
+        }
+    }
+    let res = if drivers.is_empty() {
+        Err(anyhow!("Could not open any browser sessions"))
+    } else {
+        log::info!("Scraping with {} sessions", drivers.len());
+        resume_scrape(&drivers).await
+    };
+    for driver in drivers {
+        driver.quit().await?;
+    }
+    res
+}
+
+async fn advancement(driver: &Session) -> anyhow::Result<()> {
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    print_advancement(&analytic::round_probabilities(&actual, &matrix)?)
+}
+
+async fn forecast_advancement(input: Option<&Path>) -> anyhow::Result<()> {
+    print_advancement(&load_forecast(input).await?)
+}
+
+fn print_advancement(probs: &RoundProbabilities) -> anyhow::Result<()> {
+    if output::json() {
+        return output::emit(probs);
+    }
+    report::advancement_report(probs);
+    Ok(())
+}
+
+async fn scrape_forecast_table(driver: &Session) -> anyhow::Result<()> {
+    let probs = scrape_round_chances(driver).await?;
+    let path = season::data_path(forecast::FORECAST_TABLE_PATH);
+    probs.save(&path)?;
+    log::info!("Saved the forecast table to {}", path);
+    print_advancement(&probs)
+}
+
+async fn calibration(driver: &Session, args: &CalibrationArgs) -> anyhow::Result<()> {
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let hover = analytic::round_probabilities(&actual, &matrix)?;
+    let table = scrape_round_chances(driver).await?;
+    let missing = table
+        .probs
+        .keys()
+        .filter(|team| !hover.probs.contains_key(*team))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        log::warn!("Not in the bracket: {}", missing.join(", "));
+    }
+    let tolerance = args.tolerance / 100.;
+    let gaps = report::calibration_gaps(&hover, &table, tolerance);
+    if output::json() {
+        return output::emit(&gaps);
+    }
+    report::calibration_report(&gaps, tolerance);
+    Ok(())
+}
+
+async fn load_forecast(input: Option<&Path>) -> anyhow::Result<RoundProbabilities> {
+    let data = match input {
+        Some(path) => fs::read_to_string(path)
+            .with_context(|| format!("Could not read forecast {}", path.display()))?,
+        None => forecast::download_forecast().await?,
+    };
+    forecast::parse_forecast(&data)
+}
+
+async fn leverage(driver: &Session, args: &LeverageArgs) -> anyhow::Result<()> {
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    print_leverage(&analytic::round_probabilities(&actual, &matrix)?, args)
+}
+
+async fn forecast_leverage(args: &LeverageArgs) -> anyhow::Result<()> {
+    print_leverage(&load_forecast(args.input.as_deref()).await?, args)
+}
+
+fn print_leverage(probs: &RoundProbabilities, args: &LeverageArgs) -> anyhow::Result<()> {
+    if let Some(round_num) = args.round {
+        if !(1..=6).contains(&round_num) {
+            return Err(anyhow!("--round must be between 1 and 6"));
+        }
+    }
+    let picks = PublicPicks::load(season::data_path(PUBLIC_PICKS_PATH))
+        .context("No public picks found, run scrape-public-picks first")?;
+    if output::json() {
+        return output::emit(&report::leverage_picks(probs, &picks, args.round));
+    }
+    report::leverage_report(probs, &picks, args.round);
+    Ok(())
+}
+
+async fn most_likely(driver: &Session) -> anyhow::Result<()> {
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let (bracket, prob) = analytic::most_probable_bracket(&actual, &matrix)?;
+    log::info!("Most likely bracket: {}", bracket);
+    log::info!("Chance of being exactly right: {:e}", prob);
+    let path = season::data_path(MOST_LIKELY_PATH);
+    bracket.save(&path)?;
+    log::info!("Saved bracket to {}", path);
+    if output::json() {
+        output::emit(&json!({ "bracket": bracket, "probability": prob }))?;
+    }
+    Ok(())
+}
+
+async fn build_bracket(driver: &Session, args: &BuildBracketArgs) -> anyhow::Result<()> {
+    let scoring = args.scoring.scoring()?;
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let bracket = strategy::build_bracket(args.strategy, &actual, &matrix, &scoring)?;
+    if output::json() {
+        output::emit(&bracket)?;
+    } else {
+        print_bracket(&bracket, args.display);
+    }
+    let path = season::data_path(BRACKET_PATH);
+    bracket.save(&path)?;
+    log::info!("Saved bracket to {}", path);
+    Ok(())
+}
+
+async fn stands(driver: &Session, args: &StandsArgs) -> anyhow::Result<()> {
+    let scoring = args.scoring.scoring()?;
+    let path = &bracket_path(&args.bracket);
+    let bracket = Tournament::load(path)
+        .with_context(|| format!("Could not load bracket {}", path.display()))?;
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let (most_likely, _) = analytic::most_probable_bracket(&actual, &matrix)?;
+    let stands = scoring::stands(&bracket, &most_likely, &scoring);
+    if output::json() {
+        return output::emit(&stands);
+    }
+    report::stands_report(
+        &stands,
+        &analytic::round_probabilities(&actual, &matrix)?,
+        &bracket.field,
+        scoring.max_points(&bracket.field),
+    );
+    Ok(())
+}
+
+async fn record_results(driver: &Session) -> anyhow::Result<()> {
+    let actual = simulate::current_bracket(driver).await?;
+    let path = season::data_path(RESULTS_HISTORY_PATH);
+    let new = results::record_results(&actual, &path)?;
+    for record in &new {
+        log::info!(
+            "{}: {} beat {}",
+            RoundKind::Round(record.round),
+            record.winner,
+            record.loser
+        );
+    }
+    log::info!("Recorded {} new results to {}", new.len(), path);
+    if output::json() {
+        output::emit(&new)?;
+    }
+    Ok(())
+}
+
+async fn node_ids(driver: &Session) -> anyhow::Result<()> {
+    let ids = simulate::node_ids(driver).await?;
+    let failed = ids.iter().filter(|node| node.parsed.is_err()).count();
+    if output::json() {
+        output::emit(&ids)?;
+    } else {
+        println!("{:<40} {:<24} Round", "Id", "Team");
+        for node in &ids {
+            match &node.parsed {
+                Ok((team, round)) => {
+                    println!("{:<40} {:<24} {}", node.id, team, round)
+                }
+                Err(e) => println!("{:<40} UNPARSED: {}", node.id, e),
+            }
+        }
+    }
+    if failed > 0 {
+        log::warn!("{} of {} node ids could not be read", failed, ids.len());
+    } else {
+        log::info!("Read all {} node ids", ids.len());
+    }
+    Ok(())
+}
+
+async fn evaluate(
+    driver: &Session,
+    args: &EvaluateArgs,
+    print: EvaluationReport,
+) -> anyhow::Result<()> {
+    let actual = simulate::cached_bracket(driver).await?;
+    print_evaluation(&actual, args, print)
+}
+
+fn evaluate_results(args: &EvaluateArgs, print: EvaluationReport) -> anyhow::Result<()> {
+    let path = args
+        .input
+        .as_ref()
+        .context("--input must point to the results")?;
+    let actual = Tournament::load(path)
+        .with_context(|| format!("Could not load results {}", path.display()))?;
+    print_evaluation(&actual, args, print)
+}
+
+fn print_evaluation(
+    actual: &Tournament,
+    args: &EvaluateArgs,
+    print: EvaluationReport,
+) -> anyhow::Result<()> {
+    let (rounds, overall) = match args.predictions {
+        Predictions::Matrix => {
+            let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+                .context("No scraped probabilities found, run scrape-probabilities first")?;
+            evaluate::evaluate(actual, |team1, team2| matrix.get(team1, team2))
+        }
+        Predictions::Kenpom => {
+            let path = args
+                .efficiency
+                .as_ref()
+                .context("--efficiency must point to the ratings")?;
+            let efficiencies = Efficiencies::load(path)?;
+            evaluate::evaluate(actual, |team1, team2| {
+                efficiencies.win_probability(team1, team2)
+            })
+        }
+        Predictions::Odds => {
+            let path = args
+                .odds
+                .as_ref()
+                .context("--odds must point to the moneylines")?;
+            let odds = Odds::load(path)?;
+            evaluate::evaluate(actual, |team1, team2| odds.win_probability(team1, team2))
+        }
+        Predictions::Seeds => {
+            let prior = SeedPrior::new(&load_teams()?);
+            evaluate::evaluate(actual, |team1, team2| prior.win_probability(team1, team2))
+        }
+    };
+    if overall.missing > 0 {
+        log::warn!("{} decided games have no prediction", overall.missing);
+    }
+    if output::json() {
+        let rounds = rounds
+            .iter()
+            .map(|(round, evaluation)| (round.to_string(), evaluation_json(evaluation)))
+            .collect::<serde_json::Map<_, _>>();
+        return output::emit(&json!({
+            "rounds": rounds,
+            "overall": evaluation_json(&overall),
+        }));
+    }
+    print(&rounds, &overall);
+    Ok(())
+}
+
+fn evaluation_json(evaluation: &Evaluation) -> serde_json::Value {
+    json!({
+        "games": evaluation.games,
+        "missing": evaluation.missing,
+        "brier": evaluation.brier(),
+        "log_loss": evaluation.log_loss(),
+        "surprisal": evaluation.surprisal(),
+        "expected_surprisal": evaluation.expected_surprisal(),
+        "madness": evaluation.madness(),
+    })
+}
+
+fn backtest(args: &BacktestArgs) -> anyhow::Result<()> {
+    if args.sharpen <= 0. {
+        return Err(anyhow!("--sharpen must be positive"));
+    }
+    let scoring = args.scoring.scoring()?;
+    let mut sampler = args.sampling.sampler();
+    let runs = args.sampling.runs;
+    let scores = backtest::load_seasons(&args.history_dir)?
+        .iter()
+        .map(|season| backtest::backtest_season(season, &scoring, runs, args.sharpen, &mut sampler))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if output::json() {
+        return output::emit(&scores);
+    }
+    report::backtest_report(&scores);
+    Ok(())
+}
+
+async fn portfolio(driver: &Session, args: &PortfolioArgs) -> anyhow::Result<()> {
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH))
+        .context("No scraped probabilities found, run scrape-probabilities first")?;
+    let actual = simulate::cached_bracket(driver).await?;
+    let entries = portfolio::diversified_entries(&actual, &matrix, args.entries)?;
+
+    fs::create_dir_all(&args.brackets_dir)?;
+    let mut champions = HashSet::new();
+    let mut covered = 0.;
+    let mut paths = vec![];
+    for (i, entry) in entries.iter().enumerate() {
+        let path = args.brackets_dir.join(format!("entry-{}.json", i + 1));
+        entry.bracket.save(&path)?;
+        if !output::json() {
+            println!(
+                "Entry {}: {} ({:.1}%), Final Four {} -> {}",
+                i + 1,
+                entry.champion,
+                entry.title_prob * 100.,
+                entry.final_four.join(", "),
+                path.display()
+            );
+        }
+        if champions.insert(entry.champion.clone()) {
+            covered += entry.title_prob;
+        }
+        paths.push(path);
+    }
+    if output::json() {
+        let entries = entries
+            .iter()
+            .zip(&paths)
+            .map(|(entry, path)| json!({ "path": path, "entry": entry }))
+            .collect::<Vec<_>>();
+        return output::emit(&json!({ "entries": entries, "champion_coverage": covered }));
+    }
+    println!(
+        "Chance one of the entries has the right champion: {:.1}%",
+        covered * 100.
+    );
+    Ok(())
+}
+
+async fn serve(global: &GlobalOpts, args: &ServeArgs) -> anyhow::Result<()> {
+    let options = simulate_options(&args.simulate)?;
+    #[cfg(feature = "grpc")]
+    let grpc_options = match args.grpc_port {
+        Some(port) => Some((simulate_options(&args.simulate)?, port)),
+        None => None,
+    };
+    let chromedriver = launch_chromedriver(global).await?;
+    let driver = Arc::new(Mutex::new(connect(global, chromedriver.as_ref()).await?));
+    #[cfg(feature = "grpc")]
+    let res = match grpc_options {
+        Some((grpc_options, grpc_port)) => tokio::try_join!(
+            server::serve(driver.clone(), options, args.port),
+            grpc::serve(driver.clone(), grpc_options, grpc_port)
+        )
+        .map(|_| ()),
+        None => server::serve(driver.clone(), options, args.port).await,
+    };
+    #[cfg(not(feature = "grpc"))]
+    let res = server::serve(driver.clone(), options, args.port).await;
+    // The server has shut down, so nothing else holds the driver
+    let driver = Arc::try_unwrap(driver)
+        .map_err(|_| anyhow!("Driver is still in use"))?
+        .into_inner();
+    // The task's own error is the more useful one to report
+    let closed = close(driver, global).await;
+    res.and(closed)
+}
+
+fn simulate_options(args: &SimulateArgs) -> anyhow::Result<SimulateOptions> {
+    if !(1..=6).contains(&args.start_round) {
+        return Err(anyhow!("--start-round must be between 1 and 6"));
+    }
+    if !(args.start_round..=6).contains(&args.through_round) {
+        return Err(anyhow!(
+            "--through-round must be between --start-round and 6"
+        ));
+    }
+    let picks = match &args.picks {
+        Some(path) if args.start_round > 1 => Some(
+            Tournament::load(path)
+                .with_context(|| format!("Could not load picks {}", path.display()))?,
+        ),
+        _ => None,
+    };
+    let saved = if args.resume {
+        let path = season::data_path(BRACKET_PATH);
+        Some(
+            Tournament::load(&path)
+                .with_context(|| format!("No saved bracket to resume from at {}", path))?,
+        )
+    } else {
+        None
+    };
+    Ok(SimulateOptions {
+        start_round: args.start_round,
+        picks,
+        through_round: args.through_round,
+        stream_games: args.stream_games,
+        saved,
+        best_effort: args.best_effort,
+        ..prediction_options(
+            &args.predictions,
+            &args.sampling,
+            args.sharpen,
+            args.games_format,
+        )?
+    })
+}
+
+/// Simulation options for predicting and drawing games, simulating the whole tournament
+fn prediction_options(
+    predictions: &PredictionArgs,
+    sampling: &SamplingArgs,
+    sharpen: f64,
+    games_format: GamesFormat,
+) -> anyhow::Result<SimulateOptions> {
+    if sharpen <= 0. {
+        return Err(anyhow!("--sharpen must be positive"));
+    }
+    let weights = source_weights(predictions)?;
+    Ok(SimulateOptions {
+        odds: predictions.odds.as_deref().map(Odds::load).transpose()?,
+        efficiencies: predictions
+            .efficiency
+            .as_deref()
+            .map(Efficiencies::load)
+            .transpose()?,
+        seed_prior: if weights.seeds > 0. {
+            Some(SeedPrior::new(&load_teams()?))
+        } else {
+            None
+        },
+        weights,
+        overrides: predictions
+            .overrides
+            .as_deref()
+            .map(MatchupOverrides::load)
+            .transpose()?,
+        adjustments: adjustments(predictions)?,
+        sharpen,
+        runs: sampling.runs,
+        rng: sampling.rng,
+        seed: sampling.seed,
+        sampling: sampling.sampling,
+        games_format,
+        ..Default::default()
+    })
+}
+
+/// Weights of the prediction sources, from --weights or the deprecated --odds-weight
+fn source_weights(predictions: &PredictionArgs) -> anyhow::Result<SourceWeights> {
+    let odds_weight = match predictions.odds_weight {
+        Some(odds_weight) => odds_weight,
+        None => return Ok(predictions.weights),
+    };
+    if !(0. ..=1.).contains(&odds_weight) {
+        return Err(anyhow!("--odds-weight must be between 0 and 1"));
+    }
+    let weights = format!("538={},odds={}", 1. - odds_weight, odds_weight);
+    log::warn!("--odds-weight is deprecated, use --weights {}", weights);
+    weights.parse()
+}
+
+/// Adjustments from the --adjustments file and any --boost, if either was given
+fn adjustments(predictions: &PredictionArgs) -> anyhow::Result<Option<Adjustments>> {
+    let mut adjustments = match &predictions.adjustments {
+        Some(path) => Adjustments::load(path)?,
+        None if predictions.boost.is_empty() => return Ok(None),
+        None => Adjustments::default(),
+    };
+    for boost in &predictions.boost {
+        adjustments.add_boost(boost);
+    }
+    Ok(Some(adjustments))
+}
+
+fn import_espn(args: &ImportEspnArgs) -> anyhow::Result<()> {
+    let paths = espn::write_entries(&args.input, load_teams()?, &args.brackets_dir)?;
+    if output::json() {
+        output::emit(&paths)?;
+    }
+    Ok(())
+}
+
+fn export_picks(args: &ExportPicksArgs) -> anyhow::Result<()> {
+    let path = &bracket_path(&args.bracket);
+    let bracket = Tournament::load(path)
+        .with_context(|| format!("Could not load bracket {}", path.display()))?;
+    let picks = pick_export::format_picks(&bracket, args.format, &args.entry)?;
+    match &args.output {
+        Some(out) => {
+            fs::write(out, &picks)
+                .with_context(|| format!("Could not write picks to {}", out.display()))?;
+            log::info!("Wrote the picks of {} to {}", path.display(), out.display());
+            if output::json() {
+                output::emit(&json!({ "written": out }))?;
+            }
+        }
+        None if output::json() => output::emit(&json!({ "picks": picks }))?,
+        None => print!("{}", picks),
+    }
+    Ok(())
+}
+
+async fn submit_bracket(driver: &Session, args: &SubmitArgs) -> anyhow::Result<()> {
+    let path = &bracket_path(&args.bracket);
+    let bracket = Tournament::load(path)
+        .with_context(|| format!("Could not load bracket {}", path.display()))?;
+    if !(1..=6).contains(&args.first_round) {
+        return Err(anyhow!("--first-round must be between 1 and 6"));
+    }
+    submit::submit_bracket(driver, args.pool_site, &bracket, args.first_round).await?;
+    if output::json() {
+        output::emit(&json!({ "submitted": path }))?;
+    }
+    Ok(())
+}
+
+fn futures_report(args: &FuturesArgs) -> anyhow::Result<()> {
+    let summary = SimulationSummary::load(season::data_path(SUMMARY_PATH))
+        .context("No simulation summary found, run the simulation first")?;
+    let futures = Futures::load(&args.input)?;
+    if output::json() {
+        return output::emit(&report::futures_comparisons(&summary, &futures));
+    }
+    report::futures_report(&summary, &futures);
+    Ok(())
+}
+
+fn parlays(args: &ParlayArgs) -> anyhow::Result<()> {
+    let summary = SimulationSummary::load(season::data_path(SUMMARY_PATH))
+        .context("No simulation summary found, run the simulation first")?;
+    let odds = Odds::load(&args.input)?;
+    let suggestions = report::parlay_suggestions(&summary, &odds, args.max_legs);
+    if output::json() {
+        return output::emit(&suggestions);
+    }
+    report::parlay_report(&suggestions);
+    Ok(())
+}
+
+fn kelly(args: &KellyArgs) -> anyhow::Result<()> {
+    if !(args.fraction > 0. && args.fraction <= 1.) {
+        return Err(anyhow!("--fraction must be above 0 and at most 1"));
+    }
+    let summary = SimulationSummary::load(season::data_path(SUMMARY_PATH))
+        .context("No simulation summary found, run the simulation first")?;
+    let odds = Odds::load(&args.input)?;
+    let bets = report::single_bets(&summary, &odds);
+    let stakes = report::kelly_stakes(&bets, args.fraction, args.bankroll);
+    if output::json() {
+        return output::emit(&stakes);
+    }
+    report::kelly_report(&stakes, args.fraction);
+    Ok(())
+}
+
+fn calcutta(args: &CalcuttaArgs) -> anyhow::Result<()> {
+    let payouts = CalcuttaPayouts::from_percents(&args.calcutta_payouts)?;
+    let summary = SimulationSummary::load(season::data_path(SUMMARY_PATH))
+        .context("No simulation summary found, run the simulation first")?;
+    if output::json() {
+        return output::emit(&report::calcutta_values(&summary, &payouts, args.pot));
+    }
+    report::calcutta_report(&summary, &payouts, args.pot);
+    Ok(())
+}
+
+/// The bracket given, or else the last one simulated or built
+fn bracket_path(given: &Option<PathBuf>) -> PathBuf {
+    given
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(season::data_path(BRACKET_PATH)))
+}
+
+fn cinderellas(args: &CinderellaArgs) -> anyhow::Result<()> {
+    let path = &bracket_path(&args.bracket);
+    let bracket = Tournament::load(path)
+        .with_context(|| format!("Could not load bracket {}", path.display()))?;
+    print_cinderellas(&bracket, args)
+}
+
+fn print_cinderellas(bracket: &Tournament, args: &CinderellaArgs) -> anyhow::Result<()> {
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH)).ok();
+    let cinderellas = report::cinderellas(bracket, matrix.as_ref(), args.min_seed);
+    if output::json() {
+        return output::emit(&cinderellas);
+    }
+    report::cinderella_report(&cinderellas);
+    Ok(())
+}
+
+fn chalk_score(args: &ChalkScoreArgs) -> anyhow::Result<()> {
+    let brackets = match &args.bracket {
+        Some(path) => vec![(
+            path.display().to_string(),
+            Tournament::load(path)
+                .with_context(|| format!("Could not load bracket {}", path.display()))?,
+        )],
+        None => scoring::load_brackets(&args.brackets_dir)?,
+    };
+    let matrix = ProbabilityMatrix::load(season::data_path(PROBABILITIES_PATH)).ok();
+    let scores = brackets
+        .iter()
+        .map(|(name, bracket)| (name.clone(), scoring::chalk_score(bracket, matrix.as_ref())))
+        .collect::<Vec<_>>();
+    if output::json() {
+        return output::emit(&scores);
+    }
+    report::chalk_report(&scores);
+    Ok(())
+}
+
+fn similarity(args: &SimilarityArgs) -> anyhow::Result<()> {
+    let scoring = args.scoring.scoring()?;
+    let path = &bracket_path(&args.bracket);
+    let bracket = Tournament::load(path)
+        .with_context(|| format!("Could not load bracket {}", path.display()))?;
+    let consensus_path = &args.consensus;
+    let consensus = Tournament::load(consensus_path)
+        .with_context(|| format!("Could not load bracket {}", consensus_path.display()))?;
+    let similarity = scoring::similarity(&bracket, &consensus, &scoring);
+    if output::json() {
+        return output::emit(&similarity);
+    }
+    report::similarity_report(&similarity, &bracket.field);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scrape_sources() {
+        assert_eq!(
+            "payload".parse::<ScrapeSource>().unwrap(),
+            ScrapeSource::Payload
+        );
+        assert_eq!(
+            "ratings".parse::<ScrapeSource>().unwrap(),
+            ScrapeSource::Ratings
+        );
+        assert_eq!(
+            "ratings:kenpom.csv".parse::<ScrapeSource>().unwrap(),
+            ScrapeSource::RatingsFile(PathBuf::from("kenpom.csv"))
+        );
+        for bad in ["ratings:", "rating:kenpom.csv", "elo", ""] {
+            assert!(bad.parse::<ScrapeSource>().is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::sample_bracket;
+use crate::probabilities::ProbabilityMatrix;
+use crate::rng::{RngKind, Sampler, Sampling};
+use crate::tournament::Tournament;
+
+/// Name of the manifest written next to generated brackets
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// How a batch of sampled brackets was generated, enough to draw any of them again exactly
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Manifest {
+    /// Version of this tool, since the sampling itself may change between versions
+    pub tool_version: String,
+    /// Hash of the starting bracket and head-to-head probabilities the brackets came from
+    pub source_hash: String,
+    pub rng: RngKind,
+    /// Each bracket is sampled game by game with probabilities sharpened by this factor
+    pub sharpen: f64,
+    pub brackets: Vec<ManifestEntry>,
+}
+
+/// One generated bracket
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    pub seed: u64,
+}
+
+/// Sample `count` brackets from the undecided games of `start` into `dir`, the i-th drawn
+/// with seed `seed + i`, and write a manifest of them alongside
+pub fn generate_brackets(
+    start: &Tournament,
+    matrix: &ProbabilityMatrix,
+    dir: &Path,
+    count: usize,
+    rng: RngKind,
+    seed: u64,
+    sharpen: f64,
+) -> anyhow::Result<Manifest> {
+    fs::create_dir_all(dir)?;
+    let mut manifest = Manifest {
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        source_hash: source_hash(start, matrix)?,
+        rng,
+        sharpen,
+        brackets: vec![],
+    };
+    for ind in 0..count {
+        let entry = ManifestEntry {
+            path: dir.join(format!("sampled-{}.json", ind + 1)),
+            seed: seed.wrapping_add(ind as u64),
+        };
+        draw(start, matrix, &manifest, &entry)?.save(&entry.path)?;
+        manifest.brackets.push(entry);
+    }
+    let path = dir.join(MANIFEST_FILE);
+    serde_json::to_writer_pretty(BufWriter::new(File::create(&path)?), &manifest)?;
+    log::info!(
+        "Wrote {} brackets and their manifest to {}",
+        count,
+        path.display()
+    );
+    Ok(manifest)
+}
+
+/// Draw the given bracket (numbered from 1) of a manifest again and save it to its path. Fails
+/// if the starting bracket or probabilities differ from the ones it was generated from.
+pub fn regenerate_bracket(
+    start: &Tournament,
+    matrix: &ProbabilityMatrix,
+    manifest_path: &Path,
+    number: usize,
+) -> anyhow::Result<PathBuf> {
+    let manifest: Manifest = serde_json::from_reader(BufReader::new(
+        File::open(manifest_path)
+            .with_context(|| format!("Could not open manifest {}", manifest_path.display()))?,
+    ))?;
+    let entry = number
+        .checked_sub(1)
+        .and_then(|ind| manifest.brackets.get(ind))
+        .ok_or_else(|| {
+            anyhow!(
+                "{} lists brackets 1 to {}, not {}",
+                manifest_path.display(),
+                manifest.brackets.len(),
+                number
+            )
+        })?;
+    if source_hash(start, matrix)? != manifest.source_hash {
+        return Err(anyhow!(
+            "The bracket or probabilities have changed since {} was written, so its brackets \
+             can't be drawn again",
+            manifest_path.display()
+        ));
+    }
+    if manifest.tool_version != env!("CARGO_PKG_VERSION") {
+        log::warn!(
+            "{} was written by version {}, brackets may differ",
+            manifest_path.display(),
+            manifest.tool_version
+        );
+    }
+    draw(start, matrix, &manifest, entry)?.save(&entry.path)?;
+    Ok(entry.path.clone())
+}
+
+fn draw(
+    start: &Tournament,
+    matrix: &ProbabilityMatrix,
+    manifest: &Manifest,
+    entry: &ManifestEntry,
+) -> anyhow::Result<Tournament> {
+    let mut sampler = Sampler::new(manifest.rng, Some(entry.seed), Sampling::Independent, 1);
+    sampler.start_run();
+    sample_bracket(start, matrix, manifest.sharpen, &mut sampler)
+}
+
+/// FNV-1a hash of the inputs as saved, which unlike std's hasher is the same on every build
+fn source_hash(start: &Tournament, matrix: &ProbabilityMatrix) -> anyhow::Result<String> {
+    let data = serde_json::to_vec(&(start, matrix))?;
+    let hash = data.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    Ok(format!("{:016x}", hash))
+}
@@ -0,0 +1,86 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use thirtyfour::error::WebDriverError;
+
+/// Probability scrapes finished, successful or not
+static SCRAPES: AtomicU64 = AtomicU64::new(0);
+/// Total time spent on those scrapes, in ms
+static SCRAPE_MS: AtomicU64 = AtomicU64::new(0);
+/// When the latest successful scrape finished, in seconds since the Unix epoch
+static LAST_SCRAPE_SECS: AtomicU64 = AtomicU64::new(0);
+/// Operations which failed because of an error from the WebDriver server
+static WEBDRIVER_ERRORS: AtomicU64 = AtomicU64::new(0);
+/// Simulated brackets played out
+static SIMULATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Record a finished probability scrape
+pub fn record_scrape(elapsed: Duration, succeeded: bool) {
+    SCRAPES.fetch_add(1, Ordering::Relaxed);
+    SCRAPE_MS.fetch_add(elapsed.as_millis() as u64, Ordering::Relaxed);
+    if succeeded {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        LAST_SCRAPE_SECS.store(now, Ordering::Relaxed);
+    }
+}
+
+/// Record a simulated bracket
+pub fn record_simulation() {
+    SIMULATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count the error if it came from the WebDriver server, passing the result through
+pub fn track_errors<T>(res: anyhow::Result<T>) -> anyhow::Result<T> {
+    if let Err(e) = &res {
+        if e.chain().any(|cause| cause.is::<WebDriverError>()) {
+            WEBDRIVER_ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    res
+}
+
+/// All metrics in the Prometheus text format
+pub fn render() -> String {
+    let load = |metric: &AtomicU64| metric.load(Ordering::Relaxed);
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(&str, String)]| {
+        // Writing to a String can't fail
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (suffix, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, suffix, value);
+        }
+    };
+    metric(
+        "march_madness_scrape_duration_seconds",
+        "summary",
+        "Time spent scraping head-to-head probabilities",
+        &[
+            ("_sum", format!("{:.3}", load(&SCRAPE_MS) as f64 / 1000.)),
+            ("_count", load(&SCRAPES).to_string()),
+        ],
+    );
+    metric(
+        "march_madness_last_successful_scrape_timestamp_seconds",
+        "gauge",
+        "When the latest successful probability scrape finished, 0 if none has",
+        &[("", load(&LAST_SCRAPE_SECS).to_string())],
+    );
+    metric(
+        "march_madness_webdriver_errors_total",
+        "counter",
+        "Operations which failed with an error from the WebDriver server",
+        &[("", load(&WEBDRIVER_ERRORS).to_string())],
+    );
+    metric(
+        "march_madness_simulations_total",
+        "counter",
+        "Simulated brackets played out",
+        &[("", load(&SIMULATIONS).to_string())],
+    );
+    out
+}
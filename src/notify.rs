@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use serde_json::json;
+
+use crate::teams::Team;
+use crate::tournament::{RoundKind, Seed, Tournament};
+
+/// Only upsets where the winner's seed is at least this much worse are worth a mention
+const NOTABLE_UPSET_GAP: u8 = 5;
+
+/// Format a short summary of a bracket for chat: champion, Final Four and notable upsets.
+/// Extra lines (e.g. pool standings) are appended at the end.
+pub fn bracket_message(bracket: &Tournament, teams: &[Team], extra: &[String]) -> String {
+    let seeds: HashMap<&str, Seed> = teams.iter().map(|t| (t.name(), t.seed)).collect();
+    let with_seed = |team: &str| match seeds.get(team) {
+        Some(seed) => format!("({}) {}", seed.0, team),
+        None => team.to_string(),
+    };
+
+    let mut lines = vec![];
+    let champion = bracket.champion();
+    if let Some(champion) = champion {
+        lines.push(format!("Champion: {}", with_seed(champion)));
+    }
+    let final_four = bracket
+        .final_four()
+        .into_iter()
+        .map(with_seed)
+        .collect::<Vec<_>>();
+    if !final_four.is_empty() {
+        lines.push(format!("Final Four: {}", final_four.join(", ")));
+    }
+
+    let mut upsets = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        for matchup in &bracket.rounds[&round].matchups {
+            let (teams, winner) = match (matchup.try_teams(), matchup.winning_team()) {
+                (Some(teams), Some(winner)) => (teams, winner),
+                _ => continue,
+            };
+            let loser = if teams[0] == winner {
+                teams[1]
+            } else {
+                teams[0]
+            };
+            if let (Some(w), Some(l)) = (seeds.get(winner), seeds.get(loser)) {
+                if w.0 >= l.0 + NOTABLE_UPSET_GAP {
+                    upsets.push(format!(
+                        "{}: {} over {}",
+                        bracket.round_name(round),
+                        with_seed(winner),
+                        with_seed(loser)
+                    ));
+                }
+            }
+        }
+    }
+    if !upsets.is_empty() {
+        lines.push("Notable upsets:".to_string());
+        lines.extend(upsets.into_iter().map(|u| format!("- {}", u)));
+    }
+    lines.extend(extra.iter().cloned());
+    lines.join("\n")
+}
+
+/// Post a message to a Slack or Discord incoming webhook
+pub async fn post_webhook(url: &str, message: &str) -> anyhow::Result<()> {
+    // Discord and Slack use different keys for the message body
+    let body = if url.contains("discord.com") || url.contains("discordapp.com") {
+        json!({ "content": message })
+    } else {
+        json!({ "text": message })
+    };
+    reqwest::Client::new()
+        .post(url)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()
+        .context("Webhook rejected the notification")?;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::teams::normalize_name;
+
+/// One row of a moneyline file, e.g. `Gonzaga,Georgia St,-5000,+1800`
+#[derive(Debug, Deserialize)]
+struct MoneylineRecord {
+    team1: String,
+    team2: String,
+    moneyline1: String,
+    moneyline2: String,
+}
+
+/// A game's moneylines as quoted, e.g. -5000 and +1800
+#[derive(Debug, Clone)]
+pub struct GameLine {
+    pub teams: [String; 2],
+    pub moneylines: [f64; 2],
+}
+
+/// Sportsbook moneylines converted to implied win probabilities
+#[derive(Debug, Default)]
+pub struct Odds {
+    /// Probability that the first team beats the second, keyed by normalized team names
+    games: HashMap<(String, String), f64>,
+    /// Every game in the file, in order, for betting on the quoted prices
+    pub lines: Vec<GameLine>,
+}
+
+impl Odds {
+    /// Load moneylines from a CSV with a `team1,team2,moneyline1,moneyline2` header
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = csv::Reader::from_path(path)
+            .with_context(|| format!("Could not open odds file {}", path.display()))?;
+        let mut games = HashMap::new();
+        let mut lines = vec![];
+        for record in reader.deserialize() {
+            let record: MoneylineRecord = record?;
+            let moneylines = [
+                parse_moneyline(&record.moneyline1)?,
+                parse_moneyline(&record.moneyline2)?,
+            ];
+            games.insert(
+                (normalize_name(&record.team1), normalize_name(&record.team2)),
+                implied_probability(moneylines[0], moneylines[1]),
+            );
+            lines.push(GameLine {
+                teams: [record.team1, record.team2],
+                moneylines,
+            });
+        }
+        Ok(Self { games, lines })
+    }
+
+    /// Implied probability that `team1` beats `team2`, None if there is no line for this game
+    pub fn win_probability(&self, team1: &str, team2: &str) -> Option<f64> {
+        let (team1, team2) = (normalize_name(team1), normalize_name(team2));
+        if let Some(prob) = self.games.get(&(team1.clone(), team2.clone())) {
+            Some(*prob)
+        } else {
+            self.games.get(&(team2, team1)).map(|prob| 1. - prob)
+        }
+    }
+}
+
+/// Futures markets on how far a team goes
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FuturesMarket {
+    /// Team reaches the Final Four
+    FinalFour,
+    /// Team wins the title
+    Champion,
+}
+
+impl FuturesMarket {
+    /// The round a team has to win to cash this bet
+    pub fn round_num(self) -> usize {
+        match self {
+            Self::FinalFour => 4,
+            Self::Champion => 6,
+        }
+    }
+
+    /// How many teams win this bet, which is what the implied probabilities should sum to
+    fn winners(self) -> f64 {
+        match self {
+            Self::FinalFour => 4.,
+            Self::Champion => 1.,
+        }
+    }
+}
+
+impl Display for FuturesMarket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FinalFour => write!(f, "Final Four"),
+            Self::Champion => write!(f, "Champion"),
+        }
+    }
+}
+
+/// One row of a futures file, e.g. `Gonzaga,champion,+350`
+#[derive(Debug, Deserialize)]
+struct FuturesRecord {
+    team: String,
+    market: FuturesMarket,
+    moneyline: String,
+}
+
+/// Sportsbook futures converted to implied probabilities
+#[derive(Debug, Default)]
+pub struct Futures {
+    /// Implied probability for each team with a line in each market, margin removed
+    pub markets: HashMap<FuturesMarket, Vec<(String, f64)>>,
+}
+
+impl Futures {
+    /// Load futures from a CSV with a `team,market,moneyline` header, where market is one of
+    /// `final_four` or `champion`
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = csv::Reader::from_path(path)
+            .with_context(|| format!("Could not open futures file {}", path.display()))?;
+        let mut markets: HashMap<_, Vec<_>> = HashMap::new();
+        for record in reader.deserialize() {
+            let record: FuturesRecord = record?;
+            let prob = raw_probability(parse_moneyline(&record.moneyline)?);
+            markets
+                .entry(record.market)
+                .or_default()
+                .push((record.team, prob));
+        }
+        // Books price futures so the probabilities sum to more than the number of winners
+        for (market, lines) in &mut markets {
+            let total: f64 = lines.iter().map(|(_, prob)| prob).sum();
+            let scale = market.winners() / total.max(market.winners());
+            for (_, prob) in lines {
+                *prob *= scale;
+            }
+        }
+        Ok(Self { markets })
+    }
+}
+
+/// Parse an American moneyline like "-150" or "+130"
+fn parse_moneyline(text: &str) -> anyhow::Result<f64> {
+    let line: f64 = text
+        .trim()
+        .trim_start_matches('+')
+        .parse()
+        .with_context(|| format!("Invalid moneyline {}", text))?;
+    if line.abs() < 100. {
+        return Err(anyhow!("Invalid moneyline {}", text));
+    }
+    Ok(line)
+}
+
+/// Probability implied by a single moneyline, including the book's margin
+fn raw_probability(line: f64) -> f64 {
+    if line < 0. {
+        -line / (-line + 100.)
+    } else {
+        100. / (line + 100.)
+    }
+}
+
+/// Total returned for each unit staked on a winning bet at this moneyline, stake included
+pub fn decimal_odds(line: f64) -> f64 {
+    1. / raw_probability(line)
+}
+
+/// Probability that the first side wins, with the book's margin removed by normalizing both
+/// sides to sum to 1
+pub fn implied_probability(line1: f64, line2: f64) -> f64 {
+    let (p1, p2) = (raw_probability(line1), raw_probability(line2));
+    p1 / (p1 + p2)
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+
+/// Whether tasks print a JSON document instead of tables and text
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Whether bracket output marks results with symbols as well as colors
+static SYMBOLS: AtomicBool = AtomicBool::new(false);
+
+/// How tasks print their results
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum OutputFormat {
+    /// Tables and text for reading in a terminal
+    Text,
+    /// One JSON document per task run on stdout, for scripts
+    Json,
+}
+
+pub fn set_format(format: OutputFormat) {
+    JSON.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+/// Whether to print JSON with [`emit`] rather than text
+pub fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// How winners and losers are told apart in printed brackets
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum ResultMarkers {
+    /// Winners in green and losers in red, or winners marked with a `*` without colors
+    Color,
+    /// Winners marked with ✓ and in bold, losers with ✗ and struck through, along with the
+    /// colors, so results can be told apart without seeing red and green
+    Symbols,
+}
+
+pub fn set_result_markers(markers: ResultMarkers) {
+    SYMBOLS.store(markers == ResultMarkers::Symbols, Ordering::Relaxed);
+}
+
+/// Whether to mark results with symbols and styling rather than colors alone
+pub fn symbols() -> bool {
+    SYMBOLS.load(Ordering::Relaxed)
+}
+
+/// Print a task's results as a single line of JSON on stdout
+pub fn emit<T: Serialize + ?Sized>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+
+use crate::teams::normalize_name;
+
+/// Explicit win probabilities for specific games, read from lines like
+/// `Gonzaga vs Memphis in round 2 => 0.55`. The round is optional, and lines starting with
+/// `#` are comments.
+#[derive(Debug, Default)]
+pub struct MatchupOverrides {
+    /// Chance the first team beats the second, keyed by normalized names and the round, or
+    /// None for any round
+    games: HashMap<(String, String, Option<usize>), f64>,
+}
+
+impl MatchupOverrides {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Could not read overrides file {}", path.display()))?;
+        let mut games = HashMap::new();
+        for (line_num, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (team1, team2, round_num, prob) = parse_line(line)
+                .with_context(|| format!("Bad override on line {}: {}", line_num + 1, line))?;
+            games.insert(
+                (normalize_name(team1), normalize_name(team2), round_num),
+                prob,
+            );
+        }
+        Ok(Self { games })
+    }
+
+    /// Overridden chance of `team1` beating `team2` in the round, preferring an override for
+    /// that round over one for any round. None if the game isn't overridden.
+    pub fn get(&self, team1: &str, team2: &str, round_num: usize) -> Option<f64> {
+        let (team1, team2) = (normalize_name(team1), normalize_name(team2));
+        [Some(round_num), None].iter().find_map(|round| {
+            if let Some(prob) = self.games.get(&(team1.clone(), team2.clone(), *round)) {
+                Some(*prob)
+            } else {
+                self.games
+                    .get(&(team2.clone(), team1.clone(), *round))
+                    .map(|prob| 1. - prob)
+            }
+        })
+    }
+}
+
+/// Split `A vs B in round N => p` into its parts
+fn parse_line(line: &str) -> anyhow::Result<(&str, &str, Option<usize>, f64)> {
+    let (game, prob) = line
+        .split_once("=>")
+        .ok_or_else(|| anyhow!("Expected `=>` followed by a probability"))?;
+    let prob = prob.trim().parse::<f64>()?;
+    if !(0. ..=1.).contains(&prob) {
+        return Err(anyhow!("Probability {} is not between 0 and 1", prob));
+    }
+    let (teams, round_num) = match game.rsplit_once(" in round ") {
+        Some((teams, round)) => (teams, Some(round.trim().parse::<usize>()?)),
+        None => (game, None),
+    };
+    let (team1, team2) = teams
+        .split_once(" vs ")
+        .ok_or_else(|| anyhow!("Expected two teams separated by `vs`"))?;
+    Ok((team1.trim(), team2.trim(), round_num, prob))
+}
@@ -0,0 +1,223 @@
+use anyhow::{anyhow, Context};
+
+use crate::selectors::selectors;
+use crate::tournament::RoundKind;
+
+/// Convert the 538 team name to an HTML-friendly name used in element classes
+pub fn construct_html_name(name: &str) -> String {
+    name.chars()
+        .filter(|&c| c == '-' || c.is_alphabetic())
+        .collect()
+}
+
+/// Id of a team's node for the given round, where the page numbers rounds by depth from the
+/// title game. For example, Kentucky in round 1 -> node-Kentucky-6
+pub fn node_id(team: &str, round_num: usize) -> String {
+    selectors().node_id(&construct_html_name(team), 7 - round_num)
+}
+
+/// For example, node-Kentucky-6 -> ("Kentucky", round 1). Depth 0 is the champion's node,
+/// read as round 7.
+pub fn extract_team_round_from_id(id: &str) -> anyhow::Result<(String, RoundKind)> {
+    let (team, depth_str) = selectors()
+        .split_node_id(id)
+        .ok_or_else(|| anyhow!("Unexpected format"))?;
+    let depth = depth_str.parse::<usize>()?;
+    if depth > 6 {
+        return Err(anyhow!("Unexpected depth {}", depth));
+    }
+    Ok((team.to_string(), RoundKind::Round(7 - depth)))
+}
+
+/// Parse a win% label such as "57%" or "99.6%" into a probability. Capped labels like ">99%"
+/// and "<1%" are taken as halfway to certain, since flattening them to 1 or 0 would make the
+/// favorite unbeatable in every later calculation.
+pub fn parse_win_probability(label: &str) -> anyhow::Result<f64> {
+    let percent = |s: &str| -> anyhow::Result<f64> {
+        s.trim_end_matches('%')
+            .trim()
+            .parse::<f64>()
+            .with_context(|| format!("Unexpected win percentage {}", label))
+    };
+    let percent = if let Some(bound) = label.strip_prefix('>') {
+        (percent(bound)? + 100.) / 2.
+    } else if let Some(bound) = label.strip_prefix('<') {
+        percent(bound)? / 2.
+    } else {
+        percent(label)?
+    };
+    Ok(percent / 100.)
+}
+
+/// Parse a team's chance of reaching a round from a cell of the teams table, which shows a
+/// check once the team is through and a dash once it's out
+pub fn parse_round_chance(cell: &str) -> anyhow::Result<f64> {
+    match cell.trim() {
+        "✓" => Ok(1.),
+        "" | "-" | "–" | "—" => Ok(0.),
+        percent => parse_win_probability(percent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    /// Team names as 538 writes them, with the html names the page uses for them
+    const TEAM_NAMES: [(&str, &str); 10] = [
+        ("Gonzaga", "Gonzaga"),
+        ("Saint Mary's (CA)", "SaintMarysCA"),
+        ("Texas A&M-Corpus Christi", "TexasAM-CorpusChristi"),
+        ("Miami (FL)", "MiamiFL"),
+        ("Saint Peter's", "SaintPeters"),
+        ("Loyola (IL)", "LoyolaIL"),
+        ("UCLA", "UCLA"),
+        ("North Carolina", "NorthCarolina"),
+        ("Cal State Fullerton", "CalStateFullerton"),
+        ("Jacksonville State", "JacksonvilleState"),
+    ];
+
+    /// Node ids seen on the page, with the team and round they stand for
+    const NODE_IDS: [(&str, &str, usize); 6] = [
+        ("node-Kentucky-6", "Kentucky", 1),
+        ("node-Gonzaga-5", "Gonzaga", 2),
+        ("node-SaintPeters-3", "SaintPeters", 4),
+        ("node-NorthCarolina-2", "NorthCarolina", 5),
+        ("node-Kansas-1", "Kansas", 6),
+        ("node-TexasAM-CorpusChristi-6", "TexasAM-CorpusChristi", 1),
+    ];
+
+    /// Characters to build random names from, covering what shows up in team names
+    const NAME_CHARS: &[u8] =
+        b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ &'.-()0123456789";
+
+    /// How many random cases each property is checked against
+    const CASES: usize = 1000;
+
+    fn random_name(rng: &mut StdRng) -> String {
+        let len = rng.gen_range(1..30);
+        (0..len)
+            .map(|_| NAME_CHARS[rng.gen_range(0..NAME_CHARS.len())] as char)
+            .collect()
+    }
+
+    #[test]
+    fn html_names_of_real_teams() {
+        for (name, html_name) in TEAM_NAMES {
+            assert_eq!(construct_html_name(name), html_name);
+        }
+    }
+
+    #[test]
+    fn html_names_keep_only_letters_and_hyphens() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..CASES {
+            let name = random_name(&mut rng);
+            let html_name = construct_html_name(&name);
+            assert!(html_name.chars().all(|c| c == '-' || c.is_alphabetic()));
+            assert_eq!(construct_html_name(&html_name), html_name);
+            let kept = name
+                .chars()
+                .filter(|&c| c == '-' || c.is_alphabetic())
+                .count();
+            assert_eq!(html_name.chars().count(), kept);
+        }
+    }
+
+    #[test]
+    fn real_node_ids() {
+        for (id, team, round_num) in NODE_IDS {
+            let (parsed_team, round) = extract_team_round_from_id(id).unwrap();
+            assert_eq!(parsed_team, team);
+            assert_eq!(round, RoundKind::Round(round_num));
+        }
+    }
+
+    #[test]
+    fn node_ids_round_trip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..CASES {
+            let name = random_name(&mut rng);
+            if construct_html_name(&name).is_empty() {
+                continue;
+            }
+            let round_num = rng.gen_range(1..=6);
+            let (team, round) = extract_team_round_from_id(&node_id(&name, round_num)).unwrap();
+            assert_eq!(team, construct_html_name(&name));
+            assert_eq!(round, RoundKind::Round(round_num));
+        }
+    }
+
+    #[test]
+    fn malformed_node_ids() {
+        for id in [
+            "",
+            "node",
+            "node-Kentucky",
+            "Kentucky-6",
+            "node-Kentucky-x",
+            "node-Kentucky-7",
+        ] {
+            assert!(extract_team_round_from_id(id).is_err(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn real_win_labels() {
+        for (label, prob) in [
+            ("57%", 0.57),
+            ("99.6%", 0.996),
+            ("3%", 0.03),
+            ("50%", 0.5),
+            (">99%", 0.995),
+            ("<1%", 0.005),
+        ] {
+            assert!((parse_win_probability(label).unwrap() - prob).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn win_labels_give_probabilities() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..CASES {
+            let tenths = rng.gen_range(0..=1000);
+            let percent = format!("{}.{}", tenths / 10, tenths % 10);
+            let prob = parse_win_probability(&format!("{}%", percent)).unwrap();
+            assert!((prob - tenths as f64 / 1000.).abs() < 1e-9);
+
+            // Capped labels land between the bound and certainty
+            let bound = tenths as f64 / 1000.;
+            let above = parse_win_probability(&format!(">{}%", percent)).unwrap();
+            let below = parse_win_probability(&format!("<{}%", percent)).unwrap();
+            assert!(above >= bound && above <= 1.);
+            assert!(below <= bound && below >= 0.);
+        }
+    }
+
+    #[test]
+    fn round_chances_of_decided_rounds() {
+        for (cell, prob) in [
+            ("✓", 1.),
+            (" — ", 0.),
+            ("", 0.),
+            ("<1%", 0.005),
+            ("42%", 0.42),
+        ] {
+            assert!(
+                (parse_round_chance(cell).unwrap() - prob).abs() < 1e-9,
+                "{}",
+                cell
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_win_labels() {
+        for label in ["", "%", "abc", "57 percent", ">", "<%", "5 7%"] {
+            assert!(parse_win_probability(label).is_err(), "{}", label);
+        }
+    }
+}
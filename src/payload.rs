@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use scraper::{Html, Selector};
+use serde_json::Value;
+use thirtyfour::By;
+
+use crate::gender::gender;
+use crate::probabilities::ProbabilityMatrix;
+use crate::selectors::selectors;
+use crate::session::Session;
+use crate::teams::{find_team, load_teams};
+use crate::{audit, metrics, preflight, throttle, timeouts};
+
+/// Load the 538 page once and turn the team ratings embedded in it into head-to-head
+/// probabilities for every pair of teams, without hovering over anything
+pub async fn scrape_payload_probabilities(driver: &Session) -> anyhow::Result<ProbabilityMatrix> {
+    let start = Instant::now();
+    let res = metrics::track_errors(read_payload(driver).await);
+    metrics::record_scrape(start.elapsed(), res.is_ok());
+    res
+}
+
+async fn read_payload(driver: &Session) -> anyhow::Result<ProbabilityMatrix> {
+    throttle::rate_limit().await;
+    preflight::load_page(driver).await?;
+    // The data is in place once the bracket has been drawn from it
+    driver
+        .wait_for(By::Css(&selectors().bracket), timeouts::element_timeout())
+        .await?;
+    throttle::rate_limit().await;
+    let html = driver.page_source().await?;
+    let ratings = parse_ratings(&html)?;
+    log::info!("Found ratings for {} teams in the page data", ratings.len());
+    ratings_matrix(&ratings)
+}
+
+/// Probabilities for every pair of rated teams in the bracket, named as in the teams table
+fn ratings_matrix(ratings: &BTreeMap<String, f64>) -> anyhow::Result<ProbabilityMatrix> {
+    let teams = load_teams()?;
+    let mut rated = vec![];
+    for (name, rating) in ratings {
+        match find_team(&teams, name) {
+            Some(team) => rated.push((team.name(), *rating)),
+            None => log::warn!("Could not match {} from the page data to a 538 team", name),
+        }
+    }
+    let matrix = ProbabilityMatrix::from_ratings(&rated);
+    audit::record_ratings("payload", &matrix, &rated);
+    Ok(matrix)
+}
+
+/// Pull the rating of each team in the current tournament from the latest forecast in the
+/// page's scripts. The data uses the same fields as the forecast CSV, either as a JSON script
+/// or assigned to a variable.
+pub fn parse_ratings(html: &str) -> anyhow::Result<BTreeMap<String, f64>> {
+    let parsed = Html::parse_document(html);
+    let scripts = Selector::parse("script").unwrap();
+    // Team name to the forecast date and rating
+    let mut latest: BTreeMap<String, (String, f64)> = BTreeMap::new();
+    for script in parsed.select(&scripts) {
+        let text = script.text().collect::<String>();
+        if let Some(value) = script_value(&text) {
+            collect_ratings(&value, &mut latest);
+        }
+    }
+    if latest.is_empty() {
+        return Err(anyhow!("No team ratings found in the page data"));
+    }
+    Ok(latest
+        .into_iter()
+        .map(|(name, (_, rating))| (name, rating))
+        .collect())
+}
+
+/// The JSON held by a script, which is either all JSON or starts with an assignment
+fn script_value(text: &str) -> Option<Value> {
+    let text = text.trim();
+    let json = match text.chars().next() {
+        Some('{') | Some('[') => text,
+        _ => text.split_once('=')?.1.trim_start(),
+    };
+    // Anything after the value, like a trailing semicolon or more code, is ignored
+    serde_json::Deserializer::from_str(json)
+        .into_iter::<Value>()
+        .next()?
+        .ok()
+}
+
+fn collect_ratings(value: &Value, latest: &mut BTreeMap<String, (String, f64)>) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|v| collect_ratings(v, latest)),
+        Value::Object(fields) => {
+            let name = fields.get("team_name").and_then(Value::as_str);
+            let rating = fields.get("team_rating").and_then(Value::as_f64);
+            let followed = fields
+                .get("gender")
+                .and_then(Value::as_str)
+                .unwrap_or("mens")
+                == gender().key();
+            match (name, rating) {
+                (Some(name), Some(rating)) if followed => {
+                    let date = fields
+                        .get("forecast_date")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    let entry = latest
+                        .entry(name.to_string())
+                        .or_insert_with(|| (date.clone(), rating));
+                    if date >= entry.0 {
+                        *entry = (date, rating);
+                    }
+                }
+                _ => fields.values().for_each(|v| collect_ratings(v, latest)),
+            }
+        }
+        _ => {}
+    }
+}
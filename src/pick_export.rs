@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+use anyhow::anyhow;
+
+use crate::tournament::{RoundKind, Tournament};
+
+/// Formats pool sites take pasted or uploaded picks in
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum PickFormat {
+    /// ESPN group import CSV with an `entry,round,team` header and a row per pick, as read
+    /// back by import-espn
+    EspnCsv,
+    /// Every pick on its own numbered line in game order, round 1 top to bottom first and the
+    /// champion last, which is how most sites list their 63 games
+    PickList,
+}
+
+/// Write the picks of a completed bracket in a pool site's format, under `entry` where the
+/// format names entries
+pub fn format_picks(
+    bracket: &Tournament,
+    format: PickFormat,
+    entry: &str,
+) -> anyhow::Result<String> {
+    let picks = picks(bracket)?;
+    match format {
+        PickFormat::EspnCsv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            writer.write_record(["entry", "round", "team"])?;
+            for (round_num, team) in &picks {
+                writer.write_record([entry, &round_num.to_string(), team])?;
+            }
+            Ok(String::from_utf8(writer.into_inner()?)?)
+        }
+        PickFormat::PickList => {
+            let mut out = String::new();
+            for (ind, (_, team)) in picks.iter().enumerate() {
+                writeln!(out, "{}. {}", ind + 1, team)?;
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// The winner of every game in game order with its round number, failing if any is unpicked
+fn picks(bracket: &Tournament) -> anyhow::Result<Vec<(usize, &str)>> {
+    let mut picks = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        for matchup in &bracket.rounds[&round].matchups {
+            let team = matchup.winning_team().ok_or_else(|| {
+                anyhow!(
+                    "Game {} of the {} has no pick, only complete brackets can be exported",
+                    matchup.index() + 1,
+                    bracket.round_name(round)
+                )
+            })?;
+            picks.push((round_num, team));
+        }
+    }
+    Ok(picks)
+}
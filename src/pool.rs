@@ -0,0 +1,72 @@
+use std::cmp::Reverse;
+
+use serde::Serialize;
+
+use crate::engine::sample_bracket;
+use crate::probabilities::ProbabilityMatrix;
+use crate::rng::Sampler;
+use crate::scoring::{score_bracket, ScoringSystem};
+use crate::tournament::Tournament;
+
+/// How an entry fared over many playings of the rest of the tournament
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolOdds {
+    pub name: String,
+    /// Chance of finishing first, second and third. Tied entries share the places they tie for.
+    pub finishes: [f64; 3],
+    /// Average final score
+    pub mean_points: f64,
+}
+
+/// Play out the undecided games of `actual` `runs` times, score every entry against each
+/// outcome and count where each one finishes in the pool
+pub fn simulate_pool(
+    brackets: &[(String, Tournament)],
+    actual: &Tournament,
+    matrix: &ProbabilityMatrix,
+    scoring: &ScoringSystem,
+    runs: usize,
+    sampler: &mut Sampler,
+) -> anyhow::Result<Vec<PoolOdds>> {
+    let mut finishes = vec![[0.; 3]; brackets.len()];
+    let mut total_points = vec![0.; brackets.len()];
+    for _ in 0..runs {
+        sampler.start_run();
+        let outcome = sample_bracket(actual, matrix, 1., sampler)?;
+        let mut scores = brackets
+            .iter()
+            .enumerate()
+            .map(|(ind, (_, bracket))| (ind, score_bracket(bracket, &outcome, scoring).points))
+            .collect::<Vec<_>>();
+        scores.sort_by_key(|(_, points)| Reverse(*points));
+        for (ind, points) in &scores {
+            total_points[*ind] += *points as f64;
+        }
+
+        // Entries on the same score split the places they cover between them
+        let mut start = 0;
+        while start < scores.len().min(3) {
+            let tied = scores[start..]
+                .iter()
+                .take_while(|(_, points)| *points == scores[start].1)
+                .count();
+            for (ind, _) in &scores[start..start + tied] {
+                for share in &mut finishes[*ind][start..(start + tied).min(3)] {
+                    *share += 1. / tied as f64;
+                }
+            }
+            start += tied;
+        }
+    }
+
+    let runs = runs.max(1) as f64;
+    Ok(brackets
+        .iter()
+        .zip(finishes.iter().zip(&total_points))
+        .map(|((name, _), (places, points))| PoolOdds {
+            name: name.clone(),
+            finishes: places.map(|count| count / runs),
+            mean_points: points / runs,
+        })
+        .collect())
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+use crate::analytic::{most_probable_bracket_with, round_probabilities};
+use crate::probabilities::ProbabilityMatrix;
+use crate::tournament::{RoundKind, Tournament};
+
+/// How much less likely a team is treated as reaching the Final Four for each earlier entry
+/// that already has it there
+const FINAL_FOUR_PENALTY: f64 = 2.;
+
+/// One bracket of a portfolio and what it stakes its chances on
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioEntry {
+    pub bracket: Tournament,
+    pub champion: String,
+    /// Chance the champion wins the title
+    pub title_prob: f64,
+    pub final_four: Vec<String>,
+}
+
+/// Brackets for `entries` pool entries which spread their bets rather than all backing the
+/// favorite. Each entry gets a different champion, most likely first, so together they cover
+/// as much title probability as possible. The rest of each bracket is the most likely one for
+/// its champion, with Final Four teams picked by earlier entries made less attractive.
+pub fn diversified_entries(
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    entries: usize,
+) -> anyhow::Result<Vec<PortfolioEntry>> {
+    let probs = round_probabilities(bracket, matrix)?;
+    let mut champions = probs
+        .probs
+        .iter()
+        .map(|(team, round_probs)| (team.clone(), round_probs[5]))
+        .filter(|(_, title_prob)| *title_prob > 0.)
+        .collect::<Vec<_>>();
+    champions.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    if champions.is_empty() {
+        return Err(anyhow!("No possible champion"));
+    }
+    if entries > champions.len() {
+        log::warn!(
+            "Only {} teams can win the title, so some of the {} entries share a champion",
+            champions.len(),
+            entries
+        );
+    }
+
+    let mut penalties: HashMap<String, f64> = HashMap::new();
+    let mut res = vec![];
+    for (champion, title_prob) in champions.into_iter().cycle().take(entries) {
+        let bracket = most_probable_bracket_with(bracket, matrix, &champion, &penalties)?;
+        let final_four = bracket.rounds[&RoundKind::Round(4)]
+            .matchups
+            .iter()
+            .filter_map(|m| m.winning_team())
+            .map(|team| team.to_string())
+            .collect::<Vec<_>>();
+        for team in &final_four {
+            *penalties.entry(team.clone()).or_insert(0.) += FINAL_FOUR_PENALTY.ln();
+        }
+        res.push(PortfolioEntry {
+            bracket,
+            champion,
+            title_prob,
+            final_four,
+        });
+    }
+    Ok(res)
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use thirtyfour::By;
+use tokio::time::sleep;
+
+use crate::selectors::selectors;
+use crate::session::Session;
+use crate::simulate::parse_node_ids;
+use crate::timings::{self, Phase};
+use crate::{page_url, throttle, timeouts};
+
+/// Where the page is saved when it isn't laid out as the selectors expect
+pub const PAGE_DUMP_PATH: &str = "page_dump.html";
+
+/// How much of the page to quote when reporting a layout problem, in characters
+const SNIPPET_CHARS: usize = 1000;
+
+/// Load the 538 page and check it has everything scraping relies on
+pub async fn load_page(driver: &Session) -> anyhow::Result<()> {
+    timings::time(Phase::PageLoad, async {
+        driver.goto(&page_url()).await?;
+        check_layout(driver).await
+    })
+    .await
+}
+
+/// Check the loaded page has the teams table and bracket the selectors point at, waiting for
+/// them to render. When the markup has changed this names every missing part at once, rather
+/// than failing later on whichever element happens to be looked for first.
+pub async fn check_layout(driver: &Session) -> anyhow::Result<()> {
+    let selectors = selectors();
+    let parts = [
+        ("teams table", &selectors.team_table),
+        ("teams table rows", &selectors.team_rows),
+        ("bracket", &selectors.bracket),
+        ("bracket nodes", &selectors.node),
+    ];
+    let timeout = timeouts::element_timeout();
+    let start = Instant::now();
+    let missing = loop {
+        let mut missing = vec![];
+        for (name, selector) in parts {
+            if driver.find_all(By::Css(selector)).await?.is_empty() {
+                missing.push(format!("{} (`{}`)", name, selector));
+            }
+        }
+        if missing.is_empty() || start.elapsed() > timeout {
+            break missing;
+        }
+        sleep(Duration::from_millis(250)).await;
+    };
+    let missing = if missing.is_empty() {
+        match unreadable_node_ids(driver).await? {
+            Some(id) => vec![format!(
+                "node ids fitting `{}` (found ids like `{}`)",
+                selectors.node_id, id
+            )],
+            None => return Ok(()),
+        }
+    } else {
+        missing
+    };
+    throttle::rate_limit().await;
+    Err(layout_error(&missing, &driver.page_source().await?))
+}
+
+/// The first node id on the page, when none of them fit the node id template
+async fn unreadable_node_ids(driver: &Session) -> anyhow::Result<Option<String>> {
+    let html = driver
+        .find(By::Css(&selectors().bracket))
+        .await?
+        .inner_html()
+        .await?;
+    let ids = parse_node_ids(&html);
+    if ids.iter().any(|id| id.parsed.is_ok()) {
+        return Ok(None);
+    }
+    Ok(ids.into_iter().map(|id| id.id).next())
+}
+
+/// Describe what's missing from the page, saving the page to look through
+fn layout_error(missing: &[String], html: &str) -> anyhow::Error {
+    let saved = match fs::write(PAGE_DUMP_PATH, html) {
+        Ok(()) => format!("The page is saved to {}, and begins:", PAGE_DUMP_PATH),
+        Err(e) => {
+            log::warn!("Could not save the page to {}: {}", PAGE_DUMP_PATH, e);
+            "The page begins:".to_string()
+        }
+    };
+    anyhow!(
+        "The 538 page isn't laid out as expected, it has no {}. If its markup has changed, point \
+         --selectors at a fixed copy of data/selectors.json. {}\n{}",
+        missing.join(", "),
+        saved,
+        snippet(html)
+    )
+}
+
+/// The start of the page body, or of the page if it has none
+fn snippet(html: &str) -> String {
+    let body = html.find("<body").map_or(html, |start| &html[start..]);
+    let mut snippet: String = body.chars().take(SNIPPET_CHARS).collect();
+    if snippet.len() < body.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_start_at_the_body() {
+        let html = format!(
+            "<html><head></head><body>{}</body></html>",
+            "x".repeat(2000)
+        );
+        let start = snippet(&html);
+        assert!(start.starts_with("<body>x"));
+        assert!(start.ends_with("x..."));
+        assert_eq!(start.chars().count(), SNIPPET_CHARS + 3);
+
+        assert_eq!(snippet("<p>Access denied</p>"), "<p>Access denied</p>");
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Where scraped matchup probabilities are saved
+pub const PROBABILITIES_PATH: &str = "probabilities.json";
+
+/// Rating points per point of expected margin in 538's win probability formula
+const RATING_SCALE: f64 = 30.464;
+
+/// Head-to-head win probabilities for pairs of teams which can meet in the bracket. 538's
+/// numbers depend slightly on game location, which is ignored here, so a pair has the same
+/// probability in whatever round they meet.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ProbabilityMatrix {
+    /// Chance that the outer team beats the inner team. Both orders of a pair are stored
+    probs: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl ProbabilityMatrix {
+    /// Record that `team1` beats `team2` with probability `prob`
+    pub fn insert(&mut self, team1: &str, team2: &str, prob: f64) {
+        self.probs
+            .entry(team1.to_string())
+            .or_default()
+            .insert(team2.to_string(), prob);
+        self.probs
+            .entry(team2.to_string())
+            .or_default()
+            .insert(team1.to_string(), 1. - prob);
+    }
+
+    /// Probabilities for every pair of the given teams from their 538 power ratings
+    pub fn from_ratings(ratings: &[(&str, f64)]) -> Self {
+        Self::from_ratings_with(ratings, rating_win_probability)
+    }
+
+    /// Probabilities for every pair of the given teams from their ratings, with
+    /// `win_probability` giving the chance of a team with the first rating beating one with
+    /// the second
+    pub fn from_ratings_with(
+        ratings: &[(&str, f64)],
+        win_probability: impl Fn(f64, f64) -> f64,
+    ) -> Self {
+        let mut matrix = Self::default();
+        for (i, (team1, rating1)) in ratings.iter().enumerate() {
+            for (team2, rating2) in &ratings[i + 1..] {
+                matrix.insert(team1, team2, win_probability(*rating1, *rating2));
+            }
+        }
+        matrix
+    }
+
+    /// Chance that `team1` beats `team2`, None if this pair hasn't been scraped
+    pub fn get(&self, team1: &str, team2: &str) -> Option<f64> {
+        self.probs.get(team1)?.get(team2).copied()
+    }
+
+    /// Each pair once, as the chance of the alphabetically first team beating the other
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str, f64)> {
+        self.probs.iter().flat_map(|(team1, opps)| {
+            opps.iter()
+                .filter(move |(team2, _)| team1 < *team2)
+                .map(move |(team2, prob)| (team1.as_str(), team2.as_str(), *prob))
+        })
+    }
+
+    /// Number of distinct pairs with a probability
+    pub fn len(&self) -> usize {
+        self.probs.values().map(|opps| opps.len()).sum::<usize>() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.probs.is_empty()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?,
+        );
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// Chance that a team rated `rating1` beats a team rated `rating2` on a neutral court, the
+/// same formula 538 uses for the numbers shown when hovering
+pub fn rating_win_probability(rating1: f64, rating2: f64) -> f64 {
+    1. / (1. + 10_f64.powf(-(rating1 - rating2) * RATING_SCALE / 400.))
+}
+
+/// Scale a win probability on the logit scale, so a `factor` above 1 makes favorites more
+/// likely to win than the probability says, below 1 evens games out and 1 leaves it as is.
+/// Certain results stay certain.
+pub fn sharpen(prob: f64, factor: f64) -> f64 {
+    if prob <= 0. || prob >= 1. {
+        return prob;
+    }
+    let logit = (prob / (1. - prob)).ln();
+    1. / (1. + (-factor * logit).exp())
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+/// Directory the profiles are kept in, one directory each
+pub const PROFILES_DIR: &str = "profiles";
+
+/// Name of the settings file in a profile's directory
+pub const PROFILE_FILE: &str = "profile.json";
+
+/// Options a profile runs tasks with unless they're given on the command line, e.g.
+/// `{"global": ["--gender", "womens"], "tasks": {"build-bracket": ["--strategy", "chalk"]}}`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Options for every task
+    pub global: Vec<String>,
+    /// Options for each task, by its name on the command line
+    pub tasks: BTreeMap<String, Vec<String>>,
+}
+
+impl Profile {
+    /// Load the named profile's settings, creating its directory and an empty settings file
+    /// for a new profile
+    pub fn load(name: &str) -> anyhow::Result<Self> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!(
+                "Profile names can only have letters, digits, - and _, not {:?}",
+                name
+            ));
+        }
+        let dir = dir(name);
+        let path = dir.join(PROFILE_FILE);
+        if !path.exists() {
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Could not create {}", dir.display()))?;
+            serde_json::to_writer_pretty(File::create(&path)?, &Profile::default())?;
+            return Ok(Profile::default());
+        }
+        serde_json::from_reader(BufReader::new(File::open(&path)?))
+            .with_context(|| format!("Could not read profile settings from {}", path.display()))
+    }
+
+    /// Command line arguments with the profile's options put in front of the ones given for
+    /// the task and for every task, so that given options override the profile's
+    pub fn apply(&self, args: &[OsString], task: &str) -> Vec<OsString> {
+        let (program, rest) = args.split_at(1.min(args.len()));
+        let mut applied = program.to_vec();
+        applied.extend(self.global.iter().map(OsString::from));
+        let task_ind = rest.iter().position(|arg| arg == task);
+        match (task_ind, self.tasks.get(task)) {
+            (Some(ind), Some(options)) => {
+                applied.extend_from_slice(&rest[..=ind]);
+                applied.extend(options.iter().map(OsString::from));
+                applied.extend_from_slice(&rest[ind + 1..]);
+            }
+            _ => applied.extend_from_slice(rest),
+        }
+        applied
+    }
+}
+
+/// The named profile's directory, which its tasks are run in
+pub fn dir(name: &str) -> PathBuf {
+    PathBuf::from(PROFILES_DIR).join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_options_come_after_profile_options() {
+        let profile = Profile {
+            global: vec!["--color".into(), "never".into()],
+            tasks: [(
+                "build-bracket".to_string(),
+                vec!["--strategy".into(), "expected-points".into()],
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let args: Vec<OsString> = ["march-madness", "build-bracket", "--strategy", "chalk"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        let applied: Vec<_> = profile
+            .apply(&args, "build-bracket")
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect();
+        assert_eq!(
+            applied,
+            [
+                "march-madness",
+                "--color",
+                "never",
+                "build-bracket",
+                "--strategy",
+                "expected-points",
+                "--strategy",
+                "chalk"
+            ]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "driver")]
+mod scrape;
+
+#[cfg(feature = "driver")]
+pub use scrape::scrape_public_picks;
+
+/// Where scraped public pick percentages are saved
+pub const PUBLIC_PICKS_PATH: &str = "public_picks.json";
+
+/// Share of ESPN entries picking each team to win in each round
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PublicPicks {
+    /// For each team, the fraction of entries picking it to win its round 1..=6 game
+    pub picks: BTreeMap<String, [f64; 6]>,
+}
+
+impl PublicPicks {
+    /// Fraction of entries picking the team to win its game in the given round
+    pub fn pick_share(&self, team: &str, round_num: usize) -> f64 {
+        self.picks.get(team).map(|p| p[round_num - 1]).unwrap_or(0.)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?,
+        );
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::{anyhow, Context};
+use scraper::{Html, Selector};
+use thirtyfour::By;
+
+use super::PublicPicks;
+use crate::session::Session;
+use crate::teams::{find_team, Team};
+use crate::{throttle, timeouts};
+
+/// ESPN Tournament Challenge page showing how often the public picked each team in each round
+const WHO_PICKED_WHOM_URL: &str =
+    "https://fantasy.espn.com/tournament-challenge-bracket/2022/en/whopickedwhom";
+
+/// Rows of the pick table, each holding one cell per round
+const ROWS: &str = "table.wpw-table tbody tr";
+const CELLS: &str = "td";
+const TEAM_NAME: &str = ".teamName";
+const PERCENTAGE: &str = ".percentage";
+
+/// Scrape ESPN's "Who Picked Whom" table. Team names are matched to 538 names so the picks
+/// line up with the probability matrix, and teams which can't be matched are skipped.
+pub async fn scrape_public_picks(driver: &Session, teams: &[Team]) -> anyhow::Result<PublicPicks> {
+    driver.goto(WHO_PICKED_WHOM_URL).await?;
+    driver
+        .wait_for(By::Css(ROWS), timeouts::element_timeout())
+        .await
+        .context("Could not find the Who Picked Whom table")?;
+    throttle::rate_limit().await;
+    let html = driver.page_source().await?;
+    parse_public_picks(&html, teams)
+}
+
+/// Each table row lists the nth most picked team for every round, so a team shows up once
+/// per round in different rows
+fn parse_public_picks(html: &str, teams: &[Team]) -> anyhow::Result<PublicPicks> {
+    let parsed = Html::parse_document(html);
+    let rows = Selector::parse(ROWS).unwrap();
+    let cells = Selector::parse(CELLS).unwrap();
+    let team_name = Selector::parse(TEAM_NAME).unwrap();
+    let percentage = Selector::parse(PERCENTAGE).unwrap();
+
+    let mut res = PublicPicks::default();
+    for row in parsed.select(&rows) {
+        for (round_ind, cell) in row.select(&cells).take(6).enumerate() {
+            let name = match cell.select(&team_name).next() {
+                Some(name) => name.text().collect::<String>(),
+                None => continue,
+            };
+            let share = cell
+                .select(&percentage)
+                .next()
+                .map(|p| p.text().collect::<String>())
+                .ok_or_else(|| anyhow!("No pick percentage for {}", name))?;
+            let share = share.trim().trim_end_matches('%').parse::<f64>()? / 100.;
+            match find_team(teams, name.trim()) {
+                Some(team) => {
+                    res.picks.entry(team.name().to_string()).or_default()[round_ind] = share
+                }
+                None => log::warn!("Could not match ESPN team {} to a 538 team", name.trim()),
+            }
+        }
+    }
+    if res.picks.is_empty() {
+        return Err(anyhow!("No public picks found on the page"));
+    }
+    Ok(res)
+}
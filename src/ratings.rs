@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use crate::probabilities::ProbabilityMatrix;
+use crate::teams::{find_team, Team};
+
+/// One row of a ratings file, e.g. `Gonzaga,24.1`
+#[derive(Debug, Deserialize)]
+struct RatingRecord {
+    team: String,
+    rating: f64,
+}
+
+/// How the gap between two teams' ratings becomes the chance of the better one winning
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum RatingFormula {
+    /// 1 / (1 + 10^(-gap / scale)), for Elo style ratings, where the scale is the gap at
+    /// which the better team wins 10 times out of 11. 400 unless given
+    Elo,
+    /// 1 / (1 + e^(-gap / scale)), for ratings in points per game such as an expected margin.
+    /// 6 unless given, which fits college basketball margins
+    Logistic,
+}
+
+impl RatingFormula {
+    /// Scale used unless one is given
+    pub fn default_scale(self) -> f64 {
+        match self {
+            RatingFormula::Elo => 400.,
+            RatingFormula::Logistic => 6.,
+        }
+    }
+
+    /// Chance of a team rated `rating1` beating one rated `rating2`
+    pub fn win_probability(self, rating1: f64, rating2: f64, scale: f64) -> f64 {
+        let gap = (rating1 - rating2) / scale;
+        match self {
+            RatingFormula::Elo => 1. / (1. + 10_f64.powf(-gap)),
+            RatingFormula::Logistic => 1. / (1. + (-gap).exp()),
+        }
+    }
+}
+
+/// Load each team's rating from a CSV with a `team,rating` header, from any model. Rows are
+/// matched to the given teams by name, and ones for teams outside the tournament are
+/// ignored, so a file rating every team in the country works. Every given team needs a
+/// rating.
+pub fn load_ratings<'a>(path: &Path, teams: &'a [Team]) -> anyhow::Result<Vec<(&'a str, f64)>> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Could not open ratings file {}", path.display()))?;
+    let mut ratings = vec![];
+    for record in reader.deserialize() {
+        let record: RatingRecord =
+            record.with_context(|| format!("Could not read {}", path.display()))?;
+        let team = match find_team(teams, &record.team) {
+            Some(team) => team.name(),
+            None => continue,
+        };
+        if ratings.iter().any(|(rated, _)| *rated == team) {
+            return Err(anyhow!("{} rates {} twice", path.display(), team));
+        }
+        ratings.push((team, record.rating));
+    }
+    let unrated: Vec<_> = teams
+        .iter()
+        .map(Team::name)
+        .filter(|name| !ratings.iter().any(|(rated, _)| rated == name))
+        .collect();
+    if !unrated.is_empty() {
+        return Err(anyhow!(
+            "{} has no rating for {}",
+            path.display(),
+            unrated.join(", ")
+        ));
+    }
+    Ok(ratings)
+}
+
+/// Probabilities for every pair of rated teams with the given formula and scale
+pub fn rating_matrix(
+    ratings: &[(&str, f64)],
+    formula: RatingFormula,
+    scale: f64,
+) -> ProbabilityMatrix {
+    ProbabilityMatrix::from_ratings_with(ratings, |rating1, rating2| {
+        formula.win_probability(rating1, rating2, scale)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::tournament::Region;
+
+    #[test]
+    fn a_gap_of_one_scale_wins_ten_of_eleven_elo_games() {
+        let elo = RatingFormula::Elo;
+        assert!((elo.win_probability(1600., 1200., 400.) - 10. / 11.).abs() < 1e-9);
+        assert!((elo.win_probability(1500., 1500., 400.) - 0.5).abs() < 1e-9);
+        let logistic = RatingFormula::Logistic;
+        let favorite = logistic.win_probability(10., 4., 6.);
+        assert!((favorite + logistic.win_probability(4., 10., 6.) - 1.).abs() < 1e-9);
+        assert!((favorite - 1. / (1. + (-1_f64).exp())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn loads_a_rating_for_every_team_once() {
+        let teams: Vec<Team> = ["Duke", "Gonzaga"]
+            .iter()
+            .map(|name| {
+                serde_json::from_value(json!({"name": name, "region": Region::West, "seed": 1}))
+                    .unwrap()
+            })
+            .collect();
+        let dir =
+            std::env::temp_dir().join(format!("march-madness-ratings-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let load = |name: &str, csv: &str| {
+            let path = dir.join(name);
+            fs::write(&path, csv).unwrap();
+            load_ratings(&path, &teams).map_err(|err| err.to_string())
+        };
+
+        // Teams outside the field are skipped
+        let ratings = load(
+            "all.csv",
+            "team,rating\nduke,20\nGonzaga,24.1\nMemphis,15\n",
+        )
+        .unwrap();
+        assert_eq!(ratings, [("Duke", 20.), ("Gonzaga", 24.1)]);
+        let missing = load("missing.csv", "team,rating\nDuke,20\n").unwrap_err();
+        assert!(
+            missing.ends_with("has no rating for Gonzaga"),
+            "{}",
+            missing
+        );
+        let twice = load("twice.csv", "team,rating\nDuke,20\nGonzaga,24.1\nduke,21\n").unwrap_err();
+        assert!(twice.ends_with("rates Duke twice"), "{}", twice);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,47 @@
+mod advancement;
+mod backtest;
+mod blank;
+mod calcutta;
+mod calibration;
+mod chalk;
+mod cinderellas;
+mod conditional;
+mod convergence;
+mod evaluation;
+mod futures;
+mod kelly;
+mod leaderboard;
+mod leverage;
+mod madness;
+mod markdown;
+mod parlays;
+mod pool;
+mod regions;
+mod sensitivity;
+mod similarity;
+mod stands;
+mod upsets;
+
+pub use advancement::advancement_report;
+pub use backtest::backtest_report;
+pub use blank::blank_bracket;
+pub use calcutta::{calcutta_report, calcutta_values, CalcuttaPayouts, CalcuttaValue};
+pub use calibration::{calibration_gaps, calibration_report, CalibrationGap};
+pub use chalk::chalk_report;
+pub use cinderellas::{cinderella_report, cinderellas, Cinderella, CinderellaWin};
+pub use conditional::{conditional_report, conditional_shifts, ConditionalShift};
+pub use convergence::convergence_report;
+pub use evaluation::evaluation_report;
+pub use futures::{futures_comparisons, futures_report, FuturesComparison};
+pub use kelly::{kelly_report, kelly_stakes, KellyStake};
+pub use leaderboard::{leaderboard, leaderboard_lines};
+pub use leverage::{leverage_picks, leverage_report, LeveragePick};
+pub use madness::madness_report;
+pub use markdown::bracket_markdown;
+pub use parlays::{parlay_report, parlay_suggestions, single_bets, Bet, Leg, ParlaySuggestions};
+pub use pool::pool_report;
+pub use regions::region_bracket;
+pub use sensitivity::sensitivity_report;
+pub use similarity::similarity_report;
+pub use stands::stands_report;
+pub use upsets::upset_summary;
@@ -0,0 +1,23 @@
+use crate::analytic::RoundProbabilities;
+
+/// Column headers for the chance of winning in each round
+const ROUND_HEADERS: [&str; 6] = ["R32", "S16", "E8", "F4", "Final", "Champ"];
+
+/// Print each team's chance of reaching each later round, most likely champions first
+pub fn advancement_report(probs: &RoundProbabilities) {
+    let mut teams = probs.probs.iter().collect::<Vec<_>>();
+    teams.sort_by(|a, b| b.1[5].partial_cmp(&a.1[5]).unwrap());
+
+    print!("{:<24}", "Team");
+    for header in ROUND_HEADERS {
+        print!(" {:>7}", header);
+    }
+    println!();
+    for (team, round_probs) in teams {
+        print!("{:<24}", team);
+        for prob in round_probs {
+            print!(" {:>6.1}%", prob * 100.);
+        }
+        println!();
+    }
+}
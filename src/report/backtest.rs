@@ -0,0 +1,29 @@
+use crate::backtest::SeasonScore;
+
+/// Print how each strategy would have scored in each past season, with averages over all of
+/// them
+pub fn backtest_report(scores: &[SeasonScore]) {
+    println!(
+        "{:<12} {:>11} {:>12} {:>12} {:>8}",
+        "Season", "Most likely", "Sampled avg", "Sampled best", "Perfect"
+    );
+    for score in scores {
+        println!(
+            "{:<12} {:>11} {:>12.1} {:>12} {:>8}",
+            score.season, score.most_likely, score.sampled_mean, score.sampled_best, score.perfect
+        );
+    }
+    if scores.is_empty() {
+        return;
+    }
+    let seasons = scores.len() as f64;
+    let mean = |f: fn(&SeasonScore) -> f64| scores.iter().map(f).sum::<f64>() / seasons;
+    println!(
+        "{:<12} {:>11.1} {:>12.1} {:>12.1} {:>8.1}",
+        "Average",
+        mean(|s| s.most_likely as f64),
+        mean(|s| s.sampled_mean),
+        mean(|s| s.sampled_best as f64),
+        mean(|s| s.perfect as f64)
+    );
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::field::Field;
+use crate::teams::Team;
+use crate::tournament::{Region, RoundKind};
+
+/// Render an empty bracket from the teams table: round 1 with seeds, grouped by region, and
+/// blank lines for every later game. First Four pairs share a line since either could be there.
+pub fn blank_bracket(teams: &[Team]) -> String {
+    let mut out = String::new();
+    write_blank(&mut out, teams).expect("Writing to a String can't fail");
+    out
+}
+
+fn write_blank(out: &mut String, teams: &[Team]) -> std::fmt::Result {
+    // Round 1 lines by region then matchup, each holding the teams who could fill either side
+    let mut slots: BTreeMap<Option<usize>, [Vec<&Team>; 2]> = BTreeMap::new();
+    for team in teams {
+        let side = if team.seed.0 <= 8 { 0 } else { 1 };
+        let key = Field::NCAA.first_round_matchup(team.region.to_ind(), team.seed);
+        slots.entry(key).or_default()[side].push(team);
+    }
+
+    writeln!(out, "{}", RoundKind::Round(1))?;
+    let mut region: Option<Region> = None;
+    for sides in slots.values() {
+        let slot_region = sides.iter().flatten().next().map(|team| team.region);
+        if slot_region != region {
+            if let Some(slot_region) = slot_region {
+                writeln!(out, "\n{}", slot_region)?;
+            }
+            region = slot_region;
+        }
+        writeln!(
+            out,
+            "{} vs {}",
+            side_display(&sides[0]),
+            side_display(&sides[1])
+        )?;
+    }
+    for round_num in 2..=6 {
+        let round = RoundKind::Round(round_num);
+        writeln!(out, "\n{}", round)?;
+        for _ in 0..Field::NCAA.matchup_count(round_num) {
+            writeln!(out, "___ vs ___")?;
+        }
+    }
+    Ok(())
+}
+
+fn side_display(teams: &[&Team]) -> String {
+    match teams.first() {
+        Some(team) => format!(
+            "({}) {}",
+            team.seed.0,
+            teams
+                .iter()
+                .map(|t| t.name())
+                .collect::<Vec<_>>()
+                .join(" / ")
+        ),
+        None => "___".to_string(),
+    }
+}
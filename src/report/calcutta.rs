@@ -0,0 +1,94 @@
+use anyhow::anyhow;
+use serde::Serialize;
+
+use crate::summary::SimulationSummary;
+
+/// Share of the pot paid out per game won in each round of a Calcutta auction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalcuttaPayouts {
+    /// Percent of the pot paid for each win in round 1..=6
+    pub percents: [f64; 6],
+}
+
+impl Default for CalcuttaPayouts {
+    /// Pays 16% of the pot across each of the first five rounds and 20% to the champion
+    fn default() -> Self {
+        Self {
+            percents: [0.5, 1., 2., 4., 8., 20.],
+        }
+    }
+}
+
+impl CalcuttaPayouts {
+    /// Build from a list of percents per round, which must have one entry per round
+    pub fn from_percents(percents: &[f64]) -> anyhow::Result<Self> {
+        let percents = percents
+            .try_into()
+            .map_err(|_| anyhow!("Expected payouts for 6 rounds, got {}", percents.len()))?;
+        let payouts = Self { percents };
+        let total = payouts.total_percent();
+        if (total - 100.).abs() > 0.01 {
+            log::warn!(
+                "Payouts add up to {:.1}% of the pot rather than 100%",
+                total
+            );
+        }
+        Ok(payouts)
+    }
+
+    /// Percent of the pot paid out over the whole tournament, counting every game in each round
+    fn total_percent(&self) -> f64 {
+        self.percents
+            .iter()
+            .enumerate()
+            .map(|(round_ind, percent)| percent * (32 >> round_ind) as f64)
+            .sum()
+    }
+}
+
+/// A team's expected Calcutta payout
+#[derive(Debug, Clone, Serialize)]
+pub struct CalcuttaValue {
+    pub team: String,
+    pub value: f64,
+}
+
+/// Each team's expected Calcutta payout from its simulated chance of winning in each round,
+/// most valuable first. Bidding up to the expected payout breaks even on average.
+pub fn calcutta_values(
+    summary: &SimulationSummary,
+    payouts: &CalcuttaPayouts,
+    pot: f64,
+) -> Vec<CalcuttaValue> {
+    let mut values = summary
+        .wins
+        .keys()
+        .map(|team| {
+            let value: f64 = (1..=6)
+                .map(|round_num| {
+                    summary.win_probability(team, round_num) * payouts.percents[round_num - 1]
+                })
+                .sum::<f64>()
+                / 100.
+                * pot;
+            CalcuttaValue {
+                team: team.clone(),
+                value,
+            }
+        })
+        .collect::<Vec<_>>();
+    values.sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap());
+    values
+}
+
+/// Print each team's expected Calcutta payout, most valuable first
+pub fn calcutta_report(summary: &SimulationSummary, payouts: &CalcuttaPayouts, pot: f64) {
+    println!(
+        "Expected Calcutta payouts from a {:.0} pot ({} simulated runs)",
+        pot, summary.runs
+    );
+    println!("{:<24} {:>10} {:>8}", "Team", "Value", "Share");
+    for CalcuttaValue { team, value } in calcutta_values(summary, payouts, pot) {
+        println!("{:<24} {:>10.2} {:>7.2}%", team, value, value / pot * 100.);
+    }
+}
@@ -0,0 +1,75 @@
+use serde::Serialize;
+
+use crate::analytic::RoundProbabilities;
+use crate::tournament::RoundKind;
+
+/// A team's chance of winning a round worked out from the hover probabilities, next to the
+/// chance the teams table gives
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationGap {
+    pub team: String,
+    pub round: usize,
+    pub hover: f64,
+    pub table: f64,
+}
+
+impl CalibrationGap {
+    pub fn gap(&self) -> f64 {
+        self.hover - self.table
+    }
+}
+
+/// Every chance which differs between the two by more than `tolerance`, largest gap first.
+/// Both come from the same 538 model, so gaps beyond the rounding of hover labels point to a
+/// scraping bug or hovers scraped before the latest results.
+pub fn calibration_gaps(
+    hover: &RoundProbabilities,
+    table: &RoundProbabilities,
+    tolerance: f64,
+) -> Vec<CalibrationGap> {
+    let mut gaps = table
+        .probs
+        .keys()
+        .flat_map(|team| {
+            (1..=6).map(move |round_num| CalibrationGap {
+                team: team.clone(),
+                round: round_num,
+                hover: hover.win_probability(team, round_num),
+                table: table.win_probability(team, round_num),
+            })
+        })
+        .filter(|gap| gap.gap().abs() > tolerance)
+        .collect::<Vec<_>>();
+    gaps.sort_by(|a, b| b.gap().abs().partial_cmp(&a.gap().abs()).unwrap());
+    gaps
+}
+
+/// Print the chances on which the hover probabilities and teams table disagree
+pub fn calibration_report(gaps: &[CalibrationGap], tolerance: f64) {
+    if gaps.is_empty() {
+        println!(
+            "Hover probabilities agree with the teams table within {:.1} points",
+            tolerance * 100.
+        );
+        return;
+    }
+    println!(
+        "{} chances differ by more than {:.1} points",
+        gaps.len(),
+        tolerance * 100.
+    );
+    println!(
+        "{:<24} {:<14} {:>8} {:>8} {:>8}",
+        "Team", "Round", "Hover", "Table", "Gap"
+    );
+    for gap in gaps {
+        println!(
+            "{:<24} {:<14} {:>7.1}% {:>7.1}% {:>+7.1}%",
+            gap.team,
+            RoundKind::Round(gap.round).to_string(),
+            gap.hover * 100.,
+            gap.table * 100.,
+            gap.gap() * 100.
+        );
+    }
+}
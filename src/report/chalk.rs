@@ -0,0 +1,18 @@
+use crate::scoring::ChalkScore;
+
+/// Print chalk scores for brackets side by side, chalkiest first
+pub fn chalk_report(scores: &[(String, ChalkScore)]) {
+    let mut scores = scores.iter().collect::<Vec<_>>();
+    scores.sort_by_key(|(_, score)| (score.seed_gap, score.upsets));
+
+    println!(
+        "{:<24} {:>7} {:>8} {:>10} {:>7}",
+        "Bracket", "Upsets", "Seed gap", "Log prob", "Priced"
+    );
+    for (name, score) in scores {
+        println!(
+            "{:<24} {:>7} {:>8} {:>10.2} {:>7}",
+            name, score.upsets, score.seed_gap, score.log_prob, score.priced_picks
+        );
+    }
+}
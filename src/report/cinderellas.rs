@@ -0,0 +1,156 @@
+use serde::Serialize;
+
+use crate::probabilities::ProbabilityMatrix;
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+
+/// Wins it takes to reach the Sweet 16
+const SWEET_16_WINS: usize = 2;
+
+/// A low seed's run to the Sweet 16 or further
+#[derive(Debug, Clone, Serialize)]
+pub struct Cinderella {
+    pub team: String,
+    pub seed: u8,
+    /// Games won on the way, in order
+    pub wins: Vec<CinderellaWin>,
+    /// Whether the run ended with the title
+    pub champion: bool,
+    /// Chance of winning all of those games, when every one of them has a probability
+    pub run_probability: Option<f64>,
+}
+
+/// One game won on a Cinderella run
+#[derive(Debug, Clone, Serialize)]
+pub struct CinderellaWin {
+    pub round: usize,
+    pub opponent: String,
+    pub opponent_seed: Option<u8>,
+    /// Chance the team had of winning, as the game was simulated or from scraped probabilities
+    pub probability: Option<f64>,
+}
+
+impl Cinderella {
+    /// How far the team got, e.g. reached the Elite Eight
+    pub fn run(&self) -> String {
+        if self.champion {
+            "won the title".to_string()
+        } else {
+            format!("reached the {}", RoundKind::Round(self.wins.len() + 1))
+        }
+    }
+}
+
+/// Every team seeded `min_seed` or worse which won its way to the Sweet 16 or further in a
+/// bracket, furthest first. Game probabilities come from the bracket where it was simulated,
+/// otherwise from `matrix`.
+pub fn cinderellas(
+    bracket: &Tournament,
+    matrix: Option<&ProbabilityMatrix>,
+    min_seed: u8,
+) -> Vec<Cinderella> {
+    let mut cinderellas = vec![];
+    for matchup in &bracket.rounds[&RoundKind::Round(1)].matchups {
+        for ind in [MatchupInd::Team1, MatchupInd::Team2] {
+            let (team, seed) = match (matchup.team(ind), matchup.seed(ind)) {
+                (Some(team), Some(seed)) if seed.0 >= min_seed => (team, seed),
+                _ => continue,
+            };
+            let wins = team_wins(bracket, matrix, team);
+            if wins.len() >= SWEET_16_WINS {
+                let run_probability = wins
+                    .iter()
+                    .map(|win| win.probability)
+                    .product::<Option<f64>>();
+                cinderellas.push(Cinderella {
+                    team: team.to_string(),
+                    seed: seed.0,
+                    champion: wins.len() == bracket.field.round_count(),
+                    wins,
+                    run_probability,
+                });
+            }
+        }
+    }
+    cinderellas.sort_by(|a, b| b.wins.len().cmp(&a.wins.len()).then(b.seed.cmp(&a.seed)));
+    cinderellas
+}
+
+fn team_wins(
+    bracket: &Tournament,
+    matrix: Option<&ProbabilityMatrix>,
+    team: &str,
+) -> Vec<CinderellaWin> {
+    let mut wins = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let matchup = match bracket.rounds[&RoundKind::Round(round_num)]
+            .matchups
+            .iter()
+            .find(|matchup| matchup.includes_team(team))
+        {
+            Some(matchup) => matchup,
+            None => break,
+        };
+        let (ind, opponent_ind) = if matchup.team(MatchupInd::Team1) == Some(team) {
+            (MatchupInd::Team1, MatchupInd::Team2)
+        } else {
+            (MatchupInd::Team2, MatchupInd::Team1)
+        };
+        let opponent = match (matchup.winner(), matchup.team(opponent_ind)) {
+            (Some(winner), Some(opponent)) if winner == ind => opponent,
+            _ => break,
+        };
+        let probability = match (matchup.win_probability(), ind) {
+            (Some(prob), MatchupInd::Team1) => Some(prob),
+            (Some(prob), MatchupInd::Team2) => Some(1. - prob),
+            (None, _) => matrix.and_then(|m| m.get(team, opponent)),
+        };
+        wins.push(CinderellaWin {
+            round: round_num,
+            opponent: opponent.to_string(),
+            opponent_seed: matchup.seed(opponent_ind).map(|seed| seed.0),
+            probability,
+        });
+    }
+    wins
+}
+
+/// Print each Cinderella run with the chance of every upset along the way
+pub fn cinderella_report(cinderellas: &[Cinderella]) {
+    if cinderellas.is_empty() {
+        println!("No Cinderellas reached the Sweet 16");
+        return;
+    }
+    for cinderella in cinderellas {
+        match cinderella.run_probability {
+            Some(prob) => println!(
+                "({}) {} {}, a {:.2}% chance",
+                cinderella.seed,
+                cinderella.team,
+                cinderella.run(),
+                prob * 100.
+            ),
+            None => println!(
+                "({}) {} {}",
+                cinderella.seed,
+                cinderella.team,
+                cinderella.run()
+            ),
+        }
+        for win in &cinderella.wins {
+            let opponent = match win.opponent_seed {
+                Some(seed) => format!("({}) {}", seed, win.opponent),
+                None => win.opponent.clone(),
+            };
+            let prob = win
+                .probability
+                .map(|prob| format!(" at {:.0}%", prob * 100.))
+                .unwrap_or_default();
+            println!(
+                "  {}: beat {}{}",
+                RoundKind::Round(win.round),
+                opponent,
+                prob
+            );
+        }
+    }
+}
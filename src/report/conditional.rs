@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+use crate::engine::Condition;
+use crate::summary::SimulationSummary;
+use crate::tournament::RoundKind;
+
+/// How many of the largest shifts to show
+const SHIFTS_SHOWN: usize = 20;
+
+/// A team's chance of winning a round over every run and over the runs where the condition
+/// held
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionalShift {
+    pub team: String,
+    pub round: usize,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl ConditionalShift {
+    pub fn shift(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// How every other team's chance of winning each round moves once the condition is assumed,
+/// largest moves first
+pub fn conditional_shifts(
+    all: &SimulationSummary,
+    given: &SimulationSummary,
+    condition: &Condition,
+) -> Vec<ConditionalShift> {
+    let mut shifts = all
+        .wins
+        .keys()
+        .filter(|team| **team != condition.team)
+        .flat_map(|team| {
+            (1..=6).map(move |round_num| ConditionalShift {
+                team: team.clone(),
+                round: round_num,
+                before: all.win_probability(team, round_num),
+                after: given.win_probability(team, round_num),
+            })
+        })
+        .filter(|shift| shift.before > 0. || shift.after > 0.)
+        .collect::<Vec<_>>();
+    shifts.sort_by(|a, b| b.shift().abs().partial_cmp(&a.shift().abs()).unwrap());
+    shifts
+}
+
+/// Print the chances that move the most when the condition is assumed
+pub fn conditional_report(
+    all: &SimulationSummary,
+    given: &SimulationSummary,
+    condition: &Condition,
+) {
+    println!(
+        "If {} wins in the {} ({} of {} runs, {:.1}%)",
+        condition.team,
+        RoundKind::Round(condition.round_num),
+        given.runs,
+        all.runs,
+        given.runs as f64 / all.runs as f64 * 100.
+    );
+    println!(
+        "{:<24} {:<14} {:>8} {:>8} {:>8}",
+        "Team", "Round", "Before", "After", "Shift"
+    );
+    for shift in conditional_shifts(all, given, condition)
+        .into_iter()
+        .take(SHIFTS_SHOWN)
+    {
+        println!(
+            "{:<24} {:<14} {:>7.1}% {:>7.1}% {:>+7.1}%",
+            shift.team,
+            RoundKind::Round(shift.round).to_string(),
+            shift.before * 100.,
+            shift.after * 100.,
+            shift.shift() * 100.
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::summary::SimulationSummary;
+
+/// How many title contenders to show
+const CHAMPIONS_SHOWN: usize = 10;
+
+/// z score of a two sided 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Half width of the 95% interval on title chances that counts as converged, e.g. 0.01 for
+/// within a percentage point
+const TARGET_HALF_WIDTH: f64 = 0.01;
+
+/// Print the likeliest champions with standard errors and 95% confidence intervals on their
+/// simulated title chances, then whether the runs pin every title chance down to within
+/// the target and roughly how many runs would
+pub fn convergence_report(summary: &SimulationSummary) {
+    let mut champions = summary
+        .wins
+        .keys()
+        .map(|team| (team, summary.win_probability(team, 6)))
+        .filter(|(_, prob)| *prob > 0.)
+        .collect::<Vec<_>>();
+    champions.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    println!("Title chances over {} runs", summary.runs);
+    if summary.independent_errors() {
+        println!("Errors and intervals are bounds for independent runs, these should be closer");
+    }
+    println!(
+        "{:<24} {:>7} {:>7} {:>17}",
+        "Team", "Chance", "SE", "95% interval"
+    );
+    for (team, prob) in champions.iter().take(CHAMPIONS_SHOWN) {
+        let se = summary.standard_error(team, 6);
+        println!(
+            "{:<24} {:>6.1}% {:>6.2}% {:>7.1}% - {:>5.1}%",
+            team,
+            prob * 100.,
+            se * 100.,
+            (prob - Z_95 * se).max(0.) * 100.,
+            (prob + Z_95 * se).min(1.) * 100.
+        );
+    }
+
+    // The interval shrinks with the square root of the runs, so the widest one decides how
+    // many runs are needed
+    let max_error = champions
+        .iter()
+        .map(|(team, _)| summary.standard_error(team, 6))
+        .fold(0., f64::max);
+    let half_width = Z_95 * max_error;
+    let needed = (summary.runs as f64 * (half_width / TARGET_HALF_WIDTH).powi(2)).ceil();
+    if half_width <= TARGET_HALF_WIDTH {
+        println!(
+            "Converged: every title chance is within {:.1} points at 95% confidence",
+            half_width * 100.
+        );
+    } else {
+        println!(
+            "Not converged: title chances are only within {:.1} points at 95% confidence, about \
+             {} runs would get within {:.1}",
+            half_width * 100.,
+            needed,
+            TARGET_HALF_WIDTH * 100.
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use crate::evaluate::Evaluation;
+use crate::tournament::RoundKind;
+
+/// Print Brier score and log loss of predictions for each round and over every game
+pub fn evaluation_report(rounds: &[(RoundKind, Evaluation)], overall: &Evaluation) {
+    println!(
+        "{:<14} {:>6} {:>8} {:>9} {:>8}",
+        "Round", "Games", "Brier", "Log loss", "Missing"
+    );
+    let rows = rounds
+        .iter()
+        .map(|(round, evaluation)| (round.to_string(), evaluation))
+        .chain(std::iter::once(("Overall".to_string(), overall)));
+    for (name, evaluation) in rows {
+        println!(
+            "{:<14} {:>6} {:>8.4} {:>9.4} {:>8}",
+            name,
+            evaluation.games,
+            evaluation.brier(),
+            evaluation.log_loss(),
+            evaluation.missing
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use serde::Serialize;
+
+use crate::odds::{Futures, FuturesMarket};
+use crate::summary::SimulationSummary;
+use crate::teams::normalize_name;
+
+/// Disagreements at least this large (in percentage points) are flagged
+const FLAG_THRESHOLD: f64 = 5.;
+
+/// A team's simulated chance of cashing a futures bet next to the market's
+#[derive(Debug, Clone, Serialize)]
+pub struct FuturesComparison {
+    pub market: FuturesMarket,
+    pub team: String,
+    /// Simulated chance, in percent
+    pub model: f64,
+    /// Chance implied by the odds, in percent
+    pub market_chance: f64,
+}
+
+impl FuturesComparison {
+    /// How far the simulations are above the market, in percentage points
+    pub fn diff(&self) -> f64 {
+        self.model - self.market_chance
+    }
+}
+
+/// Compare simulated Final Four and title chances with the futures market, largest
+/// disagreements first within each market
+pub fn futures_comparisons(
+    summary: &SimulationSummary,
+    futures: &Futures,
+) -> Vec<FuturesComparison> {
+    let mut rows = vec![];
+    for market in [FuturesMarket::Champion, FuturesMarket::FinalFour] {
+        let lines = match futures.markets.get(&market) {
+            Some(lines) => lines,
+            None => continue,
+        };
+        let mut market_rows = lines
+            .iter()
+            .map(|(team, market_prob)| {
+                let target = normalize_name(team);
+                let model_prob = summary
+                    .wins
+                    .keys()
+                    .find(|name| normalize_name(name) == target)
+                    .map(|name| summary.win_probability(name, market.round_num()))
+                    .unwrap_or(0.);
+                FuturesComparison {
+                    market,
+                    team: team.clone(),
+                    model: model_prob * 100.,
+                    market_chance: market_prob * 100.,
+                }
+            })
+            .collect::<Vec<_>>();
+        market_rows.sort_by(|a, b| b.diff().abs().partial_cmp(&a.diff().abs()).unwrap());
+        rows.extend(market_rows);
+    }
+    rows
+}
+
+/// Print simulated Final Four and title chances next to the futures market, largest
+/// disagreements first
+pub fn futures_report(summary: &SimulationSummary, futures: &Futures) {
+    let rows = futures_comparisons(summary, futures);
+    for market in [FuturesMarket::Champion, FuturesMarket::FinalFour] {
+        if !futures.markets.contains_key(&market) {
+            continue;
+        }
+        println!("{} ({} simulated runs)", market, summary.runs);
+        println!(
+            "{:<24} {:>8} {:>8} {:>8}",
+            "Team", "Model", "Market", "Diff"
+        );
+        for row in rows.iter().filter(|row| row.market == market) {
+            let diff = row.diff();
+            let flag = if diff.abs() >= FLAG_THRESHOLD {
+                if diff > 0. {
+                    "  <- model higher"
+                } else {
+                    "  <- market higher"
+                }
+            } else {
+                ""
+            };
+            println!(
+                "{:<24} {:>7.1}% {:>7.1}% {:>+8.1}{}",
+                row.team, row.model, row.market_chance, diff, flag
+            );
+        }
+        println!();
+    }
+}
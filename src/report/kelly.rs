@@ -0,0 +1,75 @@
+use serde::Serialize;
+
+use super::parlays::Bet;
+
+/// How much to stake on a bet by the Kelly criterion
+#[derive(Debug, Clone, Serialize)]
+pub struct KellyStake {
+    pub bet: Bet,
+    /// Full Kelly stake, as a share of the bankroll
+    pub full: f64,
+    /// The chosen fraction of the full stake, as a share of the bankroll
+    pub fractional: f64,
+    /// The fractional stake out of the bankroll, if one was given
+    pub amount: Option<f64>,
+}
+
+/// Kelly stakes for each bet, scaled down by `fraction` as well since full Kelly swings
+/// wildly whenever the model's chances are off. Every stake is sized on its own, so the
+/// total can be more than is wise to have out at once.
+pub fn kelly_stakes(bets: &[Bet], fraction: f64, bankroll: Option<f64>) -> Vec<KellyStake> {
+    bets.iter()
+        .map(|bet| {
+            let full = bet.kelly();
+            let fractional = full * fraction;
+            KellyStake {
+                bet: bet.clone(),
+                full,
+                fractional,
+                amount: bankroll.map(|bankroll| bankroll * fractional),
+            }
+        })
+        .collect()
+}
+
+/// Print each bet's Kelly stakes
+pub fn kelly_report(stakes: &[KellyStake], fraction: f64) {
+    if stakes.is_empty() {
+        println!("No upcoming game is priced below its simulated chance");
+        return;
+    }
+    let fractional = format!("{}x Kelly", fraction);
+    print!(
+        "{:<32} {:>8} {:>8} {:>8} {:>10} {:>12}",
+        "Bet", "Model", "Implied", "Edge", "Kelly", fractional
+    );
+    if stakes.iter().any(|stake| stake.amount.is_some()) {
+        print!(" {:>10}", "Stake");
+    }
+    println!();
+    for stake in stakes {
+        print!(
+            "{:<32} {:>7.1}% {:>7.1}% {:>+7.1}% {:>9.1}% {:>11.1}%",
+            stake.bet.describe(),
+            stake.bet.model * 100.,
+            stake.bet.implied * 100.,
+            stake.bet.edge() * 100.,
+            stake.full * 100.,
+            stake.fractional * 100.
+        );
+        if let Some(amount) = stake.amount {
+            print!(" {:>10.2}", amount);
+        }
+        println!();
+    }
+    let total: f64 = stakes.iter().map(|stake| stake.fractional).sum();
+    println!(
+        "Total staked: {:.1}% of the bankroll{}",
+        total * 100.,
+        if total > 1. {
+            ", more than it holds"
+        } else {
+            ""
+        }
+    );
+}
@@ -0,0 +1,43 @@
+use std::cmp::Reverse;
+
+use crate::scoring::{score_bracket, BracketScore, ScoringSystem};
+use crate::tournament::Tournament;
+
+/// Score every bracket against the real results, best first. Ties on points are broken by
+/// the most points still available.
+pub fn leaderboard(
+    brackets: &[(String, Tournament)],
+    actual: &Tournament,
+    scoring: &ScoringSystem,
+) -> Vec<(String, BracketScore)> {
+    let mut scores = brackets
+        .iter()
+        .map(|(name, bracket)| (name.clone(), score_bracket(bracket, actual, scoring)))
+        .collect::<Vec<_>>();
+    scores.sort_by_key(|(_, score)| Reverse((score.points, score.max_remaining)));
+    scores
+}
+
+/// Format the leaderboard as a table, one line per entry
+pub fn leaderboard_lines(scores: &[(String, BracketScore)]) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{:>4}  {:<24} {:>6} {:>8}  {}",
+        "Rank", "Entry", "Points", "Max left", "Champion"
+    )];
+    for (rank, (name, score)) in scores.iter().enumerate() {
+        let champion = match &score.champion {
+            Some(champion) if score.champion_alive => format!("{} (alive)", champion),
+            Some(champion) => format!("{} (out)", champion),
+            None => "-".to_string(),
+        };
+        lines.push(format!(
+            "{:>4}  {:<24} {:>6} {:>8}  {}",
+            rank + 1,
+            name,
+            score.points,
+            score.max_remaining,
+            champion
+        ));
+    }
+    lines
+}
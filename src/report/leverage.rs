@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+use crate::analytic::RoundProbabilities;
+use crate::public_picks::PublicPicks;
+use crate::tournament::RoundKind;
+
+/// How many contrarian picks to show
+const LEVERAGE_SHOWN: usize = 15;
+
+/// A team's chance of winning a round next to the share of the public picking it to
+#[derive(Debug, Clone, Serialize)]
+pub struct LeveragePick {
+    pub team: String,
+    pub round: usize,
+    pub chance: f64,
+    pub picked: f64,
+}
+
+impl LeveragePick {
+    pub fn leverage(&self) -> f64 {
+        self.chance - self.picked
+    }
+}
+
+/// Every pick ordered by how much a team's chance of winning a round exceeds the share of the
+/// public picking it to, since those gain the most ground on the rest of a pool when they
+/// hit. Only the given round is considered if there is one.
+pub fn leverage_picks(
+    probs: &RoundProbabilities,
+    picks: &PublicPicks,
+    round_num: Option<usize>,
+) -> Vec<LeveragePick> {
+    let rounds = match round_num {
+        Some(round_num) => round_num..=round_num,
+        None => 1..=6,
+    };
+    let mut leverage = probs
+        .probs
+        .keys()
+        .filter(|team| picks.picks.contains_key(*team))
+        .flat_map(|team| {
+            rounds.clone().map(move |round_num| LeveragePick {
+                team: team.clone(),
+                round: round_num,
+                chance: probs.win_probability(team, round_num),
+                picked: picks.pick_share(team, round_num),
+            })
+        })
+        .collect::<Vec<_>>();
+    leverage.sort_by(|a, b| b.leverage().partial_cmp(&a.leverage()).unwrap());
+    leverage
+}
+
+/// Print the picks with the most leverage over the public
+pub fn leverage_report(probs: &RoundProbabilities, picks: &PublicPicks, round_num: Option<usize>) {
+    println!(
+        "{:<24} {:<14} {:>8} {:>8} {:>9}",
+        "Team", "Round", "Chance", "Picked", "Leverage"
+    );
+    for pick in leverage_picks(probs, picks, round_num)
+        .into_iter()
+        .take(LEVERAGE_SHOWN)
+    {
+        println!(
+            "{:<24} {:<14} {:>7.1}% {:>7.1}% {:>+8.1}%",
+            pick.team,
+            RoundKind::Round(pick.round).to_string(),
+            pick.chance * 100.,
+            pick.picked * 100.,
+            pick.leverage() * 100.
+        );
+    }
+}
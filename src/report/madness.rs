@@ -0,0 +1,26 @@
+use crate::evaluate::Evaluation;
+use crate::tournament::RoundKind;
+
+/// Print how surprising the results were under the predictions for each round and over every
+/// game, against how surprising they were expected to be
+pub fn madness_report(rounds: &[(RoundKind, Evaluation)], overall: &Evaluation) {
+    println!(
+        "{:<14} {:>6} {:>10} {:>10} {:>8}",
+        "Round", "Games", "Surprisal", "Expected", "Madness"
+    );
+    let rows = rounds
+        .iter()
+        .map(|(round, evaluation)| (round.to_string(), evaluation))
+        .chain(std::iter::once(("Overall".to_string(), overall)));
+    for (name, evaluation) in rows {
+        println!(
+            "{:<14} {:>6} {:>10.2} {:>10.2} {:>+8.2}",
+            name,
+            evaluation.games,
+            evaluation.surprisal(),
+            evaluation.expected_surprisal(),
+            evaluation.madness()
+        );
+    }
+    println!("Surprisal is in bits: a coin flip going either way is 1, a 1 in 8 upset 3");
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::summary::SimulationSummary;
+use crate::teams::Team;
+use crate::tiebreaker::ScorePrediction;
+use crate::tournament::{RoundKind, Seed, Tournament};
+
+/// How many title contenders to list
+const TOP_CONTENDERS: usize = 10;
+
+/// Render a bracket as Markdown: the champion, Final Four and tiebreaker score up top, then a
+/// table of picks per round, then the most likely champions if a multi-run summary is given
+pub fn bracket_markdown(
+    bracket: &Tournament,
+    teams: &[Team],
+    summary: Option<&SimulationSummary>,
+    tiebreaker: Option<&ScorePrediction>,
+) -> String {
+    let mut out = String::new();
+    write_markdown(&mut out, bracket, teams, summary, tiebreaker)
+        .expect("Writing to a String can't fail");
+    out
+}
+
+fn write_markdown(
+    out: &mut String,
+    bracket: &Tournament,
+    teams: &[Team],
+    summary: Option<&SimulationSummary>,
+    tiebreaker: Option<&ScorePrediction>,
+) -> std::fmt::Result {
+    let seeds: HashMap<&str, Seed> = teams.iter().map(|t| (t.name(), t.seed)).collect();
+    let with_seed = |team: &str| match seeds.get(team) {
+        Some(seed) => format!("({}) {}", seed.0, team),
+        None => team.to_string(),
+    };
+
+    writeln!(out, "# Bracket\n")?;
+    if let Some(champion) = bracket.champion() {
+        writeln!(out, "**Champion:** {}\n", with_seed(champion))?;
+    }
+    let final_four = bracket
+        .final_four()
+        .into_iter()
+        .map(with_seed)
+        .collect::<Vec<_>>();
+    if !final_four.is_empty() {
+        writeln!(out, "**Final Four:** {}\n", final_four.join(", "))?;
+    }
+    if let Some(score) = tiebreaker {
+        writeln!(out, "**Tiebreaker:** {}\n", score)?;
+    }
+
+    for round_num in 1..=bracket.field.round_count() {
+        let round = &bracket.rounds[&RoundKind::Round(round_num)];
+        writeln!(out, "## {}\n", bracket.round_name(round.round))?;
+        writeln!(out, "| Matchup | Pick |")?;
+        writeln!(out, "| --- | --- |")?;
+        for matchup in &round.matchups {
+            let teams = match matchup.try_teams() {
+                Some(teams) => teams,
+                None => continue,
+            };
+            let pick = matchup.winning_team().map(with_seed).unwrap_or_default();
+            writeln!(
+                out,
+                "| {} vs {} | {} |",
+                with_seed(teams[0]),
+                with_seed(teams[1]),
+                pick
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    if let Some(summary) = summary.filter(|s| s.runs > 1) {
+        let mut contenders = summary
+            .wins
+            .keys()
+            .map(|team| (team, summary.win_probability(team, 6)))
+            .filter(|(_, prob)| *prob > 0.)
+            .collect::<Vec<_>>();
+        contenders.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        writeln!(out, "## Title odds ({} runs)\n", summary.runs)?;
+        writeln!(out, "| Team | Final Four | Champion |")?;
+        writeln!(out, "| --- | ---: | ---: |")?;
+        for (team, prob) in contenders.into_iter().take(TOP_CONTENDERS) {
+            writeln!(
+                out,
+                "| {} | {:.1}% | {:.1}% |",
+                with_seed(team),
+                summary.win_probability(team, 4) * 100.,
+                prob * 100.
+            )?;
+        }
+    }
+    Ok(())
+}
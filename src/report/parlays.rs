@@ -0,0 +1,208 @@
+use serde::Serialize;
+
+use crate::odds::{decimal_odds, Odds};
+use crate::summary::SimulationSummary;
+use crate::teams::normalize_name;
+
+/// How many parlays to show
+const PARLAYS_SHOWN: usize = 10;
+
+/// One side of a game's moneyline
+#[derive(Debug, Clone, Serialize)]
+pub struct Leg {
+    pub team: String,
+    pub opponent: String,
+    pub moneyline: f64,
+    /// Simulated chance of the team winning the game
+    pub model: f64,
+}
+
+/// A single bet or a parlay of legs from different games, all of which have to win
+#[derive(Debug, Clone, Serialize)]
+pub struct Bet {
+    pub legs: Vec<Leg>,
+    /// Simulated chance every leg wins, taking the games as independent
+    pub model: f64,
+    /// Chance the price implies, which the bet has to beat to pay off on average
+    pub implied: f64,
+    /// Total returned per unit staked when the bet wins
+    pub decimal_odds: f64,
+}
+
+impl Bet {
+    fn new(legs: Vec<Leg>) -> Self {
+        let model = legs.iter().map(|leg| leg.model).product();
+        let decimal_odds = legs.iter().map(|leg| decimal_odds(leg.moneyline)).product();
+        Self {
+            legs,
+            model,
+            implied: 1. / decimal_odds,
+            decimal_odds,
+        }
+    }
+
+    /// How far the simulated chance is above the implied one
+    pub fn edge(&self) -> f64 {
+        self.model - self.implied
+    }
+
+    /// Average profit per unit staked
+    pub fn expected_value(&self) -> f64 {
+        self.model * self.decimal_odds - 1.
+    }
+
+    /// Share of a bankroll to stake by the Kelly criterion, which grows it fastest in the long
+    /// run. Nothing for bets with no edge.
+    pub fn kelly(&self) -> f64 {
+        (self.expected_value() / (self.decimal_odds - 1.)).max(0.)
+    }
+
+    /// The legs and their moneylines, e.g. `Duke (+120) + Gonzaga (-150)`
+    pub fn describe(&self) -> String {
+        self.legs
+            .iter()
+            .map(|leg| format!("{} ({:+})", leg.team, leg.moneyline))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
+
+/// Bets worth making on the upcoming games
+#[derive(Debug, Clone, Serialize)]
+pub struct ParlaySuggestions {
+    /// Single bets with positive expected value, best first
+    pub singles: Vec<Bet>,
+    /// Parlays of those singles, best first
+    pub parlays: Vec<Bet>,
+}
+
+/// Find the sides of the lines in `odds` which the simulations give better chances than the
+/// price, and the parlays of up to `max_legs` of them
+pub fn parlay_suggestions(
+    summary: &SimulationSummary,
+    odds: &Odds,
+    max_legs: usize,
+) -> ParlaySuggestions {
+    let singles = single_bets(summary, odds);
+    let mut parlays = vec![];
+    let mut legs = vec![];
+    add_parlays(&singles, max_legs, &mut legs, &mut parlays);
+    parlays.sort_by(|a, b| b.expected_value().partial_cmp(&a.expected_value()).unwrap());
+    ParlaySuggestions { singles, parlays }
+}
+
+/// The side of each line in `odds` with positive expected value, if either has, best first.
+/// Only games in the next round of the simulated bracket are priced, the ones both teams are
+/// sure to be playing.
+pub fn single_bets(summary: &SimulationSummary, odds: &Odds) -> Vec<Bet> {
+    let mut singles = vec![];
+    for line in &odds.lines {
+        let probs = match game_probabilities(summary, &line.teams) {
+            Some(probs) => probs,
+            None => {
+                log::warn!(
+                    "Skipping {} vs {}, which isn't an upcoming game in the simulations",
+                    line.teams[0],
+                    line.teams[1]
+                );
+                continue;
+            }
+        };
+        // At most one side of a game can beat the price
+        let best = (0..2)
+            .map(|ind| {
+                Bet::new(vec![Leg {
+                    team: line.teams[ind].clone(),
+                    opponent: line.teams[1 - ind].clone(),
+                    moneyline: line.moneylines[ind],
+                    model: probs[ind],
+                }])
+            })
+            .find(|bet| bet.expected_value() > 0.);
+        singles.extend(best);
+    }
+    singles.sort_by(|a, b| b.expected_value().partial_cmp(&a.expected_value()).unwrap());
+    singles
+}
+
+/// Every combination of two or more singles, each from its own game, up to `max_legs`
+fn add_parlays(singles: &[Bet], max_legs: usize, legs: &mut Vec<Leg>, parlays: &mut Vec<Bet>) {
+    for (ind, single) in singles.iter().enumerate() {
+        if legs.len() == max_legs {
+            return;
+        }
+        legs.push(single.legs[0].clone());
+        if legs.len() > 1 {
+            parlays.push(Bet::new(legs.clone()));
+        }
+        add_parlays(&singles[ind + 1..], max_legs, legs, parlays);
+        legs.pop();
+    }
+}
+
+/// Each team's simulated chance of winning the game, None unless the game is next for both
+fn game_probabilities(summary: &SimulationSummary, teams: &[String; 2]) -> Option<[f64; 2]> {
+    let wins = [team_wins(summary, &teams[0]), team_wins(summary, &teams[1])];
+    let round_num = next_round(summary, &wins[0])?;
+    if next_round(summary, &wins[1])? != round_num {
+        return None;
+    }
+    let round_wins = [wins[0][round_num - 1], wins[1][round_num - 1]];
+    let total = (round_wins[0] + round_wins[1]) as f64;
+    if total == 0. {
+        return None;
+    }
+    Some([round_wins[0] as f64 / total, round_wins[1] as f64 / total])
+}
+
+/// A team's simulated wins in each round, none for teams which never won a game
+fn team_wins(summary: &SimulationSummary, team: &str) -> [usize; 6] {
+    let target = normalize_name(team);
+    summary
+        .wins
+        .iter()
+        .find(|(name, _)| normalize_name(name) == target)
+        .map_or([0; 6], |(_, wins)| *wins)
+}
+
+/// The first round a team didn't win in every simulated run, if it reached it in all of them
+fn next_round(summary: &SimulationSummary, wins: &[usize; 6]) -> Option<usize> {
+    let round_num = wins.iter().position(|&w| w < summary.runs)? + 1;
+    if round_num == 1 || wins[round_num - 2] == summary.runs {
+        Some(round_num)
+    } else {
+        None
+    }
+}
+
+/// Print the single bets and parlays with positive expected value
+pub fn parlay_report(suggestions: &ParlaySuggestions) {
+    if suggestions.singles.is_empty() {
+        println!("No upcoming game is priced below its simulated chance");
+        return;
+    }
+    println!("Single bets");
+    print_bets(&suggestions.singles);
+    if !suggestions.parlays.is_empty() {
+        println!();
+        println!("Parlays");
+        print_bets(&suggestions.parlays[..suggestions.parlays.len().min(PARLAYS_SHOWN)]);
+    }
+}
+
+fn print_bets(bets: &[Bet]) {
+    println!(
+        "{:<48} {:>8} {:>8} {:>8} {:>8}",
+        "Bet", "Model", "Implied", "Edge", "EV"
+    );
+    for bet in bets {
+        println!(
+            "{:<48} {:>7.1}% {:>7.1}% {:>+7.1}% {:>+7.1}%",
+            bet.describe(),
+            bet.model * 100.,
+            bet.implied * 100.,
+            bet.edge() * 100.,
+            bet.expected_value() * 100.
+        );
+    }
+}
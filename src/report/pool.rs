@@ -0,0 +1,22 @@
+use crate::pool::PoolOdds;
+
+/// Print each entry's chances of finishing in the top three of the pool, likeliest winner
+/// first
+pub fn pool_report(odds: &[PoolOdds]) {
+    let mut odds = odds.iter().collect::<Vec<_>>();
+    odds.sort_by(|a, b| b.finishes[0].partial_cmp(&a.finishes[0]).unwrap());
+    println!(
+        "{:<24} {:>7} {:>7} {:>7} {:>8}",
+        "Entry", "1st", "2nd", "3rd", "Avg pts"
+    );
+    for entry in odds {
+        println!(
+            "{:<24} {:>6.1}% {:>6.1}% {:>6.1}% {:>8.1}",
+            entry.name,
+            entry.finishes[0] * 100.,
+            entry.finishes[1] * 100.,
+            entry.finishes[2] * 100.,
+            entry.mean_points
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt::Write;
+
+use crate::tournament::{Region, RoundKind, Tournament};
+
+/// Render a bracket a region at a time, each region's games round by round up to its winner,
+/// followed by the rounds between regions such as the Final Four and championship
+pub fn region_bracket(bracket: &Tournament) -> String {
+    let mut out = String::new();
+    write_regions(&mut out, bracket).expect("Writing to a String can't fail");
+    out
+}
+
+fn write_regions(out: &mut String, bracket: &Tournament) -> std::fmt::Result {
+    let field = &bracket.field;
+    // Rounds with at least one game per region are played within the regions
+    let regional_rounds = (1..=field.round_count())
+        .take_while(|&round_num| {
+            field.regions > 1 && field.matchup_count(round_num) >= field.regions
+        })
+        .collect::<Vec<_>>();
+    for region_ind in 0..field.regions {
+        match Region::from_ind(region_ind) {
+            Some(region) => writeln!(out, "{}\n", region)?,
+            None => writeln!(out, "Region {}\n", region_ind + 1)?,
+        }
+        for &round_num in &regional_rounds {
+            let round = &bracket.rounds[&RoundKind::Round(round_num)];
+            let per_region = round.matchups.len() / field.regions;
+            writeln!(out, "{}", bracket.round_name(round.round))?;
+            for matchup in &round.matchups[region_ind * per_region..(region_ind + 1) * per_region] {
+                write!(out, "{}", matchup)?;
+            }
+            writeln!(out)?;
+        }
+    }
+    for round_num in regional_rounds.len() + 1..=field.round_count() {
+        write!(out, "{}", bracket.rounds[&RoundKind::Round(round_num)])?;
+    }
+    Ok(())
+}
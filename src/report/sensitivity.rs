@@ -0,0 +1,34 @@
+use crate::analytic::{RoundProbabilities, SwingGame};
+
+/// Print each contender's title chances and the games which swing them the most, as found by
+/// [`crate::analytic::swing_games`]
+pub fn sensitivity_report(probs: &RoundProbabilities, swings: &[SwingGame], delta: f64) {
+    let mut contender = None;
+    for swing in swings {
+        if contender != Some(&swing.contender) {
+            if contender.is_some() {
+                println!();
+            }
+            contender = Some(&swing.contender);
+            println!(
+                "{}, {:.1}% to win the title",
+                swing.contender,
+                probs.win_probability(&swing.contender, 6) * 100.
+            );
+            println!(
+                "{:<40} {:>8} {:>8} {:>8}",
+                format!("Game (first team's chance -/+{:.0}%)", delta * 100.),
+                "Down",
+                "Up",
+                "Swing"
+            );
+        }
+        println!(
+            "{:<40} {:>7.1}% {:>7.1}% {:>+7.1}%",
+            format!("{} vs {}", swing.teams[0], swing.teams[1]),
+            swing.title_if_down * 100.,
+            swing.title_if_up * 100.,
+            swing.swing() * 100.
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use crate::field::Field;
+use crate::scoring::Similarity;
+use crate::tournament::RoundKind;
+
+/// Print how often a bracket of `field` agrees with the consensus in each round and overall
+pub fn similarity_report(similarity: &Similarity, field: &Field) {
+    println!("Agreement with the consensus bracket");
+    for (round_ind, share) in similarity.rounds[..field.round_count()].iter().enumerate() {
+        println!(
+            "{:<14} {:>5.1}%",
+            field.round_name(RoundKind::Round(round_ind + 1)),
+            share * 100.
+        );
+    }
+    println!("{:<14} {:>5.1}%", "Overall", similarity.overall * 100.);
+    println!("{:<14} {:>5.1}%", "By points", similarity.weighted * 100.);
+}
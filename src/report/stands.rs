@@ -0,0 +1,46 @@
+use crate::analytic::RoundProbabilities;
+use crate::field::Field;
+use crate::scoring::Stand;
+use crate::tournament::RoundKind;
+
+/// Print every pick going against the most likely bracket, grouped by round, with each team's
+/// chance of winning that round and the points riding on it. Rounds are named for `field`.
+pub fn stands_report(
+    stands: &[Stand],
+    probs: &RoundProbabilities,
+    field: &Field,
+    total_points: u32,
+) {
+    if stands.is_empty() {
+        println!("Every pick matches the most likely bracket");
+        return;
+    }
+    for round_num in 1..=field.round_count() {
+        let round = RoundKind::Round(round_num);
+        let round_stands = stands
+            .iter()
+            .filter(|s| s.round == round)
+            .collect::<Vec<_>>();
+        if round_stands.is_empty() {
+            continue;
+        }
+        println!("{}", field.round_name(round));
+        for stand in round_stands {
+            println!(
+                "  {:<20} {:>5.1}%  over  {:<20} {:>5.1}%  {:>3} pts",
+                stand.pick,
+                probs.win_probability(&stand.pick, round_num) * 100.,
+                stand.instead_of,
+                probs.win_probability(&stand.instead_of, round_num) * 100.,
+                stand.points
+            );
+        }
+    }
+    let at_stake = stands.iter().map(|s| s.points).sum::<u32>();
+    println!(
+        "{} picks against the most likely bracket, with {} of {} points riding on them",
+        stands.len(),
+        at_stake,
+        total_points
+    );
+}
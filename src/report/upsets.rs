@@ -0,0 +1,46 @@
+use std::fmt::Write;
+
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+
+/// Summarize every game in a bracket where the worse seed won, grouped by round with a count
+/// for each round
+pub fn upset_summary(bracket: &Tournament) -> String {
+    let mut out = String::new();
+    write_upsets(&mut out, bracket).expect("Writing to a String can't fail");
+    out
+}
+
+fn write_upsets(out: &mut String, bracket: &Tournament) -> std::fmt::Result {
+    writeln!(out, "Upsets")?;
+    let mut total = 0;
+    for round_num in 1..=bracket.field.round_count() {
+        let round = &bracket.rounds[&RoundKind::Round(round_num)];
+        let upsets = round
+            .matchups
+            .iter()
+            .filter_map(|matchup| {
+                let winner = matchup.winner()?;
+                let loser = match winner {
+                    MatchupInd::Team1 => MatchupInd::Team2,
+                    MatchupInd::Team2 => MatchupInd::Team1,
+                };
+                let (winner_seed, loser_seed) = (matchup.seed(winner)?, matchup.seed(loser)?);
+                (winner_seed > loser_seed).then(|| {
+                    format!(
+                        "  ({}) {} over ({}) {}",
+                        winner_seed.0,
+                        matchup.team(winner).unwrap_or_default(),
+                        loser_seed.0,
+                        matchup.team(loser).unwrap_or_default()
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        total += upsets.len();
+        writeln!(out, "{}: {}", bracket.round_name(round.round), upsets.len())?;
+        for upset in upsets {
+            writeln!(out, "{}", upset)?;
+        }
+    }
+    writeln!(out, "Total: {}", total)
+}
@@ -0,0 +1,93 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tournament::{RoundKind, Tournament};
+
+/// Where the history of real results is kept
+pub const RESULTS_HISTORY_PATH: &str = "results_history.csv";
+
+/// A real game result and when it was first seen
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResultRecord {
+    /// Seconds since the Unix epoch
+    pub recorded_at: u64,
+    pub round: usize,
+    pub matchup: usize,
+    pub winner: String,
+    pub loser: String,
+}
+
+/// Every decided game in a bracket as (round, winner, loser)
+pub fn decided_games(actual: &Tournament) -> BTreeSet<(usize, String, String)> {
+    (1..=actual.field.round_count())
+        .flat_map(|round_num| {
+            actual.rounds[&RoundKind::Round(round_num)]
+                .matchups
+                .iter()
+                .filter_map(move |matchup| {
+                    Some((
+                        round_num,
+                        matchup.winning_team()?.to_string(),
+                        matchup.losing_team()?.to_string(),
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Load every result recorded so far, oldest first. Empty if nothing has been recorded.
+pub fn load_history(path: impl AsRef<Path>) -> anyhow::Result<Vec<ResultRecord>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut reader = csv::Reader::from_path(path)?;
+    Ok(reader.deserialize().collect::<Result<_, _>>()?)
+}
+
+/// Append the results in `actual` which aren't in the history yet, stamped with the current
+/// time. Returns the newly recorded results.
+pub fn record_results(
+    actual: &Tournament,
+    path: impl AsRef<Path>,
+) -> anyhow::Result<Vec<ResultRecord>> {
+    let path = path.as_ref();
+    let history = load_history(path)?;
+    let seen = history
+        .iter()
+        .map(|record| (record.round, record.matchup))
+        .collect::<HashSet<_>>();
+    let recorded_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let mut new = vec![];
+    for round_num in 1..=actual.field.round_count() {
+        for matchup in &actual.rounds[&RoundKind::Round(round_num)].matchups {
+            if seen.contains(&(round_num, matchup.index())) {
+                continue;
+            }
+            if let (Some(winner), Some(loser)) = (matchup.winning_team(), matchup.losing_team()) {
+                new.push(ResultRecord {
+                    recorded_at,
+                    round: round_num,
+                    matchup: matchup.index(),
+                    winner: winner.to_string(),
+                    loser: loser.to_string(),
+                });
+            }
+        }
+    }
+
+    let file = OpenOptions::new().append(true).create(true).open(path)?;
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(file.metadata()?.len() == 0)
+        .from_writer(file);
+    for record in &new {
+        writer.serialize(record)?;
+    }
+    writer.flush()?;
+    Ok(new)
+}
@@ -0,0 +1,172 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::{Deserialize, Serialize};
+
+/// Which random number generator draws game outcomes
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RngKind {
+    /// rand's standard generator, currently ChaCha12
+    Std,
+    /// Xoshiro256++, the fastest, for long runs where quality matters less
+    Xoshiro,
+    /// ChaCha8, fast and with stable output across rand versions
+    Chacha,
+}
+
+/// How draws are shared between runs
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Sampling {
+    /// Every run draws afresh
+    Independent,
+    /// Every second run mirrors the draws of the run before it (1 - u), which cancels out
+    /// some of the noise in averages over runs
+    Antithetic,
+    /// Latin hypercube sampling: across the planned runs, each draw lands once in each of as
+    /// many equal slices of [0, 1) as there are runs, in a random order. Upsets then come up
+    /// about as often as they should even over few runs.
+    Stratified,
+}
+
+/// One of the supported generators
+enum SimRng {
+    Std(StdRng),
+    Xoshiro(Xoshiro256PlusPlus),
+    Chacha(ChaCha8Rng),
+}
+
+impl SimRng {
+    fn new(kind: RngKind, seed: Option<u64>) -> Self {
+        // Seed from the OS when no seed is given
+        let seed = seed.unwrap_or_else(rand::random);
+        match kind {
+            RngKind::Std => SimRng::Std(StdRng::seed_from_u64(seed)),
+            RngKind::Xoshiro => SimRng::Xoshiro(Xoshiro256PlusPlus::seed_from_u64(seed)),
+            RngKind::Chacha => SimRng::Chacha(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+
+    fn uniform(&mut self) -> f64 {
+        match self {
+            SimRng::Std(rng) => rng.gen(),
+            SimRng::Xoshiro(rng) => rng.gen(),
+            SimRng::Chacha(rng) => rng.gen(),
+        }
+    }
+}
+
+/// Source of the uniform draws deciding games, run after run. The same kind, seed and
+/// sampling always give the same draws.
+pub struct Sampler {
+    rng: SimRng,
+    sampling: Sampling,
+    /// Number of runs started so far
+    runs: usize,
+    /// Whether this run mirrors the previous one
+    mirror: bool,
+    /// Draws made in the previous run, for antithetic runs to mirror
+    previous: Vec<f64>,
+    current: Vec<f64>,
+    /// Runs which stratified draws are spread over, after which a new set of strata begins
+    planned: usize,
+    /// For stratified sampling, the order in which the runs of the current set take the
+    /// slices of each draw
+    strata: Vec<Vec<usize>>,
+}
+
+impl Sampler {
+    /// A sampler for `runs` runs. More runs can be drawn, and with stratified sampling each
+    /// further `runs` are stratified as a set of their own.
+    pub fn new(kind: RngKind, seed: Option<u64>, sampling: Sampling, runs: usize) -> Self {
+        Self {
+            rng: SimRng::new(kind, seed),
+            sampling,
+            runs: 0,
+            mirror: false,
+            previous: vec![],
+            current: vec![],
+            planned: runs.max(1),
+            strata: vec![],
+        }
+    }
+
+    /// How this sampler shares draws between runs
+    pub fn sampling(&self) -> Sampling {
+        self.sampling
+    }
+
+    /// Start drawing for a new run
+    pub fn start_run(&mut self) {
+        // Runs pair up, with the second of each pair mirroring the first
+        self.mirror = self.sampling == Sampling::Antithetic && self.runs % 2 == 1;
+        // Each set of planned runs takes its own slices
+        let run_in_set = self.runs % self.planned;
+        if run_in_set == 0 {
+            self.strata.clear();
+        }
+        self.runs += 1;
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Next uniform draw in [0, 1)
+    pub fn draw(&mut self) -> f64 {
+        let mirrored = if self.mirror {
+            self.previous.get(self.current.len()).map(|u| 1. - u)
+        } else {
+            None
+        };
+        let draw = match mirrored {
+            Some(draw) => draw,
+            None if self.sampling == Sampling::Stratified => self.stratified_draw(),
+            None => self.rng.uniform(),
+        };
+        self.current.push(draw);
+        draw
+    }
+
+    /// A draw within this run's slice of [0, 1) for the next draw position
+    fn stratified_draw(&mut self) -> f64 {
+        let position = self.current.len();
+        while self.strata.len() <= position {
+            let order = self.shuffled(self.planned);
+            self.strata.push(order);
+        }
+        let stratum = self.strata[position][(self.runs - 1) % self.planned];
+        (stratum as f64 + self.rng.uniform()) / self.planned as f64
+    }
+
+    /// 0..len in a random order
+    fn shuffled(&mut self, len: usize) -> Vec<usize> {
+        let mut order = (0..len).collect::<Vec<_>>();
+        for i in (1..len).rev() {
+            let j = ((self.rng.uniform() * (i + 1) as f64) as usize).min(i);
+            order.swap(i, j);
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stratified_draws_cover_every_slice() {
+        let runs = 50;
+        let mut sampler = Sampler::new(RngKind::Chacha, Some(3), Sampling::Stratified, runs);
+        let mut slices = vec![vec![]; 3];
+        for _ in 0..runs {
+            sampler.start_run();
+            for slices in &mut slices {
+                slices.push((sampler.draw() * runs as f64) as usize);
+            }
+        }
+        for mut slices in slices {
+            slices.sort_unstable();
+            assert_eq!(slices, (0..runs).collect::<Vec<_>>());
+        }
+    }
+}
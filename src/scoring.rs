@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use serde::Serialize;
+
+use crate::field::Field;
+use crate::probabilities::ProbabilityMatrix;
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+
+/// Points awarded for a correct pick in each round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoringSystem {
+    pub points: [u32; 6],
+}
+
+impl Default for ScoringSystem {
+    /// The ESPN Tournament Challenge scoring of 10, 20, 40, 80, 160 and 320
+    fn default() -> Self {
+        Self {
+            points: [10, 20, 40, 80, 160, 320],
+        }
+    }
+}
+
+impl ScoringSystem {
+    /// Build from a list of points per round, which must have one entry per round
+    pub fn from_points(points: &[u32]) -> anyhow::Result<Self> {
+        let points = points
+            .try_into()
+            .map_err(|_| anyhow!("Expected points for 6 rounds, got {}", points.len()))?;
+        Ok(Self { points })
+    }
+
+    /// Scoring for a second chance pool from its points per round, starting at the Sweet 16.
+    /// The rounds before it are worth nothing.
+    pub fn second_chance(points: &[u32]) -> anyhow::Result<Self> {
+        match points {
+            &[sweet_16, elite_8, final_4, title] => Ok(Self {
+                points: [0, 0, sweet_16, elite_8, final_4, title],
+            }),
+            _ => Err(anyhow!(
+                "Expected points for the 4 second chance rounds, got {}",
+                points.len()
+            )),
+        }
+    }
+
+    /// Points a team is expected to earn for whoever picks it to win every game, given its
+    /// chance of winning in each round 1..=6
+    pub fn expected_points(&self, round_probs: &[f64; 6]) -> f64 {
+        self.points
+            .iter()
+            .zip(round_probs)
+            .map(|(points, prob)| *points as f64 * prob)
+            .sum()
+    }
+
+    /// Points for a perfect bracket of the given field, whose rounds score the points of the
+    /// first rounds of the system
+    pub fn max_points(&self, field: &Field) -> u32 {
+        (1..=field.round_count())
+            .map(|round_num| {
+                let round = RoundKind::Round(round_num);
+                self.round_points(round) * field.matchup_count(round_num) as u32
+            })
+            .sum()
+    }
+
+    fn round_points(&self, round: RoundKind) -> u32 {
+        match round {
+            RoundKind::PlayIn => 0,
+            RoundKind::Round(r) => self.points[r - 1],
+        }
+    }
+}
+
+/// How a bracket is doing against the real results
+#[derive(Debug, Clone, Serialize)]
+pub struct BracketScore {
+    /// Points from correct picks so far
+    pub points: u32,
+    /// Points still available from undecided games whose picked winner is alive
+    pub max_remaining: u32,
+    /// The picked champion
+    pub champion: Option<String>,
+    /// Whether the picked champion can still win the title
+    pub champion_alive: bool,
+}
+
+/// Score a bracket of picks against a bracket of real results
+pub fn score_bracket(
+    picks: &Tournament,
+    actual: &Tournament,
+    scoring: &ScoringSystem,
+) -> BracketScore {
+    let eliminated = actual.eliminated_teams();
+    let mut points = 0;
+    let mut max_remaining = 0;
+    for round_num in 1..=picks.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        let actual_round = &actual.rounds[&round];
+        for matchup in &picks.rounds[&round].matchups {
+            let pick = match matchup.winning_team() {
+                Some(pick) => pick,
+                None => continue,
+            };
+            match actual_round.matchups[matchup.index()].winning_team() {
+                Some(winner) if winner == pick => points += scoring.round_points(round),
+                Some(_) => {}
+                None if !eliminated.contains(pick) => max_remaining += scoring.round_points(round),
+                None => {}
+            }
+        }
+    }
+    let champion = picks.champion().map(str::to_string);
+    let champion_alive = champion
+        .as_deref()
+        .map(|c| !eliminated.contains(c))
+        .unwrap_or(false);
+    BracketScore {
+        points,
+        max_remaining,
+        champion,
+        champion_alive,
+    }
+}
+
+/// How bold a bracket's picks are
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChalkScore {
+    /// Picks where the worse seed wins
+    pub upsets: usize,
+    /// Sum over upsets of how many seed lines separate the teams
+    pub seed_gap: u32,
+    /// Sum of the log probabilities of the picks which have one, closer to 0 is chalkier
+    pub log_prob: f64,
+    /// How many picks had a probability to include in `log_prob`
+    pub priced_picks: usize,
+}
+
+/// Measure how chalky a bracket is. Pick probabilities come from those recorded while
+/// simulating, falling back to the head-to-head probabilities in `matrix`.
+pub fn chalk_score(bracket: &Tournament, matrix: Option<&ProbabilityMatrix>) -> ChalkScore {
+    let mut score = ChalkScore::default();
+    for round_num in 1..=bracket.field.round_count() {
+        for matchup in &bracket.rounds[&RoundKind::Round(round_num)].matchups {
+            let (winner, loser) = match (matchup.winner(), matchup.try_teams()) {
+                (Some(MatchupInd::Team1), Some([team1, team2])) => {
+                    ((MatchupInd::Team1, team1), (MatchupInd::Team2, team2))
+                }
+                (Some(MatchupInd::Team2), Some([team1, team2])) => {
+                    ((MatchupInd::Team2, team2), (MatchupInd::Team1, team1))
+                }
+                _ => continue,
+            };
+            if let (Some(winner_seed), Some(loser_seed)) =
+                (matchup.seed(winner.0), matchup.seed(loser.0))
+            {
+                if winner_seed > loser_seed {
+                    score.upsets += 1;
+                    score.seed_gap += (winner_seed.0 - loser_seed.0) as u32;
+                }
+            }
+            let prob = match (matchup.win_probability(), winner.0) {
+                (Some(prob), MatchupInd::Team1) => Some(prob),
+                (Some(prob), MatchupInd::Team2) => Some(1. - prob),
+                (None, _) => matrix.and_then(|m| m.get(winner.1, loser.1)),
+            };
+            if let Some(prob) = prob.filter(|prob| *prob > 0.) {
+                score.log_prob += prob.ln();
+                score.priced_picks += 1;
+            }
+        }
+    }
+    score
+}
+
+/// How closely a bracket's picks follow another bracket's
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Similarity {
+    /// Share of games in each round 1..=6 where both brackets pick the same winner, 0 for
+    /// rounds a smaller field doesn't play
+    pub rounds: [f64; 6],
+    /// Share of all games picked the same
+    pub overall: f64,
+    /// Share of the available points riding on the same picks, so later rounds count more
+    pub weighted: f64,
+}
+
+/// Compare a bracket against a consensus bracket, game by game
+pub fn similarity(
+    bracket: &Tournament,
+    consensus: &Tournament,
+    scoring: &ScoringSystem,
+) -> Similarity {
+    let mut res = Similarity::default();
+    let (mut agreed, mut games, mut agreed_points, mut total_points) = (0, 0, 0, 0);
+    for round_num in 1..=bracket.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        let consensus_round = &consensus.rounds[&round];
+        let mut round_agreed = 0;
+        let matchups = &bracket.rounds[&round].matchups;
+        for matchup in matchups {
+            let pick = matchup.winning_team();
+            if pick.is_some() && pick == consensus_round.matchups[matchup.index()].winning_team() {
+                round_agreed += 1;
+                agreed_points += scoring.round_points(round);
+            }
+            total_points += scoring.round_points(round);
+        }
+        res.rounds[round_num - 1] = round_agreed as f64 / matchups.len() as f64;
+        agreed += round_agreed;
+        games += matchups.len();
+    }
+    res.overall = agreed as f64 / games as f64;
+    res.weighted = agreed_points as f64 / total_points as f64;
+    res
+}
+
+/// A game where a bracket picks a different winner than a reference bracket
+#[derive(Debug, Clone, Serialize)]
+pub struct Stand {
+    pub round: RoundKind,
+    /// Winner picked by the bracket
+    pub pick: String,
+    /// Winner picked by the reference bracket
+    pub instead_of: String,
+    /// Points riding on the game
+    pub points: u32,
+}
+
+/// Every game, round by round, where a bracket goes against a reference bracket such as the
+/// most likely one
+pub fn stands(bracket: &Tournament, reference: &Tournament, scoring: &ScoringSystem) -> Vec<Stand> {
+    let mut res = vec![];
+    for round_num in 1..=bracket.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        let reference_round = &reference.rounds[&round];
+        for matchup in &bracket.rounds[&round].matchups {
+            let reference_pick = reference_round.matchups[matchup.index()].winning_team();
+            if let (Some(pick), Some(instead_of)) = (matchup.winning_team(), reference_pick) {
+                if pick != instead_of {
+                    res.push(Stand {
+                        round,
+                        pick: pick.to_string(),
+                        instead_of: instead_of.to_string(),
+                        points: scoring.round_points(round),
+                    });
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Load every bracket file in a directory, named after the file
+pub fn load_brackets(dir: &Path) -> anyhow::Result<Vec<(String, Tournament)>> {
+    let mut brackets = vec![];
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Could not read bracket directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let bracket = Tournament::load(&path)
+            .with_context(|| format!("Could not load bracket {}", path.display()))?;
+        brackets.push((name, bracket));
+    }
+    brackets.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(brackets)
+}
@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::gender::{gender, Gender};
+use crate::YEAR;
+
+/// Year of the tournament to follow, 0 until one is given
+static SEASON_YEAR: AtomicU32 = AtomicU32::new(0);
+
+/// Whether data files are named for the year and tournament they hold
+static SCOPED: AtomicBool = AtomicBool::new(false);
+
+/// Follow the tournament of the given year instead of [`YEAR`]'s
+pub fn set_year(year: u32) {
+    SEASON_YEAR.store(year, Ordering::Relaxed);
+}
+
+/// Year of the tournament being followed
+pub fn year() -> u32 {
+    match SEASON_YEAR.load(Ordering::Relaxed) {
+        0 => YEAR,
+        year => year,
+    }
+}
+
+/// Name data files for the year and tournament from now on, so that files for different ones
+/// sit side by side without being mixed up
+pub fn set_scoped(scoped: bool) {
+    SCOPED.store(scoped, Ordering::Relaxed);
+}
+
+/// Path of a data file such as teams.json, which is teams-2023-mens.json for the men's 2023
+/// tournament when files are named for their tournament
+pub fn data_path(name: &str) -> String {
+    if SCOPED.load(Ordering::Relaxed) {
+        scoped_name(name, year(), gender())
+    } else {
+        name.to_string()
+    }
+}
+
+fn scoped_name(name: &str, year: u32, gender: Gender) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => {
+            format!("{}-{}-{}.{}", stem, year, gender.key(), extension)
+        }
+        _ => format!("{}-{}-{}", name, year, gender.key()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_hold_year_and_tournament() {
+        assert_eq!(
+            scoped_name("teams.json", 2023, Gender::Mens),
+            "teams-2023-mens.json"
+        );
+        assert_eq!(
+            scoped_name("simulated_games.csv", 2022, Gender::Womens),
+            "simulated_games-2022-womens.csv"
+        );
+        assert_eq!(
+            scoped_name("results", 2023, Gender::Mens),
+            "results-2023-mens"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::anyhow;
+use serde::Serialize;
+
+use crate::scoring::ScoringSystem;
+use crate::summary::SimulationSummary;
+use crate::tournament::{RoundKind, Tournament};
+
+/// Where the simulated second chance bracket is saved, apart from the full bracket
+pub const SECOND_CHANCE_BRACKET_PATH: &str = "second_chance_bracket.json";
+
+/// Second chance pools start over at the Sweet 16
+pub const FIRST_ROUND: usize = 3;
+
+/// A second chance bracket's picks and how they should score
+#[derive(Debug, Clone, Serialize)]
+pub struct SecondChanceScore {
+    /// Points the picks are expected to earn, from how often each picked team won its game
+    /// over the simulated runs
+    pub expected_points: f64,
+    /// Points for getting every second chance game right
+    pub max_points: u32,
+}
+
+/// The 16 teams left once the first two rounds are decided, in bracket order. Fails while
+/// the Sweet 16 isn't set yet.
+pub fn survivors(bracket: &Tournament) -> anyhow::Result<Vec<String>> {
+    let undecided = (1..FIRST_ROUND)
+        .flat_map(|round_num| &bracket.rounds[&RoundKind::Round(round_num)].matchups)
+        .filter(|matchup| !matchup.completed())
+        .count();
+    if undecided > 0 {
+        return Err(anyhow!(
+            "The Sweet 16 isn't set yet, {} games before it are still to be played",
+            undecided
+        ));
+    }
+    Ok(bracket.rounds[&RoundKind::Round(FIRST_ROUND)]
+        .matchups
+        .iter()
+        .flat_map(|matchup| matchup.teams())
+        .collect())
+}
+
+/// Score the second chance rounds of a simulated bracket against the simulation summary
+pub fn score(
+    picks: &Tournament,
+    summary: &SimulationSummary,
+    scoring: &ScoringSystem,
+) -> SecondChanceScore {
+    let expected_points = (FIRST_ROUND..=picks.field.round_count())
+        .flat_map(|round_num| {
+            picks.rounds[&RoundKind::Round(round_num)]
+                .matchups
+                .iter()
+                .filter_map(move |matchup| Some((round_num, matchup.winning_team()?)))
+        })
+        .map(|(round_num, team)| {
+            scoring.points[round_num - 1] as f64 * summary.win_probability(team, round_num)
+        })
+        .sum();
+    SecondChanceScore {
+        expected_points,
+        max_points: scoring.max_points(&picks.field),
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+/// Version of the selectors file this build reads, bumped whenever its fields change
+pub const SELECTORS_VERSION: u32 = 3;
+
+/// Selectors for the 538 page as of the last release, and the file to copy when changing them
+const DEFAULT_SELECTORS: &str = include_str!("../data/selectors.json");
+
+/// Placeholders in the templates for the team's HTML name and the node depth
+const TEAM: &str = "{team}";
+const DEPTH: &str = "{depth}";
+
+static SELECTORS: OnceCell<Selectors> = OnceCell::new();
+
+/// Where things are found on the 538 page. These are read from a file so that a change to
+/// the page's markup can be followed without a new build, see data/selectors.json.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Selectors {
+    pub version: u32,
+    /// The teams table
+    pub team_table: String,
+    /// Rows of the teams table, one per team
+    pub team_rows: String,
+    /// Cells of a teams table row with the team's chance of reaching each round, in order.
+    /// The last six are its chances of winning its games in rounds 1 to 6
+    pub round_cells: String,
+    /// The drawn bracket, holding all the team nodes
+    pub bracket: String,
+    /// A team's node in the bracket
+    pub node: String,
+    /// Template for a team's labels, which show its win% while its node is hovered. `{depth}`
+    /// is the depth of the node the team advances to.
+    pub win_label: String,
+    /// Template for the id of a team's node, where `{depth}` counts rounds back from the
+    /// champion's node at depth 0, e.g. `node-{team}-{depth}`
+    pub node_id: String,
+    /// A game in progress, among the live games shown on game days
+    pub live_game: String,
+    /// Within a live game, each team's name in order
+    pub live_team: String,
+    /// Within a live game, each team's live chance of winning in the same order
+    pub live_win_prob: String,
+}
+
+impl Selectors {
+    fn parse(data: &str) -> anyhow::Result<Self> {
+        let selectors: Selectors = serde_json::from_str(data)?;
+        if selectors.version != SELECTORS_VERSION {
+            return Err(anyhow!(
+                "Selectors are version {}, but this build reads version {}",
+                selectors.version,
+                SELECTORS_VERSION
+            ));
+        }
+        selectors.validate()?;
+        Ok(selectors)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let (_, separator, _) = self
+            .node_id_parts()
+            .ok_or_else(|| anyhow!("node_id should hold {} and then {}", TEAM, DEPTH))?;
+        if separator.is_empty() {
+            return Err(anyhow!(
+                "node_id needs something between {} and {}",
+                TEAM,
+                DEPTH
+            ));
+        }
+        #[cfg(feature = "driver")]
+        for (name, selector) in [
+            ("team_table", self.team_table.clone()),
+            ("team_rows", self.team_rows.clone()),
+            ("round_cells", self.round_cells.clone()),
+            ("bracket", self.bracket.clone()),
+            ("node", self.node.clone()),
+            ("win_label", self.win_label("Kentucky", 6)),
+            ("live_game", self.live_game.clone()),
+            ("live_team", self.live_team.clone()),
+            ("live_win_prob", self.live_win_prob.clone()),
+        ] {
+            scraper::Selector::parse(&selector)
+                .map_err(|e| anyhow!("Bad {} selector {}: {:?}", name, selector, e))?;
+        }
+        Ok(())
+    }
+
+    /// Selector for a team's win% labels
+    pub fn win_label(&self, team: &str, depth: usize) -> String {
+        fill(&self.win_label, team, depth)
+    }
+
+    /// Id of a team's node at the given depth
+    pub fn node_id(&self, team: &str, depth: usize) -> String {
+        fill(&self.node_id, team, depth)
+    }
+
+    /// Team and depth parts of a node id, None if it doesn't fit the template
+    pub fn split_node_id<'a>(&self, id: &'a str) -> Option<(&'a str, &'a str)> {
+        let (prefix, separator, suffix) = self.node_id_parts()?;
+        id.strip_prefix(prefix)?
+            .strip_suffix(suffix)?
+            .rsplit_once(separator)
+    }
+
+    /// What comes before the team, between the team and depth, and after the depth in ids
+    fn node_id_parts(&self) -> Option<(&str, &str, &str)> {
+        let (prefix, rest) = self.node_id.split_once(TEAM)?;
+        let (separator, suffix) = rest.split_once(DEPTH)?;
+        Some((prefix, separator, suffix))
+    }
+}
+
+fn fill(template: &str, team: &str, depth: usize) -> String {
+    template
+        .replace(TEAM, team)
+        .replace(DEPTH, &depth.to_string())
+}
+
+/// Use the selectors in a file instead of the built-in ones. Only possible before the
+/// selectors are first used.
+pub fn load(path: &Path) -> anyhow::Result<()> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Could not read selectors {}", path.display()))?;
+    let selectors = Selectors::parse(&data)
+        .with_context(|| format!("Could not load selectors {}", path.display()))?;
+    log::info!("Using selectors from {}", path.display());
+    SELECTORS
+        .set(selectors)
+        .map_err(|_| anyhow!("Selectors are already in use"))
+}
+
+/// Selectors for the 538 page, from the file given to [`load`] or else the built-in ones
+pub fn selectors() -> &'static Selectors {
+    SELECTORS.get_or_init(|| Selectors::parse(DEFAULT_SELECTORS).expect("Bad built-in selectors"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_selectors_are_valid() {
+        let selectors = Selectors::parse(DEFAULT_SELECTORS).unwrap();
+        assert_eq!(selectors.node_id("Kentucky", 6), "node-Kentucky-6");
+        assert_eq!(
+            selectors.win_label("Kentucky", 5),
+            "g.nodes text[depth=\"5\"].Kentucky"
+        );
+    }
+
+    #[test]
+    fn node_ids_split_by_template() {
+        let mut selectors = Selectors::parse(DEFAULT_SELECTORS).unwrap();
+        assert_eq!(
+            selectors.split_node_id("node-TexasAM-CorpusChristi-6"),
+            Some(("TexasAM-CorpusChristi", "6"))
+        );
+        assert_eq!(selectors.split_node_id("Kentucky-6"), None);
+
+        selectors.node_id = "bracket_{team}__d{depth}".to_string();
+        assert_eq!(selectors.node_id("Kentucky", 6), "bracket_Kentucky__d6");
+        assert_eq!(
+            selectors.split_node_id("bracket_Kentucky__d6"),
+            Some(("Kentucky", "6"))
+        );
+    }
+
+    #[test]
+    fn bad_selectors_are_rejected() {
+        let mut selectors = Selectors::parse(DEFAULT_SELECTORS).unwrap();
+        selectors.version = SELECTORS_VERSION + 1;
+        assert!(Selectors::parse(&serde_json::to_string(&selectors).unwrap()).is_err());
+
+        for node_id in ["node-{depth}-{team}", "node-{team}", "node-{team}{depth}"] {
+            let mut selectors = Selectors::parse(DEFAULT_SELECTORS).unwrap();
+            selectors.node_id = node_id.to_string();
+            assert!(selectors.validate().is_err(), "{}", node_id);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::tournament::MatchupInd;
+
+/// Games a team must win to take a best-of series
+pub fn wins_needed(best_of: usize) -> usize {
+    best_of / 2 + 1
+}
+
+/// Chance the first team takes a best-of series, winning each game independently with
+/// `game_prob`. A single game is just `game_prob`.
+pub fn series_win_probability(game_prob: f64, best_of: usize) -> f64 {
+    let wins = wins_needed(best_of);
+    // The series ends on the first team's deciding win, after fewer losses than that
+    (0..wins)
+        .map(|losses| {
+            binomial(wins - 1 + losses, losses)
+                * game_prob.powi(wins as i32)
+                * (1. - game_prob).powi(losses as i32)
+        })
+        .sum()
+}
+
+/// Play out a best-of series game by game, the first team winning a game whenever `draw`
+/// comes in under `game_prob`. Returns the winner and the games each team won.
+pub fn play_series(
+    game_prob: f64,
+    best_of: usize,
+    mut draw: impl FnMut() -> f64,
+) -> (MatchupInd, [usize; 2]) {
+    let wins = wins_needed(best_of);
+    let mut games = [0, 0];
+    loop {
+        let winner = if draw() < game_prob {
+            MatchupInd::Team1
+        } else {
+            MatchupInd::Team2
+        };
+        games[winner.to_ind()] += 1;
+        if games[winner.to_ind()] == wins {
+            return (winner, games);
+        }
+    }
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1., |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn single_games_keep_the_game_probability() {
+        for prob in [0., 0.2, 0.5, 0.93, 1.] {
+            assert!((series_win_probability(prob, 1) - prob).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn series_favor_the_better_team() {
+        for best_of in [3, 5, 7, 9] {
+            assert!((series_win_probability(0.5, best_of) - 0.5).abs() < 1e-12);
+            let favorite = series_win_probability(0.6, best_of);
+            assert!(favorite > series_win_probability(0.6, best_of - 2));
+            assert!((favorite + series_win_probability(0.4, best_of) - 1.).abs() < 1e-12);
+        }
+        // Best of 7 at 60% a game, a textbook value
+        assert!((series_win_probability(0.6, 7) - 0.710208).abs() < 1e-6);
+    }
+
+    #[test]
+    fn played_series_match_the_series_probability() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let runs = 100_000;
+        let mut won = 0;
+        for _ in 0..runs {
+            let (winner, games) = play_series(0.6, 7, || rng.gen());
+            assert_eq!(games[winner.to_ind()], 4);
+            assert!(games[1 - winner.to_ind()] < 4);
+            if winner == MatchupInd::Team1 {
+                won += 1;
+            }
+        }
+        let expected = series_win_probability(0.6, 7);
+        assert!((won as f64 / runs as f64 - expected).abs() < 0.01);
+    }
+}
@@ -0,0 +1,132 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Extension, Path};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::session::Session;
+use crate::simulate::{simulate, SimulateOptions, BRACKET_PATH};
+use crate::summary::{SimulationSummary, SUMMARY_PATH};
+use crate::teams::normalize_name;
+use crate::tournament::Tournament;
+use crate::{metrics, season};
+
+/// State shared between requests. The driver sits behind a mutex since only one simulation
+/// can click through the page at a time.
+struct ServerState {
+    driver: Arc<Mutex<Session>>,
+    options: SimulateOptions,
+}
+
+/// Error returned to clients as a JSON body
+struct ApiError(StatusCode, anyhow::Error);
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(e: E) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, e.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1.to_string() }))).into_response()
+    }
+}
+
+/// A team's chances of winning in each round, from the latest simulation summary
+#[derive(Serialize)]
+struct TeamOdds {
+    team: String,
+    runs: usize,
+    /// Chance of winning the round 1..=6 game
+    win_probability: Vec<f64>,
+}
+
+/// Serve simulation endpoints over HTTP until Ctrl-C is pressed:
+/// - `POST /simulate` runs a simulation and returns the resulting bracket
+/// - `GET /bracket` returns the last saved bracket
+/// - `GET /odds/:team` returns a team's chances per round from the last simulation summary
+/// - `GET /metrics` returns scrape and simulation metrics for Prometheus
+pub async fn serve(
+    driver: Arc<Mutex<Session>>,
+    options: SimulateOptions,
+    port: u16,
+) -> anyhow::Result<()> {
+    let state = Arc::new(ServerState { driver, options });
+    let app = Router::new()
+        .route("/simulate", post(simulate_handler))
+        .route("/bracket", get(bracket_handler))
+        .route("/odds/:team", get(odds_handler))
+        .route("/metrics", get(metrics_handler))
+        .layer(Extension(state));
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    log::info!("Listening on http://{}", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+            log::info!("Shutting down server");
+        })
+        .await?;
+    Ok(())
+}
+
+/// Serve only `GET /metrics`, for tasks which run for a long time without the full server.
+/// Runs until the task is dropped.
+pub async fn serve_metrics(port: u16) -> anyhow::Result<()> {
+    let app = Router::new().route("/metrics", get(metrics_handler));
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    log::info!("Serving metrics on http://{}/metrics", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+async fn simulate_handler(
+    Extension(state): Extension<Arc<ServerState>>,
+) -> Result<Json<Tournament>, ApiError> {
+    let driver = state.driver.lock().await;
+    let (bracket, _) = simulate(&driver, &state.options).await?;
+    Ok(Json(bracket))
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+}
+
+async fn bracket_handler() -> Result<Json<Tournament>, ApiError> {
+    Ok(Json(Tournament::load(season::data_path(BRACKET_PATH))?))
+}
+
+async fn odds_handler(Path(team): Path<String>) -> Result<Json<TeamOdds>, ApiError> {
+    let summary = SimulationSummary::load(season::data_path(SUMMARY_PATH))?;
+    let target = normalize_name(&team);
+    let name = summary
+        .wins
+        .keys()
+        .find(|name| normalize_name(name) == target)
+        .ok_or_else(|| {
+            ApiError(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("No simulated odds for {}", team),
+            )
+        })?;
+    Ok(Json(TeamOdds {
+        team: name.clone(),
+        runs: summary.runs,
+        win_probability: (1..=6)
+            .map(|round_num| summary.win_probability(name, round_num))
+            .collect(),
+    }))
+}
@@ -0,0 +1,173 @@
+use std::future::Future;
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use thirtyfour::error::WebDriverResult;
+use thirtyfour::{By, WebDriver, WebElement};
+use tokio::time::sleep;
+
+use crate::{throttle, timeouts};
+
+/// How many times a page load, hover or click is tried before giving up on it
+const ATTEMPTS: usize = 3;
+
+/// Wait before trying a failed command again, doubled after each failure
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// How often to look again for an element which hasn't shown up yet
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A browser session, through which every module drives the page. Loading pages, finding
+/// elements, hovering and clicking go through its methods, which keep to the rate limit,
+/// retry what can fail for a moment on a busy page, log how long each command took and say
+/// what was being attempted when one fails. Anything else can be called on the
+/// [`WebDriver`] it derefs to.
+pub struct Session {
+    driver: WebDriver,
+}
+
+impl Session {
+    pub fn new(driver: WebDriver) -> Self {
+        Self { driver }
+    }
+
+    /// End the session and close its browser
+    pub async fn quit(self) -> anyhow::Result<()> {
+        Ok(self.driver.quit().await?)
+    }
+
+    /// Load a page
+    pub async fn goto(&self, url: &str) -> anyhow::Result<()> {
+        self.retry(&format!("load {}", url), || self.driver.get(url))
+            .await
+    }
+
+    /// Find an element, polling until it shows up or the element timeout passes. Useful on
+    /// pages which render content after load
+    pub async fn find(&self, by: By<'_>) -> anyhow::Result<WebElement<'_>> {
+        self.wait_for(by, timeouts::element_timeout()).await
+    }
+
+    /// Find an element, polling until it shows up or `timeout` passes
+    pub async fn wait_for(&self, by: By<'_>, timeout: Duration) -> anyhow::Result<WebElement<'_>> {
+        let start = Instant::now();
+        loop {
+            throttle::rate_limit().await;
+            match self.driver.find_element(by).await {
+                Ok(ele) => {
+                    log::debug!("Found {:?} in {:?}", by, start.elapsed());
+                    return Ok(ele);
+                }
+                Err(e) if start.elapsed() > timeout => {
+                    return Err(e)
+                        .with_context(|| format!("Could not find {:?} within {:?}", by, timeout))
+                }
+                Err(_) => sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Find an element and wait until it is displayed, so that it can be hovered or clicked
+    pub async fn wait_for_visible(
+        &self,
+        by: By<'_>,
+        timeout: Duration,
+    ) -> anyhow::Result<WebElement<'_>> {
+        let start = Instant::now();
+        loop {
+            let ele = self.wait_for(by, timeout).await?;
+            throttle::rate_limit().await;
+            if ele.is_displayed().await? {
+                return Ok(ele);
+            }
+            if start.elapsed() > timeout {
+                return Err(anyhow!(
+                    "{:?} never became visible within {:?}",
+                    by,
+                    timeout
+                ));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Every element matching right now, which may be none
+    pub async fn find_all(&self, by: By<'_>) -> anyhow::Result<Vec<WebElement<'_>>> {
+        self.retry(&format!("find {:?}", by), || self.driver.find_elements(by))
+            .await
+    }
+
+    /// Move the mouse over an element
+    pub async fn hover(&self, ele: &WebElement<'_>) -> anyhow::Result<()> {
+        self.retry("hover", || {
+            self.driver
+                .action_chain()
+                .move_to_element_center(ele)
+                .perform()
+        })
+        .await?;
+        throttle::action_delay().await;
+        Ok(())
+    }
+
+    /// Click an element where it sits on the page. This also clicks elements which aren't
+    /// clickable themselves, such as the 538 team nodes, since whatever clickable element
+    /// covers the same spot takes the click. Only moving to the element is retried: a click
+    /// can reach the page and still report an error, and clicking again would undo it, so
+    /// callers check whether the click took effect.
+    pub async fn click(&self, ele: &WebElement<'_>) -> anyhow::Result<()> {
+        self.retry("move to click", || {
+            self.driver
+                .action_chain()
+                .move_to_element_center(ele)
+                .perform()
+        })
+        .await?;
+        throttle::rate_limit().await;
+        let clicked = self.driver.action_chain().click().perform().await;
+        throttle::rate_limit().await;
+        self.driver.action_chain().reset_actions().await?;
+        clicked.context("Could not click")?;
+        throttle::action_delay().await;
+        Ok(())
+    }
+
+    /// Run a command under the rate limit, trying again after a short wait if the WebDriver
+    /// server reports an error
+    async fn retry<T, F, Fut>(&self, what: &str, mut command: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = WebDriverResult<T>>,
+    {
+        let mut delay = RETRY_DELAY;
+        for attempt in 1..=ATTEMPTS {
+            throttle::rate_limit().await;
+            let start = Instant::now();
+            match command().await {
+                Ok(value) => {
+                    log::debug!("Took {:?} to {}", start.elapsed(), what);
+                    return Ok(value);
+                }
+                Err(e) if attempt == ATTEMPTS => {
+                    return Err(e)
+                        .with_context(|| format!("Could not {} after {} attempts", what, ATTEMPTS))
+                }
+                Err(e) => {
+                    log::debug!("Failed to {} (attempt {}): {}", what, attempt, e);
+                    sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+        unreachable!("The last attempt returns")
+    }
+}
+
+impl Deref for Session {
+    type Target = WebDriver;
+
+    fn deref(&self) -> &WebDriver {
+        &self.driver
+    }
+}
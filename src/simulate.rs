@@ -1,97 +1,985 @@
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
 
 use anyhow::{anyhow, Context};
-use rand::random;
+use futures::future::join_all;
 use scraper::{Html, Selector};
-use thirtyfour::{By, WebDriver, WebElement};
+use serde::Serialize;
+use thirtyfour::{By, WebElement};
+use tokio::time::sleep;
 
-use crate::teams::{construct_html_name, load_teams};
-use crate::tournament::{RoundKind, Tournament};
-use crate::URL;
+use crate::adjustments::Adjustments;
+use crate::audit::{self, AuditEntry};
+use crate::blend::SourceWeights;
+use crate::games_log::{GameRecord, GamesFormat, GamesLog};
+use crate::odds::Odds;
+use crate::overrides::MatchupOverrides;
+use crate::parse::{
+    construct_html_name, extract_team_round_from_id, node_id, parse_win_probability,
+};
+use crate::probabilities::{sharpen, ProbabilityMatrix, PROBABILITIES_PATH};
+use crate::rng::{RngKind, Sampler, Sampling};
+use crate::seed_history::SeedPrior;
+use crate::selectors::selectors;
+use crate::session::Session;
+use crate::summary::{SimulationSummary, SUMMARY_PATH};
+use crate::teams::{load_teams, teams_saved, write_teams};
+use crate::tiebreaker::Efficiencies;
+use crate::timings::{self, Phase};
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+use crate::{cache, metrics, output, preflight, season, throttle, timeouts};
 
-/// For example, node-Kentucky-6 -> ("Kentucky", 6)
-fn extract_team_round_from_id(id: &str) -> anyhow::Result<(String, RoundKind)> {
-    let (left, seed_str) = id
-        .rsplit_once('-')
-        .ok_or_else(|| anyhow!("Unexpected format"))?;
-    let (_, team) = left
-        .split_once('-')
-        .ok_or_else(|| anyhow!("Unexpected format"))?;
-    let round = RoundKind::Round(7 - seed_str.parse::<usize>()?);
-    Ok((team.to_string(), round))
+/// How many times to click a team before deciding the page isn't responding to it
+const CLICK_ATTEMPTS: usize = 2;
+
+/// How many times to reload the page and try again when clicking a team fails
+const MAX_RECOVERIES: usize = 2;
+
+/// Where the simulated bracket is saved
+pub const BRACKET_PATH: &str = "bracket.json";
+
+/// Cache key for the HTML of the bracket nodes
+const BRACKET_CACHE_KEY: &str = "bracket_nodes";
+
+/// Knobs for a simulation run
+#[derive(Debug)]
+pub struct SimulateOptions {
+    /// Sportsbook odds to use for games they cover
+    pub odds: Option<Odds>,
+    /// Efficiency ratings to predict games from
+    pub efficiencies: Option<Efficiencies>,
+    /// Historical seed matchup win rates to predict games from
+    pub seed_prior: Option<SeedPrior>,
+    /// How much each prediction source counts when blending them
+    pub weights: SourceWeights,
+    /// Explicit probabilities for specific games, used in place of every other source
+    pub overrides: Option<MatchupOverrides>,
+    /// Manual team bonuses and penalties applied to every game's probability
+    pub adjustments: Option<Adjustments>,
+    /// Logit scaling applied to each game's probability before drawing, see [`sharpen`]
+    pub sharpen: f64,
+    /// Number of times to simulate the tournament
+    pub runs: usize,
+    /// First round to simulate. Games in earlier rounds without a result are taken from
+    /// `picks` instead.
+    pub start_round: usize,
+    /// Existing picks to use for games before `start_round`
+    pub picks: Option<Tournament>,
+    /// Last round to simulate, leaving the games after it empty
+    pub through_round: usize,
+    /// Generator deciding games
+    pub rng: RngKind,
+    /// Seed for the generator, for reproducible runs
+    pub seed: Option<u64>,
+    /// How draws are shared between runs
+    pub sampling: Sampling,
+    /// File format for the log of every simulated game
+    pub games_format: GamesFormat,
+    /// Also print every game as a line of JSON on stdout as soon as it is drawn
+    pub stream_games: bool,
+    /// Where the simulated bracket is saved
+    pub bracket_path: PathBuf,
+    /// Bracket saved by an earlier run, whose picks are kept rather than drawn again
+    pub saved: Option<Tournament>,
+    /// Pick games whose probability can't be read or whose pick can't be clicked without the
+    /// page, rather than giving up on the run
+    pub best_effort: bool,
 }
 
-/// Simulate the tournament using 538 predictions from the current bracket state.
-pub async fn simulate(driver: &WebDriver) -> anyhow::Result<()> {
-    driver.get(URL).await?;
+impl Default for SimulateOptions {
+    fn default() -> Self {
+        Self {
+            odds: None,
+            efficiencies: None,
+            seed_prior: None,
+            weights: SourceWeights::default(),
+            overrides: None,
+            adjustments: None,
+            sharpen: 1.,
+            runs: 1,
+            start_round: 1,
+            picks: None,
+            through_round: 6,
+            rng: RngKind::Std,
+            seed: None,
+            sampling: Sampling::Independent,
+            games_format: GamesFormat::Csv,
+            stream_games: false,
+            bracket_path: PathBuf::from(season::data_path(BRACKET_PATH)),
+            saved: None,
+            best_effort: false,
+        }
+    }
+}
 
-    let current_teams = get_current_teams(driver).await?;
-    let round1_teams = current_teams.get(&RoundKind::Round(1)).unwrap();
-    let mut teams = load_teams()?;
+/// A game picked without the page after reading or clicking it failed
+#[derive(Debug, Clone)]
+struct DegradedPick {
+    round: RoundKind,
+    teams: [String; 2],
+    winner: String,
+    /// Chance of the first team winning the pick was drawn at, None for existing picks
+    win_prob: Option<f64>,
+    /// What went wrong on the page
+    error: String,
+}
 
-    // Filter out teams who lost in the play-in. TODO: actually handle the play-in
-    teams.retain(|team| {
-        let html_name = construct_html_name(team.name());
-        round1_teams.contains(&html_name)
-    });
-    let mut tournament = Tournament::new(&mut teams, current_teams);
+/// How the games of a run were settled
+#[derive(Debug, Clone, Default)]
+struct PlayedGames {
+    /// Games the page already showed a winner for
+    decided: usize,
+    /// Games kept from the saved bracket
+    kept: usize,
+    /// Kept games never clicked into the page, since no later game needed them
+    unclicked: usize,
+    /// Games drawn, or filled in from existing picks
+    simulated: usize,
+    /// Games picked without the page in a best effort run
+    degraded: Vec<DegradedPick>,
+}
 
-    for round_num in 1..=6 {
-        let mut winning_teams = vec![];
+/// Simulate the tournament using 538 predictions from the current bracket state. The last
+/// simulated bracket is saved and returned, along with a summary of how far teams got over
+/// all runs.
+pub async fn simulate(
+    driver: &Session,
+    options: &SimulateOptions,
+) -> anyhow::Result<(Tournament, SimulationSummary)> {
+    if let Some(adjustments) = &options.adjustments {
+        ensure_teams(driver).await?;
+        for team in adjustments.unknown_teams(&load_teams()?) {
+            log::warn!(
+                "{} isn't in the teams file, so its adjustment is ignored",
+                team
+            );
+        }
+    }
+    let mut summary = SimulationSummary::new(options.sampling);
+    let games_path = options.games_format.path();
+    let mut games = GamesLog::create(options.games_format)
+        .with_context(|| format!("Could not create {}", games_path))?;
+    let mut sampler = Sampler::new(options.rng, options.seed, options.sampling, options.runs);
+    let mut last = None;
+    let mut played = PlayedGames::default();
+    let mut degraded = vec![];
+    for run in 1..=options.runs {
+        if options.runs > 1 {
+            log::info!("Starting run {} of {}", run, options.runs);
+        }
+        let (tournament, records, mut run_played) =
+            metrics::track_errors(simulate_once(driver, options, &mut sampler, run).await)?;
+        degraded.extend(run_played.degraded.drain(..).map(|pick| (run, pick)));
+        played = run_played;
+        metrics::record_simulation();
+        for record in records {
+            games.write(record)?;
+        }
+        games.flush()?;
+        summary.add(&tournament);
+        last = Some(tournament);
+    }
+    games.finish()?;
+    log::info!("Logged every simulated game to {}", games_path);
+    let tournament = last.context("At least one run is required")?;
+    log::info!(
+        "Skipped {} games decided on the page and kept {} saved picks ({} left unclicked), \
+         simulated {}",
+        played.decided,
+        played.kept,
+        played.unclicked,
+        played.simulated
+    );
+    report_degraded(&degraded);
+    log::info!("Tournament results: {}\n\n", tournament);
+    tournament.save(&options.bracket_path)?;
+    log::info!("Saved bracket to {}", options.bracket_path.display());
+    let summary_path = season::data_path(SUMMARY_PATH);
+    summary.save(&summary_path)?;
+    log::info!("Saved summary of {} runs to {}", summary.runs, summary_path);
+    Ok((tournament, summary))
+}
+
+/// Warn about every game picked without the page, by run
+fn report_degraded(degraded: &[(usize, DegradedPick)]) {
+    if degraded.is_empty() {
+        return;
+    }
+    log::warn!("{} games were picked without the page:", degraded.len());
+    for (run, pick) in degraded {
+        let drawn_at = pick
+            .win_prob
+            .map(|prob| format!(" at {:.1}%", prob * 100.))
+            .unwrap_or_default();
+        log::warn!(
+            "Run {}, {}: {} over {}{} ({})",
+            run,
+            pick.round,
+            pick.winner,
+            if pick.teams[0] == pick.winner {
+                &pick.teams[1]
+            } else {
+                &pick.teams[0]
+            },
+            drawn_at,
+            pick.error
+        );
+    }
+}
+
+/// Simulate the tournament once, clicking the picks into the page as we go. Returns the
+/// bracket along with a record of each game played and how the games were settled.
+async fn simulate_once(
+    driver: &Session,
+    options: &SimulateOptions,
+    sampler: &mut Sampler,
+    run: usize,
+) -> anyhow::Result<(Tournament, Vec<GameRecord>, PlayedGames)> {
+    sampler.start_run();
+    // Reloading resets any picks clicked in a previous run
+    let mut tournament = current_bracket(driver).await?;
+    let mut records = vec![];
+    let played = play_out(
+        driver,
+        options,
+        &mut tournament,
+        |round, matchup, teams, win_prob| {
+            let draw = sampler.draw();
+            let ind = if draw < win_prob {
+                MatchupInd::Team1
+            } else {
+                MatchupInd::Team2
+            };
+            let record = GameRecord {
+                run,
+                round: round.number(),
+                matchup,
+                team1: teams[0].clone(),
+                team2: teams[1].clone(),
+                win_prob,
+                draw,
+                winner: teams[ind.to_ind()].clone(),
+            };
+            if options.stream_games {
+                if let Err(e) = output::emit(&record) {
+                    log::warn!("Could not stream a simulated game: {}", e);
+                }
+            }
+            records.push(record);
+            ind
+        },
+    )
+    .await?;
+    Ok((tournament, records, played))
+}
+
+/// Play out every undecided game in the bracket round by round up to the last round to
+/// simulate, clicking each winner into the page. Games before the start round are filled in
+/// from the existing picks, and games the saved bracket already picked keep that pick. `pick`
+/// chooses the winner given the round, the matchup index, the teams and the first team's win
+/// probability.
+///
+/// In a best effort run a game whose probability can't be read is drawn from the fallback
+/// probability, and a pick which can't be clicked in is kept off the page along with every
+/// later game of its winner.
+async fn play_out(
+    driver: &Session,
+    options: &SimulateOptions,
+    tournament: &mut Tournament,
+    mut pick: impl FnMut(RoundKind, usize, &[String; 2], f64) -> MatchupInd,
+) -> anyhow::Result<PlayedGames> {
+    let mut played = PlayedGames::default();
+    // Kept picks are only clicked once a later game needs the team's node on the page
+    let mut unclicked: Vec<(String, usize)> = vec![];
+    let prior = if options.best_effort {
+        Some(SeedPrior::new(&load_teams()?))
+    } else {
+        None
+    };
+    // Teams whose pick never made it onto the page
+    let mut off_page: HashSet<String> = HashSet::new();
+    for round_num in 1..=options.through_round.min(tournament.field.round_count()) {
         let round_kind = RoundKind::Round(round_num);
-        let curr_round = tournament.get_round_mut(round_kind);
+        for ind in 0..tournament.field.matchup_count(round_num) {
+            let known = settle_known_game(options, tournament, round_kind, ind, &mut played);
+            let teams = match known {
+                Known::Decided => continue,
+                Known::Kept(winner) => {
+                    unclicked.push((winner, round_num));
+                    continue;
+                }
+                Known::Unknown(teams) => teams,
+            };
+            played.simulated += 1;
+            let mut error = teams
+                .iter()
+                .find(|team| off_page.contains(*team))
+                .map(|team| format!("{} isn't on the page", team));
+            let mut on_page = error.is_none();
+            if on_page {
+                if let Err(e) = click_kept_picks(driver, tournament, &mut unclicked, &teams).await {
+                    error = Some(degrade(options, e)?);
+                    on_page = false;
+                }
+            }
+            let (winner, win_prob) = if round_num < options.start_round {
+                let winner = existing_pick(options, round_kind, ind, &teams)?;
+                log::info!(
+                    "Keeping existing pick of {} in the {}",
+                    winner,
+                    tournament.round_name(round_kind)
+                );
+                (winner, None)
+            } else {
+                let read = if on_page {
+                    let read = win_probability(driver, options, &teams, round_num)
+                        .await
+                        .with_context(|| {
+                            format!(
+                                "Could not find win percentage for {} vs {}",
+                                teams[0], teams[1]
+                            )
+                        });
+                    match read {
+                        Ok(prob) => Some(prob),
+                        Err(e) => {
+                            error = Some(degrade(options, e)?);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                let win_prob = match read {
+                    Some(prob) => prob,
+                    None => fallback_probability(options, prior.as_ref(), &teams, round_num)?,
+                };
 
-        for matchup in &mut curr_round.matchups {
-            if matchup.completed() {
-                continue;
+                log::info!(
+                    "{} has a {:.1}% to win against {}",
+                    teams[0],
+                    win_prob * 100.,
+                    teams[1]
+                );
+
+                let winner = teams[pick(round_kind, ind, &teams, win_prob).to_ind()].clone();
+                log::info!("{} won!", winner);
+                (winner, Some(win_prob))
+            };
+            if on_page {
+                if let Err(e) =
+                    click_team_with_recovery(driver, tournament, &winner, round_num).await
+                {
+                    error = Some(degrade(options, e)?);
+                    on_page = false;
+                }
+            }
+            tournament.advance_team(&winner, round_kind);
+            if let Some(win_prob) = win_prob {
+                tournament.get_round_mut(round_kind).matchups[ind].set_win_probability(win_prob);
+            }
+            if !on_page {
+                off_page.insert(winner.clone());
             }
-            let teams = matchup.teams();
-            let win_perc = get_win_percent(driver, &teams[0], round_num)
-                .await
-                .with_context(|| {
-                    format!(
-                        "Could not find win percentage for {} vs {}",
-                        teams[0], teams[1]
-                    )
-                })?;
-
-            log::info!(
-                "{} has a {}% to win against {}",
+            if let Some(error) = error {
+                played.degraded.push(DegradedPick {
+                    round: round_kind,
+                    teams,
+                    winner,
+                    win_prob,
+                    error,
+                });
+            }
+        }
+    }
+    played.unclicked = unclicked.len();
+    Ok(played)
+}
+
+/// An error reading or clicking a game, which fails the run unless it is a best effort run,
+/// where it is warned about and its message kept for the report of degraded picks
+fn degrade(options: &SimulateOptions, e: anyhow::Error) -> anyhow::Result<String> {
+    if !options.best_effort {
+        return Err(e);
+    }
+    log::warn!("{:#}, picking the game without the page", e);
+    Ok(format!("{:#}", e))
+}
+
+/// What is known about a game before anything is read from the page
+#[derive(Debug, PartialEq)]
+enum Known {
+    /// The page already shows its winner
+    Decided,
+    /// Its saved pick was kept, advancing this team
+    Kept(String),
+    /// It has to be picked, between these teams
+    Unknown([String; 2]),
+}
+
+/// Settle a game which needs no pick: one already decided, or one whose saved pick is kept,
+/// advancing the saved winner. Counts either in `played`.
+fn settle_known_game(
+    options: &SimulateOptions,
+    tournament: &mut Tournament,
+    round: RoundKind,
+    ind: usize,
+    played: &mut PlayedGames,
+) -> Known {
+    let matchup = &tournament.rounds[&round].matchups[ind];
+    if matchup.completed() {
+        played.decided += 1;
+        return Known::Decided;
+    }
+    let teams = matchup.teams();
+    let (winner, win_prob) = match saved_pick(options, round, ind, &teams) {
+        Some(pick) => pick,
+        None => return Known::Unknown(teams),
+    };
+    log::info!(
+        "Keeping saved pick of {} in the {}",
+        winner,
+        tournament.round_name(round)
+    );
+    tournament.advance_team(&winner, round);
+    if let Some(win_prob) = win_prob {
+        tournament.get_round_mut(round).matchups[ind].set_win_probability(win_prob);
+    }
+    played.kept += 1;
+    Known::Kept(winner)
+}
+
+/// Winner of a game in the saved bracket, with the chance it was drawn at, if the saved
+/// bracket has the same two teams playing it. A saved bracket of another field or an older
+/// run may not have the game at all, in which case nothing is kept.
+fn saved_pick(
+    options: &SimulateOptions,
+    round: RoundKind,
+    ind: usize,
+    teams: &[String; 2],
+) -> Option<(String, Option<f64>)> {
+    let matchup = options
+        .saved
+        .as_ref()?
+        .rounds
+        .get(&round)?
+        .matchups
+        .get(ind)?;
+    if matchup.try_teams()? != [teams[0].as_str(), teams[1].as_str()] {
+        return None;
+    }
+    Some((
+        matchup.winning_team()?.to_string(),
+        matchup.win_probability(),
+    ))
+}
+
+/// Click in the kept picks of the given teams, so that their nodes for the coming game show
+/// on the page. Picks the page already shows, say after a reload clicked them back in, aren't
+/// clicked again, since that could undo them.
+async fn click_kept_picks(
+    driver: &Session,
+    tournament: &Tournament,
+    unclicked: &mut Vec<(String, usize)>,
+    teams: &[String; 2],
+) -> anyhow::Result<()> {
+    let (needed, rest) = std::mem::take(unclicked)
+        .into_iter()
+        .partition::<Vec<_>, _>(|(team, _)| teams.contains(team));
+    *unclicked = rest;
+    for (team, round_num) in needed {
+        let advanced_id = selectors().node_id(&construct_html_name(&team), 6 - round_num);
+        if driver.find_all(By::Id(&advanced_id)).await?.is_empty() {
+            click_team_with_recovery(driver, tournament, &team, round_num).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Winner of a game from the existing picks, which must be one of the teams actually playing.
+/// Picks of a bracket without the game, such as one of another field, don't count.
+fn existing_pick(
+    options: &SimulateOptions,
+    round: RoundKind,
+    ind: usize,
+    teams: &[String; 2],
+) -> anyhow::Result<String> {
+    options
+        .picks
+        .as_ref()
+        .and_then(|picks| picks.rounds.get(&round)?.matchups.get(ind)?.winning_team())
+        .filter(|winner| teams.iter().any(|team| team == winner))
+        .map(str::to_string)
+        .ok_or_else(|| {
+            anyhow!(
+                "{} vs {} in {} has no result or existing pick, pass a bracket with --picks",
                 teams[0],
-                win_perc,
-                teams[1]
-            );
+                teams[1],
+                round
+            )
+        })
+}
+
+/// One game on a team's path to the title
+#[derive(Debug, Clone, Serialize)]
+pub struct PathStep {
+    pub round: RoundKind,
+    pub opponent: String,
+    /// Chance of the team winning this game given the opponent, None if already played
+    pub win_prob: Option<f64>,
+}
 
-            let winning_team = if random::<f32>() < (win_perc as f32 / 100.) {
-                &teams[0]
+/// Find a team's most likely path to the title: the team is advanced through every round,
+/// while every other game goes to the favorite, so each opponent is the most likely team to
+/// come out of their side of the bracket (by picking favorites, game by game).
+pub async fn champion_path(driver: &Session, team: &str) -> anyhow::Result<Vec<PathStep>> {
+    let mut tournament = current_bracket(driver).await?;
+    if tournament.eliminated_teams().contains(team) {
+        return Err(anyhow!("{} has already been eliminated", team));
+    }
+
+    let mut path = vec![];
+    for round_num in 1..=6 {
+        let round = &tournament.rounds[&RoundKind::Round(round_num)];
+        let matchup = match round.matchups.iter().find(|m| m.includes_team(team)) {
+            Some(matchup) if matchup.completed() => matchup,
+            _ => break,
+        };
+        let [team1, team2] = matchup.teams();
+        path.push(PathStep {
+            round: round.round,
+            opponent: if team1 == team { team2 } else { team1 },
+            win_prob: None,
+        });
+    }
+
+    play_out(
+        driver,
+        &SimulateOptions::default(),
+        &mut tournament,
+        |round, _, teams, win_prob| {
+            let (ind, opponent, team_prob) = if teams[0] == team {
+                (MatchupInd::Team1, &teams[1], win_prob)
+            } else if teams[1] == team {
+                (MatchupInd::Team2, &teams[0], 1. - win_prob)
+            } else if win_prob >= 0.5 {
+                return MatchupInd::Team1;
             } else {
-                &teams[1]
+                return MatchupInd::Team2;
             };
-            winning_teams.push(winning_team.clone());
-            log::info!("{} won!", winning_team);
-            click_team(driver, winning_team, round_num).await?;
+            path.push(PathStep {
+                round,
+                opponent: opponent.clone(),
+                win_prob: Some(team_prob),
+            });
+            ind
+        },
+    )
+    .await?;
+    Ok(path)
+}
+
+/// Scrape head-to-head probabilities for every pair of teams which can still meet, by clicking
+/// each pair into their meeting slot and hovering. This takes a long time, so the matrix is
+/// saved after each round and pairs already in `matrix` are skipped, allowing an interrupted
+/// scrape to resume. Each round's pairs are split between the given sessions, which scrape
+/// at the same time.
+pub async fn scrape_probabilities(
+    drivers: &[Session],
+    matrix: &mut ProbabilityMatrix,
+) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let res = metrics::track_errors(scrape_missing_pairs(drivers, matrix).await);
+    metrics::record_scrape(start.elapsed(), res.is_ok());
+    res
+}
+
+async fn scrape_missing_pairs(
+    drivers: &[Session],
+    matrix: &mut ProbabilityMatrix,
+) -> anyhow::Result<()> {
+    // The in-memory copies track which teams are currently clicked into each slot on each
+    // session's page
+    let mut pages = vec![];
+    for driver in drivers {
+        pages.push(current_bracket(driver).await?);
+    }
+    let page = pages
+        .first()
+        .ok_or_else(|| anyhow!("No browser sessions to scrape with"))?;
+    let eliminated: HashSet<String> = page
+        .eliminated_teams()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let round1 = page.rounds[&RoundKind::Round(1)].matchups.clone();
+
+    for round_num in 1..=6 {
+        let matchups = pages[0].rounds[&RoundKind::Round(round_num)]
+            .matchups
+            .clone();
+        let mut pairs = vec![];
+        for matchup in matchups.iter().filter(|m| !m.completed()) {
+            let candidates = |side: usize| -> Vec<String> {
+                if round_num == 1 {
+                    return vec![matchup.teams()[side].clone()];
+                }
+                // Each side of this matchup is fed by this many round 1 matchups
+                let feeders = 1 << (round_num - 2);
+                let start = (2 * matchup.index() + side) * feeders;
+                round1[start..start + feeders]
+                    .iter()
+                    .flat_map(|m| m.teams())
+                    .filter(|team| !eliminated.contains(team))
+                    .collect()
+            };
+            for team1 in candidates(0) {
+                for team2 in candidates(1) {
+                    if matrix.get(&team1, &team2).is_none() {
+                        pairs.push((team1.clone(), team2));
+                    }
+                }
+            }
+        }
+
+        let mut error = None;
+        // Contiguous runs of pairs, so each session keeps a team in place while it goes
+        // through that team's opponents
+        let sessions = drivers.len();
+        let scrapes =
+            drivers
+                .iter()
+                .zip(pages.iter_mut())
+                .enumerate()
+                .map(|(i, (driver, page))| {
+                    let run = &pairs[i * pairs.len() / sessions..(i + 1) * pairs.len() / sessions];
+                    scrape_pairs(driver, page, run, round_num)
+                });
+        for (scraped, res) in join_all(scrapes).await {
+            for (team1, team2, prob) in scraped {
+                matrix.insert(&team1, &team2, prob);
+            }
+            if let Err(e) = res {
+                error.get_or_insert(e);
+            }
         }
+        // Saved before giving up on an error so the other sessions' pairs aren't lost
+        let path = season::data_path(PROBABILITIES_PATH);
+        matrix.save(&path)?;
+        if let Some(e) = error {
+            return Err(e);
+        }
+        log::info!(
+            "Scraped the {}, {} pairs saved to {}",
+            RoundKind::Round(round_num),
+            matrix.len(),
+            path
+        );
+    }
+    Ok(())
+}
 
-        for team in &winning_teams {
-            tournament.advance_team(team, round_kind);
+/// Scrape pairs of teams meeting in the given round on one session. Pairs scraped before an
+/// error are returned along with it.
+async fn scrape_pairs(
+    driver: &Session,
+    page: &mut Tournament,
+    pairs: &[(String, String)],
+    round_num: usize,
+) -> (Vec<(String, String, f64)>, anyhow::Result<()>) {
+    let mut scraped = vec![];
+    // Each pair's label is parsed while the next pair is being clicked into place and hovered
+    let mut pending: Option<(&str, &str, String)> = None;
+    for (team1, team2) in pairs {
+        let (label, parsed) =
+            tokio::join!(hover_pair(driver, page, team1, team2, round_num), async {
+                pending
+                    .take()
+                    .map(|(t1, t2, label)| parse_pair(t1, t2, round_num, &label))
+            });
+        match parsed {
+            Some(Ok(prob)) => scraped.push(prob),
+            Some(Err(e)) => return (scraped, Err(e)),
+            None => {}
+        }
+        match label {
+            Ok(label) => pending = Some((team1, team2, label)),
+            Err(e) => return (scraped, Err(e)),
         }
     }
-    log::info!("Tournament results: {}\n\n", tournament);
+    if let Some((team1, team2, label)) = pending {
+        match parse_pair(team1, team2, round_num, &label) {
+            Ok(prob) => scraped.push(prob),
+            Err(e) => return (scraped, Err(e)),
+        }
+    }
+    (scraped, Ok(()))
+}
+
+/// Click both teams into their meeting slot and read the first team's win% label
+async fn hover_pair(
+    driver: &Session,
+    page: &mut Tournament,
+    team1: &str,
+    team2: &str,
+    round_num: usize,
+) -> anyhow::Result<String> {
+    place_team(driver, page, team1, round_num).await?;
+    place_team(driver, page, team2, round_num).await?;
+    timings::time(Phase::Hover, hover_win_label(driver, team1, round_num))
+        .await
+        .with_context(|| format!("Could not find win percentage for {} vs {}", team1, team2))
+}
+
+fn parse_pair(
+    team1: &str,
+    team2: &str,
+    round_num: usize,
+    label: &str,
+) -> anyhow::Result<(String, String, f64)> {
+    let prob = parse_win_probability(label)?;
+    audit::record(&AuditEntry::new("538", team1, team2, Some(round_num), prob).with_raw(label));
+    log::info!("{} beats {} {:.1}% of the time", team1, team2, prob * 100.);
+    Ok((team1.to_string(), team2.to_string(), prob))
+}
+
+/// Chance of `team1` beating `team2` in the round where they would meet, found by clicking both
+/// teams into that game and hovering. If `round_num` is given it has to be that round.
+pub async fn head_to_head(
+    driver: &Session,
+    team1: &str,
+    team2: &str,
+    round_num: Option<usize>,
+) -> anyhow::Result<(usize, f64)> {
+    if team1 == team2 {
+        return Err(anyhow!("A team can't play itself"));
+    }
+    let mut page = current_bracket(driver).await?;
+    let meeting_round = page
+        .meeting_round(team1, team2)
+        .ok_or_else(|| anyhow!("{} and {} aren't both in the bracket", team1, team2))?;
+    if let Some(round_num) = round_num {
+        if round_num != meeting_round {
+            return Err(anyhow!(
+                "{} and {} can only meet in the {}",
+                team1,
+                team2,
+                RoundKind::Round(meeting_round)
+            ));
+        }
+    }
+    for team in [team1, team2] {
+        if page.eliminated_teams().contains(team) {
+            return Err(anyhow!("{} has already been eliminated", team));
+        }
+    }
+
+    place_team(driver, &mut page, team1, meeting_round).await?;
+    place_team(driver, &mut page, team2, meeting_round).await?;
+    let prob = get_win_probability(driver, team1, team2, meeting_round).await?;
+    Ok((meeting_round, prob))
+}
+
+/// Click a team forward until it sits in the given round on the page, replacing whoever was
+/// clicked into its path before
+async fn place_team(
+    driver: &Session,
+    page: &mut Tournament,
+    team: &str,
+    round_num: usize,
+) -> anyhow::Result<()> {
+    for prev_round in 1..round_num {
+        let round = RoundKind::Round(prev_round);
+        let matchup = page.rounds[&round].get_matchup_with_team(team);
+        match matchup.winning_team().map(str::to_string) {
+            Some(winner) if winner == team => continue,
+            Some(winner) => page.retract_team(&winner, round),
+            None => {}
+        }
+        click_team_with_recovery(driver, page, team, prev_round).await?;
+        page.advance_team(team, round);
+    }
     Ok(())
 }
 
+/// Load the 538 page and build a bracket holding the real results so far
+pub async fn current_bracket(driver: &Session) -> anyhow::Result<Tournament> {
+    ensure_teams(driver).await?;
+    throttle::rate_limit().await;
+    preflight::load_page(driver).await?;
+
+    let html = bracket_nodes_html(driver).await?;
+    cache::put(BRACKET_CACHE_KEY, &html);
+    bracket_from_nodes(&html)
+}
+
+/// Like [`current_bracket`], but reusing the bracket from the cache while it's fresh. Only for
+/// tasks which read the results without clicking on the page.
+pub async fn cached_bracket(driver: &Session) -> anyhow::Result<Tournament> {
+    ensure_teams(driver).await?;
+    match cache::get(BRACKET_CACHE_KEY) {
+        Some(html) => bracket_from_nodes(&html),
+        None => current_bracket(driver).await,
+    }
+}
+
+/// The bracket is laid out from the teams' seeds and regions, so if the teams haven't been
+/// saved yet, scrape them from the page first instead of making write-teams-table a
+/// separate step
+async fn ensure_teams(driver: &Session) -> anyhow::Result<()> {
+    if !teams_saved() {
+        log::warn!("No saved teams found, reading them from the 538 teams table first");
+        write_teams(driver).await?;
+    }
+    Ok(())
+}
+
+/// Build the bracket of real results from the HTML of the page's bracket nodes
+fn bracket_from_nodes(html: &str) -> anyhow::Result<Tournament> {
+    let current_teams = parse_current_teams(html);
+    let round1_teams = current_teams.get(&RoundKind::Round(1)).unwrap();
+    let mut teams = load_teams()?;
+
+    // Filter out teams who lost in the First Four. TODO: actually simulate the First Four
+    teams.retain(|team| {
+        let html_name = construct_html_name(team.name());
+        !team.in_first_four() || round1_teams.contains(&html_name)
+    });
+    if teams.len() != 64 {
+        return Err(anyhow!(
+            "Expected 64 teams in round 1 but found {}, have the First Four games been played?",
+            teams.len()
+        ));
+    }
+    Tournament::new(&mut teams, current_teams)
+}
+
+/// Probability that the first team wins, from 538 and/or sportsbook odds depending on options
+async fn win_probability(
+    driver: &Session,
+    options: &SimulateOptions,
+    teams: &[String; 2],
+    round_num: usize,
+) -> anyhow::Result<f64> {
+    let overridden = options
+        .overrides
+        .as_ref()
+        .and_then(|overrides| overrides.get(&teams[0], &teams[1], round_num));
+    if let Some(prob) = overridden {
+        let entry = AuditEntry::new("override", &teams[0], &teams[1], Some(round_num), prob);
+        audit::record(&entry);
+        return Ok(prob);
+    }
+    let (kenpom_prob, odds_prob, seed_prob) = other_sources(options, teams);
+    let weights = &options.weights;
+    // 538 is only looked up when it counts, or when no other source covers the game
+    let mut prob_538 = None;
+    let prob = match weights.blend(None, kenpom_prob, odds_prob, seed_prob) {
+        Some(prob) if !weights.uses_fivethirtyeight() => prob,
+        _ => {
+            let prob = get_win_probability(driver, &teams[0], &teams[1], round_num).await?;
+            prob_538 = Some(prob);
+            weights
+                .blend(prob_538, kenpom_prob, odds_prob, seed_prob)
+                .unwrap_or(prob)
+        }
+    };
+    let mut entry = AuditEntry::new("game", &teams[0], &teams[1], Some(round_num), prob);
+    let inputs = [
+        ("538", prob_538),
+        ("kenpom", kenpom_prob),
+        ("odds", odds_prob),
+        ("seeds", seed_prob),
+    ];
+    entry.inputs = inputs
+        .into_iter()
+        .filter_map(|(source, prob)| Some((source, prob?)))
+        .collect();
+    Ok(adjust(options, entry, prob))
+}
+
+/// Probability that the first team wins without 538, for games it couldn't be read for in a
+/// best effort run: the other prediction sources blended by their weights, or the seeds alone
+/// when none of them cover the game
+fn fallback_probability(
+    options: &SimulateOptions,
+    prior: Option<&SeedPrior>,
+    teams: &[String; 2],
+    round_num: usize,
+) -> anyhow::Result<f64> {
+    let (kenpom_prob, odds_prob, seed_prob) = other_sources(options, teams);
+    let prob = options
+        .weights
+        .blend(None, kenpom_prob, odds_prob, seed_prob)
+        .or_else(|| prior?.win_probability(&teams[0], &teams[1]))
+        .ok_or_else(|| anyhow!("Nothing to predict {} vs {} from", teams[0], teams[1]))?;
+    let entry = AuditEntry::new("fallback", &teams[0], &teams[1], Some(round_num), prob);
+    Ok(adjust(options, entry, prob))
+}
+
+/// Chances of the first team winning from efficiencies, odds and seeds, where given and
+/// covering the game
+fn other_sources(
+    options: &SimulateOptions,
+    teams: &[String; 2],
+) -> (Option<f64>, Option<f64>, Option<f64>) {
+    let kenpom_prob = options
+        .efficiencies
+        .as_ref()
+        .and_then(|efficiencies| efficiencies.win_probability(&teams[0], &teams[1]));
+    let odds_prob = options
+        .odds
+        .as_ref()
+        .and_then(|odds| odds.win_probability(&teams[0], &teams[1]));
+    let seed_prob = options
+        .seed_prior
+        .as_ref()
+        .and_then(|prior| prior.win_probability(&teams[0], &teams[1]));
+    (kenpom_prob, odds_prob, seed_prob)
+}
+
+/// Apply the adjustments and sharpening to a game's probability, recording each step in its
+/// audit entry before it is written
+fn adjust(options: &SimulateOptions, mut entry: AuditEntry, prob: f64) -> f64 {
+    let prob = match &options.adjustments {
+        Some(adjustments) => {
+            let prob = adjustments.adjust(entry.team1, entry.team2, prob);
+            entry.transforms.push(("adjustments".to_string(), prob));
+            prob
+        }
+        None => prob,
+    };
+    let prob = sharpen(prob, options.sharpen);
+    if options.sharpen != 1. {
+        entry
+            .transforms
+            .push((format!("sharpen by {}", options.sharpen), prob));
+    }
+    audit::record(&entry);
+    prob
+}
+
 /// Get a map of round to team currently advanced to that round
 async fn get_current_teams(
-    driver: &WebDriver,
+    driver: &Session,
 ) -> anyhow::Result<HashMap<RoundKind, HashSet<String>>> {
-    let html = driver
-        .find_element(By::Css("g.nodes"))
+    Ok(parse_current_teams(&bracket_nodes_html(driver).await?))
+}
+
+/// HTML of the bracket nodes, once they have rendered
+async fn bracket_nodes_html(driver: &Session) -> anyhow::Result<String> {
+    Ok(driver
+        .wait_for(By::Css(&selectors().bracket), timeouts::element_timeout())
         .await?
         .inner_html()
-        .await?;
-    let parsed = Html::parse_fragment(&html);
-    let selector = Selector::parse("g.node").unwrap();
+        .await?)
+}
+
+fn parse_current_teams(html: &str) -> HashMap<RoundKind, HashSet<String>> {
+    let parsed = Html::parse_fragment(html);
+    let selector = Selector::parse(&selectors().node).unwrap();
     let mut res: HashMap<_, HashSet<_>> = HashMap::new();
     for node in parsed.select(&selector) {
         let id = node
@@ -113,92 +1001,272 @@ async fn get_current_teams(
             }
         }
     }
-    Ok(res)
+    res
+}
+
+/// A `g.node` id on the 538 page and what was read from it
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeId {
+    pub id: String,
+    /// Team and round from the id, or why they couldn't be read
+    pub parsed: Result<(String, RoundKind), String>,
 }
 
-/// Get the win% for this team in the given round. This requires 2 steps:
-/// 1. Hover over the team node so that the HTML updates to include the win %
-/// 2. Parse the HTML to extract the win %
-async fn get_win_percent(driver: &WebDriver, team: &str, round_num: usize) -> anyhow::Result<u32> {
+/// Load the 538 page and list the id of every bracket node, for checking how well they parse
+/// after the markup changes
+pub async fn node_ids(driver: &Session) -> anyhow::Result<Vec<NodeId>> {
+    throttle::rate_limit().await;
+    preflight::load_page(driver).await?;
+    Ok(parse_node_ids(&bracket_nodes_html(driver).await?))
+}
+
+pub(crate) fn parse_node_ids(html: &str) -> Vec<NodeId> {
+    let parsed = Html::parse_fragment(html);
+    let selector = Selector::parse(&selectors().node).unwrap();
+    parsed
+        .select(&selector)
+        .map(|node| {
+            let id = node.value().id().unwrap_or_default().to_string();
+            let parsed = extract_team_round_from_id(&id).map_err(|e| e.to_string());
+            NodeId { id, parsed }
+        })
+        .collect()
+}
+
+/// Get the chance of this team beating the opponent it's placed against in the given round.
+/// This requires 2 steps:
+/// 1. Hover over the team node so that the page shows the win %
+/// 2. Read the label holding the win % once it has rendered, and parse it
+async fn get_win_probability(
+    driver: &Session,
+    team: &str,
+    opponent: &str,
+    round_num: usize,
+) -> anyhow::Result<f64> {
+    let label = timings::time(Phase::Hover, hover_win_label(driver, team, round_num)).await?;
+    let prob = parse_win_probability(&label)?;
+    audit::record(&AuditEntry::new("538", team, opponent, Some(round_num), prob).with_raw(label));
+    Ok(prob)
+}
+
+/// Hover over the team node for the given round and read the win% label that appears,
+/// polling every `--hover-poll-ms` until the hover text has rendered or `--hover-timeout-ms`
+/// passes. Only the team's labels are fetched rather than the whole bracket.
+async fn hover_win_label(driver: &Session, team: &str, round_num: usize) -> anyhow::Result<String> {
     let team = construct_html_name(team);
     let node = get_team_node(driver, &team, round_num).await?;
-    hover_node(&node, driver).await?;
-    let html = driver
-        .find_element(By::Css("g.nodes"))
-        .await?
-        .inner_html()
-        .await?;
-    let parsed = Html::parse_fragment(&html);
-    let css_selector = format!("text[depth=\"{}\"", 6 - round_num);
-    let selector = Selector::parse(&css_selector).unwrap();
-    for node in parsed.select(&selector) {
-        // TODO: seems like there should be a more idiomatic way to use this Classes type
-        if node
-            .value()
-            .classes
-            .iter()
-            .map(|c| c.to_string())
-            .collect::<HashSet<_>>()
-            .contains(&team)
-        {
-            let text = node.text().collect::<Vec<_>>();
-            // We should have one text element here if we've found the win %
-            if text.len() == 1 {
-                return Ok(match text[0] {
-                    ">99%" => 100,
-                    "<1%" => 0,
-                    t => t.replace('%', "").parse()?,
-                });
+    driver.hover(&node).await?;
+    let labels = selectors().win_label(&team, 6 - round_num);
+    let timeout = timeouts::hover_timeout();
+    let start = Instant::now();
+    loop {
+        for label in driver.find_all(By::Css(&labels)).await? {
+            let text = label.text().await?;
+            // Labels show the team name until the hover text renders
+            if text.trim().ends_with('%') {
+                return Ok(text.trim().to_string());
             }
         }
+        if start.elapsed() > timeout {
+            return Err(anyhow!(
+                "No win percentage found for {} within {:?} of hovering",
+                team,
+                timeout
+            ));
+        }
+        sleep(timeouts::hover_poll_interval()).await;
     }
-    Err(anyhow!("No win percentage found for {}", team))
 }
 
-/// Hover over the given node, used to expose up to date win percentages
-async fn hover_node<'a>(ele: &'a WebElement<'a>, driver: &'a WebDriver) -> anyhow::Result<()> {
-    driver
-        .action_chain()
-        .move_to_element_center(ele)
-        .perform()
-        .await?;
-    Ok(())
+/// Click the team node for the given round, which will advance the team. The click is checked
+/// by looking for the team's node in the next round, clicking again if it doesn't show up.
+async fn click_team(driver: &Session, team: &str, round_num: usize) -> anyhow::Result<()> {
+    let team = construct_html_name(team);
+    // The champion's node has depth 0, one past the final
+    let advanced_id = selectors().node_id(&team, 6 - round_num);
+    for attempt in 1..=CLICK_ATTEMPTS {
+        let node = get_team_node(driver, &team, round_num).await?;
+        driver.click(&node).await?;
+        if driver
+            .wait_for(By::Id(&advanced_id), timeouts::element_timeout())
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+        log::warn!(
+            "{} did not advance out of the {} after clicking (attempt {} of {})",
+            team,
+            RoundKind::Round(round_num),
+            attempt,
+            CLICK_ATTEMPTS
+        );
+    }
+    Err(anyhow!(
+        "Clicked {} in the {} but the page never showed {}",
+        team,
+        RoundKind::Round(round_num),
+        advanced_id
+    ))
 }
 
-/// Click the team node for the given round, which will advance the team
-async fn click_team(driver: &WebDriver, team: &str, round_num: usize) -> anyhow::Result<()> {
-    let team = construct_html_name(team);
-    let node = get_team_node(driver, &team, round_num).await?;
-    click_node(&node, driver).await?;
-    Ok(())
+/// Click a team forward, and if that fails reload the page, click the picks in `tournament`
+/// back in and try again. `tournament` should hold what has been clicked into the page so far.
+async fn click_team_with_recovery(
+    driver: &Session,
+    tournament: &Tournament,
+    team: &str,
+    round_num: usize,
+) -> anyhow::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match timings::time(Phase::Click, click_team(driver, team, round_num)).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= MAX_RECOVERIES => {
+                return Err(e.context(format!(
+                    "Could not advance {} out of the {} after {} page reloads",
+                    team,
+                    RoundKind::Round(round_num),
+                    attempt
+                )))
+            }
+            Err(e) => {
+                attempt += 1;
+                log::warn!(
+                    "Clicking {} failed ({}), reloading the page (attempt {} of {})",
+                    team,
+                    e,
+                    attempt,
+                    MAX_RECOVERIES
+                );
+                resync_page(driver, tournament).await?;
+            }
+        }
+    }
 }
 
-/// Click the given element. Note that we use this utility for clicking an element that is not
-/// clickable - for example the 538 team nodes are not clickable, so instead we move the
-/// mouse to them and click such that the clickable element in the same location intercepts it.
-async fn click_node<'a>(ele: &'a WebElement<'a>, driver: &'a WebDriver) -> anyhow::Result<()> {
-    driver
-        .action_chain()
-        .move_to_element_center(ele)
-        .click()
-        .perform()
-        .await?;
-    driver.action_chain().reset_actions().await?;
+/// Reload the page, which drops any picks clicked so far, then click back in every pick in
+/// `tournament` which the page doesn't already show
+async fn resync_page(driver: &Session, tournament: &Tournament) -> anyhow::Result<()> {
+    throttle::rate_limit().await;
+    preflight::load_page(driver).await?;
+    let page = get_current_teams(driver).await?;
+    for round_num in 1..=6 {
+        let advanced = page.get(&RoundKind::Round(round_num + 1));
+        for matchup in &tournament.rounds[&RoundKind::Round(round_num)].matchups {
+            if let Some(winner) = matchup.winning_team() {
+                let on_page = advanced
+                    .map(|teams| teams.contains(&construct_html_name(winner)))
+                    .unwrap_or(false);
+                if !on_page {
+                    timings::time(Phase::Click, click_team(driver, winner, round_num)).await?;
+                }
+            }
+        }
+    }
     Ok(())
 }
 
 /// Get a node for this team in the given round. The name argument should already
 /// be sanitized
 async fn get_team_node<'a>(
-    driver: &'a WebDriver,
+    driver: &'a Session,
     team: &str,
     round: usize,
 ) -> anyhow::Result<WebElement<'a>> {
-    Ok(driver
-        .find_element(By::Id(&format!(
-            "node-{}-{}",
-            construct_html_name(team),
-            7 - round
-        )))
-        .await?)
+    let id = node_id(team, round);
+    driver
+        .wait_for_visible(By::Id(&id), timeouts::element_timeout())
+        .await
+        .with_context(|| format!("Could not find {}", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::field::{Field, Seeding};
+    use crate::teams::Team;
+    use crate::tournament::Region;
+
+    fn bracket(teams: usize) -> Tournament {
+        let field = Field::new(teams, 1, Seeding::Standard).unwrap();
+        let mut teams = (1..=teams)
+            .map(|seed| {
+                serde_json::from_value::<Team>(json!({
+                    "name": format!("Team {}", seed),
+                    "region": Region::West,
+                    "seed": seed,
+                }))
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        Tournament::with_field(field, &mut teams).unwrap()
+    }
+
+    /// Settle every game as `play_out` does, picking the first team wherever there is no
+    /// known winner
+    fn play(options: &SimulateOptions, tournament: &mut Tournament) -> PlayedGames {
+        let mut played = PlayedGames::default();
+        for round_num in 1..=tournament.field.round_count() {
+            let round = RoundKind::Round(round_num);
+            for ind in 0..tournament.field.matchup_count(round_num) {
+                let known = settle_known_game(options, tournament, round, ind, &mut played);
+                if let Known::Unknown(teams) = known {
+                    tournament.advance_team(&teams[0], round);
+                    played.simulated += 1;
+                }
+            }
+        }
+        played
+    }
+
+    #[test]
+    fn keeps_saved_picks_of_the_same_games() {
+        let (round1, round2) = (RoundKind::Round(1), RoundKind::Round(2));
+        // Round 1 is 1-8, 4-5, 2-7 and 3-6, and 1 has already beaten 8 on the page
+        let mut page = bracket(8);
+        page.advance_team("Team 1", round1);
+        // The saved run never got to 3-6, so the 7 it took through has no opponent to keep
+        // a pick against in round 2
+        let mut saved = page.clone();
+        for team in ["Team 5", "Team 7"] {
+            saved.advance_team(team, round1);
+        }
+        saved.advance_team("Team 5", round2);
+        let options = SimulateOptions {
+            saved: Some(saved),
+            ..Default::default()
+        };
+        let played = play(&options, &mut page);
+        assert_eq!((played.decided, played.kept, played.simulated), (1, 3, 3));
+        assert_eq!(page.champion(), Some("Team 5"));
+
+        // A saved bracket of another field doesn't have the same games
+        let options = SimulateOptions {
+            saved: Some(bracket(4)),
+            ..Default::default()
+        };
+        let played = play(&options, &mut bracket(8));
+        assert_eq!((played.kept, played.simulated), (0, 7));
+    }
+
+    #[test]
+    fn existing_picks_need_the_same_game() {
+        let teams = ["Team 1".to_string(), "Team 8".to_string()];
+        let mut picks = bracket(8);
+        picks.advance_team("Team 1", RoundKind::Round(1));
+        let options = SimulateOptions {
+            picks: Some(picks),
+            ..Default::default()
+        };
+        assert_eq!(
+            existing_pick(&options, RoundKind::Round(1), 0, &teams).unwrap(),
+            "Team 1"
+        );
+        // Neither a missing round nor a missing game panics
+        assert!(existing_pick(&options, RoundKind::Round(4), 0, &teams).is_err());
+        assert!(existing_pick(&options, RoundKind::Round(1), 4, &teams).is_err());
+    }
 }
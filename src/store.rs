@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use rusqlite::{params, Connection};
+
+use crate::games_log::GameRecord;
+use crate::probabilities::ProbabilityMatrix;
+use crate::results::ResultRecord;
+use crate::teams::Team;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS teams (
+    name TEXT PRIMARY KEY,
+    region TEXT NOT NULL,
+    seed INTEGER NOT NULL,
+    first_four_opponent TEXT,
+    conference TEXT,
+    rating REAL
+);
+CREATE TABLE IF NOT EXISTS probabilities (
+    team1 TEXT NOT NULL,
+    team2 TEXT NOT NULL,
+    prob REAL NOT NULL,
+    PRIMARY KEY (team1, team2)
+);
+CREATE TABLE IF NOT EXISTS simulations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS simulated_games (
+    simulation INTEGER NOT NULL REFERENCES simulations (id),
+    run INTEGER NOT NULL,
+    round INTEGER NOT NULL,
+    matchup INTEGER NOT NULL,
+    team1 TEXT NOT NULL,
+    team2 TEXT NOT NULL,
+    win_prob REAL NOT NULL,
+    draw REAL NOT NULL,
+    winner TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS results (
+    recorded_at INTEGER NOT NULL,
+    round INTEGER NOT NULL,
+    matchup INTEGER NOT NULL,
+    winner TEXT NOT NULL,
+    loser TEXT NOT NULL,
+    PRIMARY KEY (round, matchup)
+);
+";
+
+/// SQLite database collecting teams, probabilities, simulated games and real results, so
+/// they can be queried together and across many simulations
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open the database, creating it and its tables if needed
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .with_context(|| format!("Could not open database {}", path.display()))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Replace the stored teams
+    pub fn save_teams(&mut self, teams: &[Team]) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM teams", [])?;
+        for team in teams {
+            tx.execute(
+                "INSERT INTO teams VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    team.name(),
+                    team.region.to_string(),
+                    team.seed.0,
+                    team.first_four_opponent,
+                    team.conference,
+                    team.rating
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replace the stored probabilities, keeping one row per pair
+    pub fn save_probabilities(&mut self, matrix: &ProbabilityMatrix) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM probabilities", [])?;
+        for (team1, team2, prob) in matrix.pairs() {
+            tx.execute(
+                "INSERT INTO probabilities VALUES (?1, ?2, ?3)",
+                params![team1, team2, prob],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Add the games of a simulation as a new simulation, returning its id
+    pub fn add_simulation(&mut self, games: &[GameRecord]) -> anyhow::Result<i64> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO simulations (created_at) VALUES (?1)",
+            params![SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64],
+        )?;
+        let id = tx.last_insert_rowid();
+        for game in games {
+            tx.execute(
+                "INSERT INTO simulated_games VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    id,
+                    game.run as i64,
+                    game.round as i64,
+                    game.matchup as i64,
+                    game.team1,
+                    game.team2,
+                    game.win_prob,
+                    game.draw,
+                    game.winner
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Add real results, keeping the first recording of each game
+    pub fn add_results(&mut self, results: &[ResultRecord]) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        for result in results {
+            tx.execute(
+                "INSERT OR IGNORE INTO results VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    result.recorded_at as i64,
+                    result.round as i64,
+                    result.matchup as i64,
+                    result.winner,
+                    result.loser
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
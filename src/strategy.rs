@@ -0,0 +1,67 @@
+use anyhow::anyhow;
+
+use crate::analytic::{max_expected_points_bracket, most_probable_bracket};
+use crate::probabilities::ProbabilityMatrix;
+use crate::scoring::ScoringSystem;
+use crate::tournament::{RoundKind, Tournament};
+
+/// How to fill in a bracket from head-to-head probabilities
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum Strategy {
+    /// Take the favorite in every game, one round at a time
+    Chalk,
+    /// The bracket scoring the most points on average
+    ExpectedPoints,
+    /// The bracket with the highest chance of being exactly right, which can pass on a
+    /// favorite with a hard path
+    MaxLikelihood,
+}
+
+/// Fill in the undecided games of `bracket` with the given strategy
+pub fn build_bracket(
+    strategy: Strategy,
+    bracket: &Tournament,
+    matrix: &ProbabilityMatrix,
+    scoring: &ScoringSystem,
+) -> anyhow::Result<Tournament> {
+    match strategy {
+        Strategy::Chalk => chalk_bracket(bracket, matrix),
+        Strategy::ExpectedPoints => {
+            let (bracket, points) = max_expected_points_bracket(bracket, matrix, scoring)?;
+            log::info!("Expected points: {:.1}", points);
+            Ok(bracket)
+        }
+        Strategy::MaxLikelihood => {
+            let (bracket, prob) = most_probable_bracket(bracket, matrix)?;
+            log::info!("Chance of being exactly right: {:e}", prob);
+            Ok(bracket)
+        }
+    }
+}
+
+fn chalk_bracket(bracket: &Tournament, matrix: &ProbabilityMatrix) -> anyhow::Result<Tournament> {
+    let mut res = bracket.clone();
+    for round_num in 1..=res.field.round_count() {
+        let round = RoundKind::Round(round_num);
+        for ind in 0..res.field.matchup_count(round_num) {
+            let matchup = &res.rounds[&round].matchups[ind];
+            if matchup.completed() {
+                continue;
+            }
+            let [team1, team2] = matchup
+                .try_teams()
+                .map(|teams| teams.map(str::to_string))
+                .ok_or_else(|| anyhow!("The {} is missing a team", res.round_name(round)))?;
+            let prob = matrix.get(&team1, &team2).ok_or_else(|| {
+                anyhow!(
+                    "No probability for {} vs {}, scrape probabilities first",
+                    team1,
+                    team2
+                )
+            })?;
+            let favorite = if prob >= 0.5 { team1 } else { team2 };
+            res.advance_team(&favorite, round);
+        }
+    }
+    Ok(res)
+}
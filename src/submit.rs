@@ -0,0 +1,176 @@
+mod cbs;
+mod espn;
+mod yahoo;
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use thirtyfour::{By, WebElement};
+use tokio::time::sleep;
+
+use crate::session::Session;
+use crate::teams::normalize_name;
+use crate::timeouts;
+use crate::tournament::{RoundKind, Tournament};
+
+/// How long to wait for bracket site pages to render
+const PAGE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// How many times a team is clicked before giving up on it showing up in the next round
+const PICK_ATTEMPTS: usize = 3;
+
+/// How often to look again for a pick in the next round
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Bracket pool sites we can enter picks on
+#[derive(PartialEq, Debug, Copy, Clone, clap::ArgEnum)]
+pub enum PoolSite {
+    /// ESPN Tournament Challenge
+    Espn,
+    /// Yahoo Fantasy Tourney Pick'em
+    Yahoo,
+    /// CBS Sports bracket pools
+    Cbs,
+}
+
+impl PoolSite {
+    fn selectors(self) -> &'static SiteSelectors {
+        match self {
+            Self::Espn => &espn::SELECTORS,
+            Self::Yahoo => &yahoo::SELECTORS,
+            Self::Cbs => &cbs::SELECTORS,
+        }
+    }
+
+    /// Prefix of the environment variables holding credentials for this site
+    fn env_prefix(self) -> &'static str {
+        match self {
+            Self::Espn => "ESPN",
+            Self::Yahoo => "YAHOO",
+            Self::Cbs => "CBS",
+        }
+    }
+}
+
+/// Where to find things on a pool site's bracket page
+pub(crate) struct SiteSelectors {
+    /// Display name of the site
+    name: &'static str,
+    /// Page for creating a new entry
+    entry_url: &'static str,
+    /// Pickable team slots in a round, with `{}` standing in for the round number
+    pick_slot: &'static str,
+    /// Button which saves the bracket once all picks are in
+    save_button: &'static str,
+}
+
+/// Log in to a pool site and enter the picks of a saved bracket from `first_round` on, e.g. 3
+/// for a second chance pool. Credentials are read from `<SITE>_USERNAME`/`<SITE>_PASSWORD`
+/// (e.g. `ESPN_USERNAME`), and an existing entry can be targeted with `<SITE>_ENTRY_URL`
+/// (otherwise a new entry is created).
+pub async fn submit_bracket(
+    driver: &Session,
+    site: PoolSite,
+    bracket: &Tournament,
+    first_round: usize,
+) -> anyhow::Result<()> {
+    let selectors = site.selectors();
+    let prefix = site.env_prefix();
+    let username = env::var(format!("{}_USERNAME", prefix))
+        .with_context(|| format!("{}_USERNAME must be set", prefix))?;
+    let password = env::var(format!("{}_PASSWORD", prefix))
+        .with_context(|| format!("{}_PASSWORD must be set", prefix))?;
+    match site {
+        PoolSite::Espn => espn::login(driver, &username, &password).await?,
+        PoolSite::Yahoo => yahoo::login(driver, &username, &password).await?,
+        PoolSite::Cbs => cbs::login(driver, &username, &password).await?,
+    }
+    log::info!("Logged in to {} as {}", selectors.name, username);
+
+    let entry_url = env::var(format!("{}_ENTRY_URL", prefix))
+        .unwrap_or_else(|_| selectors.entry_url.to_string());
+    driver.goto(&entry_url).await?;
+
+    for round_num in first_round..=6 {
+        let round = &bracket.rounds[&RoundKind::Round(round_num)];
+        for matchup in &round.matchups {
+            let team = matchup
+                .winning_team()
+                .with_context(|| format!("Bracket has no winner for {}", matchup))?;
+            pick_team(driver, selectors, team, round_num)
+                .await
+                .with_context(|| format!("Could not pick {} in the {}", team, round.round))?;
+            log::info!("Picked {} in the {}", team, round.round);
+        }
+    }
+
+    driver
+        .wait_for(By::Css(selectors.save_button), PAGE_TIMEOUT)
+        .await?
+        .click()
+        .await?;
+    log::info!("Saved {} bracket", selectors.name);
+    Ok(())
+}
+
+/// Click the slot for this team in the given round, checking the team shows up in the next
+/// round. Sites use their own team names, so slots are matched loosely against the 538 name.
+async fn pick_team(
+    driver: &Session,
+    selectors: &SiteSelectors,
+    team: &str,
+    round_num: usize,
+) -> anyhow::Result<()> {
+    let slots = |round_num: usize| selectors.pick_slot.replace("{}", &round_num.to_string());
+    let target = normalize_name(team);
+    for attempt in 1..=PICK_ATTEMPTS {
+        let slot = find_slot(driver, &slots(round_num), &target)
+            .await?
+            .ok_or_else(|| anyhow!("No slot found for {}", team))?;
+        driver.click(&slot).await?;
+        // The title game's winner has no next round to show up in
+        if round_num == 6 || shows_up(driver, &slots(round_num + 1), &target).await? {
+            return Ok(());
+        }
+        log::warn!(
+            "{} did not show up in the next round after clicking (attempt {} of {})",
+            team,
+            attempt,
+            PICK_ATTEMPTS
+        );
+    }
+    Err(anyhow!(
+        "Clicked {} but it never showed up in the next round",
+        team
+    ))
+}
+
+/// The slot matching a team among those matching `selector`
+async fn find_slot<'a>(
+    driver: &'a Session,
+    selector: &str,
+    target: &str,
+) -> anyhow::Result<Option<WebElement<'a>>> {
+    for slot in driver.find_all(By::Css(selector)).await? {
+        if normalize_name(&slot.text().await?) == target {
+            return Ok(Some(slot));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether a slot for the team shows up among those matching `selector` within the element
+/// timeout
+async fn shows_up(driver: &Session, selector: &str, target: &str) -> anyhow::Result<bool> {
+    let start = Instant::now();
+    loop {
+        if find_slot(driver, selector, target).await?.is_some() {
+            return Ok(true);
+        }
+        if start.elapsed() > timeouts::element_timeout() {
+            return Ok(false);
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+}
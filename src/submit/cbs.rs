@@ -0,0 +1,40 @@
+use anyhow::Context;
+use thirtyfour::By;
+
+use super::{SiteSelectors, PAGE_TIMEOUT};
+use crate::session::Session;
+
+pub(crate) const SELECTORS: SiteSelectors = SiteSelectors {
+    name: "CBS Sports",
+    entry_url: "https://www.cbssports.com/college-basketball/bracketology/pools/entry",
+    pick_slot: ".bracket-round-{} .team-name",
+    save_button: "button.submit-bracket, .save-picks",
+};
+
+const LOGIN_URL: &str = "https://www.cbssports.com/login";
+const LOGIN_USERNAME: &str = "input[name='email']";
+const LOGIN_PASSWORD: &str = "input[name='password']";
+const LOGIN_SUBMIT: &str = "button[type='submit']";
+const LOGGED_IN_MARKER: &str = ".user-nav-logged-in, .UserMenu";
+
+/// Fill in the CBS Sports login form
+pub(crate) async fn login(driver: &Session, username: &str, password: &str) -> anyhow::Result<()> {
+    driver.goto(LOGIN_URL).await?;
+    driver
+        .wait_for(By::Css(LOGIN_USERNAME), PAGE_TIMEOUT)
+        .await?
+        .send_keys(username)
+        .await?;
+    driver
+        .find(By::Css(LOGIN_PASSWORD))
+        .await?
+        .send_keys(password)
+        .await?;
+    driver.find(By::Css(LOGIN_SUBMIT)).await?.click().await?;
+
+    driver
+        .wait_for(By::Css(LOGGED_IN_MARKER), PAGE_TIMEOUT)
+        .await
+        .context("CBS Sports login did not complete, check the credentials")?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::Context;
+use thirtyfour::By;
+
+use super::{SiteSelectors, PAGE_TIMEOUT};
+use crate::session::Session;
+
+pub(crate) const SELECTORS: SiteSelectors = SiteSelectors {
+    name: "ESPN",
+    entry_url: "https://fantasy.espn.com/tournament-challenge-bracket/2022/en/entry",
+    pick_slot: ".matchup.round{} .name",
+    save_button: ".saveBracket, button.save-bracket",
+};
+
+const LOGIN_URL: &str = "https://www.espn.com/login";
+const LOGIN_IFRAME: &str = "#oneid-iframe, #disneyid-iframe";
+const LOGIN_USERNAME: &str = "input[type='email']";
+const LOGIN_PASSWORD: &str = "input[type='password']";
+const LOGIN_SUBMIT: &str = "button[type='submit']";
+const LOGGED_IN_MARKER: &str = ".user--logged-in, .display-user";
+
+/// Fill in the ESPN login form, which lives in an iframe
+pub(crate) async fn login(driver: &Session, username: &str, password: &str) -> anyhow::Result<()> {
+    driver.goto(LOGIN_URL).await?;
+    let iframe = driver.wait_for(By::Css(LOGIN_IFRAME), PAGE_TIMEOUT).await?;
+    driver.switch_to().frame_element(&iframe).await?;
+
+    driver
+        .wait_for(By::Css(LOGIN_USERNAME), PAGE_TIMEOUT)
+        .await?
+        .send_keys(username)
+        .await?;
+    driver
+        .wait_for(By::Css(LOGIN_PASSWORD), PAGE_TIMEOUT)
+        .await?
+        .send_keys(password)
+        .await?;
+    driver.find(By::Css(LOGIN_SUBMIT)).await?.click().await?;
+
+    driver.switch_to().default_content().await?;
+    driver
+        .wait_for(By::Css(LOGGED_IN_MARKER), PAGE_TIMEOUT)
+        .await
+        .context("ESPN login did not complete, check the credentials")?;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anyhow::Context;
+use thirtyfour::By;
+
+use super::{SiteSelectors, PAGE_TIMEOUT};
+use crate::session::Session;
+
+pub(crate) const SELECTORS: SiteSelectors = SiteSelectors {
+    name: "Yahoo",
+    entry_url: "https://tournament.fantasysports.yahoo.com/t1/register/createbracket",
+    pick_slot: ".ysf-tpl-round-{} .ysf-tpl-team-name",
+    save_button: "#ysf-tpl-save-bracket, button.ysf-tpl-save",
+};
+
+const LOGIN_URL: &str = "https://login.yahoo.com/";
+const LOGIN_USERNAME: &str = "#login-username";
+const LOGIN_PASSWORD: &str = "#login-passwd";
+const LOGIN_SUBMIT: &str = "#login-signin";
+const LOGGED_IN_MARKER: &str = "#ybarAccountMenu, #ybar-account-menu";
+
+/// Yahoo asks for the username and password on separate pages
+pub(crate) async fn login(driver: &Session, username: &str, password: &str) -> anyhow::Result<()> {
+    driver.goto(LOGIN_URL).await?;
+    driver
+        .wait_for(By::Css(LOGIN_USERNAME), PAGE_TIMEOUT)
+        .await?
+        .send_keys(username)
+        .await?;
+    driver.find(By::Css(LOGIN_SUBMIT)).await?.click().await?;
+
+    driver
+        .wait_for(By::Css(LOGIN_PASSWORD), PAGE_TIMEOUT)
+        .await?
+        .send_keys(password)
+        .await?;
+    driver.find(By::Css(LOGIN_SUBMIT)).await?.click().await?;
+
+    driver
+        .wait_for(By::Css(LOGGED_IN_MARKER), PAGE_TIMEOUT)
+        .await
+        .context("Yahoo login did not complete, check the credentials")?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rng::Sampling;
+use crate::tournament::{RoundKind, Tournament};
+
+/// Where the summary of the latest simulation runs is saved
+pub const SUMMARY_PATH: &str = "simulation_summary.json";
+
+/// How often each team won in each round over a set of simulated brackets
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SimulationSummary {
+    /// Number of brackets summarized
+    pub runs: usize,
+    /// For each team, the number of brackets in which it won its round 1..=6 game
+    pub wins: BTreeMap<String, [usize; 6]>,
+    /// How the runs shared their draws, which decides how their standard errors are found.
+    /// None for summaries saved before it was recorded, whose runs are taken as independent.
+    #[serde(default)]
+    pub sampling: Option<Sampling>,
+    /// For antithetic runs, the sum over each pair of runs of the square of a team's wins in
+    /// the pair, for each round
+    #[serde(default)]
+    pair_squares: BTreeMap<String, [usize; 6]>,
+    /// Wins in the pair of antithetic runs still missing its second run
+    #[serde(skip)]
+    open_pair: BTreeMap<String, [usize; 6]>,
+}
+
+impl SimulationSummary {
+    /// An empty summary of runs drawn with `sampling`
+    pub fn new(sampling: Sampling) -> Self {
+        Self {
+            sampling: Some(sampling),
+            ..Default::default()
+        }
+    }
+
+    /// Count the winners of a completed (or partially completed) bracket
+    pub fn add(&mut self, bracket: &Tournament) {
+        self.runs += 1;
+        let antithetic = self.sampling == Some(Sampling::Antithetic);
+        for round_num in 1..=bracket.field.round_count() {
+            for matchup in &bracket.rounds[&RoundKind::Round(round_num)].matchups {
+                if let Some(team) = matchup.winning_team() {
+                    self.wins.entry(team.to_string()).or_default()[round_num - 1] += 1;
+                    if antithetic {
+                        self.open_pair.entry(team.to_string()).or_default()[round_num - 1] += 1;
+                    }
+                }
+            }
+        }
+        // A pair is done once its mirrored run is in
+        if !antithetic || self.runs % 2 == 1 {
+            return;
+        }
+        for (team, wins) in std::mem::take(&mut self.open_pair) {
+            let squares = self.pair_squares.entry(team).or_default();
+            for (square, wins) in squares.iter_mut().zip(wins) {
+                *square += wins * wins;
+            }
+        }
+    }
+
+    /// Fraction of brackets in which the team won its game in the given round, so round 4
+    /// gives the chance of reaching the Final Four and round 6 the chance of a title
+    pub fn win_probability(&self, team: &str, round_num: usize) -> f64 {
+        if self.runs == 0 {
+            return 0.;
+        }
+        let wins = self.wins.get(team).map(|w| w[round_num - 1]).unwrap_or(0);
+        wins as f64 / self.runs as f64
+    }
+
+    /// Standard error of `win_probability` as an estimate of the true chance. Antithetic runs
+    /// are only independent pair by pair, so their error comes from the spread of the pairs'
+    /// chances. Otherwise it's the error of independent runs, see
+    /// [`SimulationSummary::independent_errors`].
+    pub fn standard_error(&self, team: &str, round_num: usize) -> f64 {
+        if self.runs == 0 {
+            return 0.;
+        }
+        let pairs = self.runs / 2;
+        if self.sampling == Some(Sampling::Antithetic) && pairs >= 2 {
+            let get = |values: &BTreeMap<String, [usize; 6]>| {
+                values.get(team).map(|v| v[round_num - 1]).unwrap_or(0) as f64
+            };
+            // Each pair's chance is half its wins
+            let paired_wins = get(&self.wins) - get(&self.open_pair);
+            let mean = paired_wins / 2. / pairs as f64;
+            let squares = get(&self.pair_squares) / 4.;
+            let variance = ((squares - pairs as f64 * mean * mean) / (pairs - 1) as f64).max(0.);
+            return (variance / pairs as f64).sqrt();
+        }
+        let prob = self.win_probability(team, round_num);
+        (prob * (1. - prob) / self.runs as f64).sqrt()
+    }
+
+    /// Whether `standard_error` is that of independent runs although the runs weren't drawn
+    /// independently. Stratified runs are one set with no replicates to compare, and their
+    /// error is at most about that of independent runs, so it serves as a bound.
+    pub fn independent_errors(&self) -> bool {
+        match self.sampling {
+            None | Some(Sampling::Independent) => false,
+            Some(Sampling::Antithetic) => self.runs / 2 < 2,
+            Some(Sampling::Stratified) => true,
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?,
+        );
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
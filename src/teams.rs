@@ -1,16 +1,27 @@
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter};
-use std::str::FromStr;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use serde::{Deserialize, Serialize};
-use thirtyfour::{By, WebDriver};
+use serde_json::{json, Value};
 
 use crate::tournament::{Region, Seed};
-use crate::URL;
+use crate::{page_url, season, URL, YEAR};
+
+#[cfg(feature = "driver")]
+mod scrape;
+
+#[cfg(feature = "driver")]
+pub use scrape::{scrape_round_chances, write_teams};
 
 const TEAMS_PATH_538: &str = "teams.json";
 
+/// Age after which saved teams are reported as stale, since their ratings change as games
+/// are played
+const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 /// A team playing in the tournament
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Team {
@@ -20,69 +31,167 @@ pub struct Team {
     pub region: Region,
     /// Seed in the tournament
     pub seed: Seed,
+    /// Opponent in the First Four, None if this team starts in round 1
+    #[serde(default)]
+    pub first_four_opponent: Option<String>,
+    /// Conference, if the teams table lists it
+    #[serde(default)]
+    pub conference: Option<String>,
+    /// 538 power rating, if the teams table lists it
+    #[serde(default)]
+    pub rating: Option<f64>,
+}
+
+/// Version of the teams file layout written by this version of the tool. Files with an older
+/// version are upgraded when loaded:
+/// 0. A bare list of teams
+/// 1. The teams along with where and when they were scraped
+/// 2. Adds this version number
+const SCHEMA_VERSION: u64 = 2;
+
+/// The teams file, recording where and when the teams were scraped
+#[derive(Debug, Deserialize, Serialize)]
+struct TeamsFile {
+    /// Layout of the file, see [`SCHEMA_VERSION`]
+    schema_version: u64,
+    /// When the table was scraped, in seconds since the Unix epoch
+    scraped_at: u64,
+    /// Page the table was scraped from
+    source_url: String,
+    /// Year of the tournament
+    year: u32,
+    /// Version of this tool which wrote the file
+    tool_version: String,
+    teams: Vec<Team>,
 }
 
 impl Team {
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
-}
 
-/// Scrape the 538 teams table and write the participating teams to a file. Must use a
-/// 538 source so that the names match to naming in HTML classes by 538.
-pub async fn write_teams(driver: &WebDriver) -> anyhow::Result<()> {
-    driver.get(URL).await?;
-    let table = driver
-        .find_elements(By::Css("#team-table tbody tr"))
-        .await?;
-    let mut teams = vec![];
-    for team in table {
-        let name_seed_text = team
-            .find_element(By::ClassName("team-name"))
-            .await?
-            .inner_html()
-            .await?;
-        let mut name_seed = name_seed_text.split(" <span>");
-        let name = name_seed.next().context("No team name found")?;
-
-        let seed = name_seed
-            .next()
-            .context("No seed found")?
-            .strip_suffix("</span>")
-            .context("Unexpected line structure")?
-            .parse()?;
-        let region = team
-            .find_element(By::ClassName("region"))
-            .await?
-            .inner_html()
-            .await?;
-        let team = Team {
-            name: name.to_string(),
-            region: Region::from_str(&region)?,
-            seed: Seed::new(seed)?,
-        };
-        log::info!("Found team {}", name);
-        teams.push(team);
+    /// Whether this team has to win a First Four game to reach round 1
+    pub fn in_first_four(&self) -> bool {
+        self.first_four_opponent.is_some()
     }
+}
+
+fn save_teams_file(file: &TeamsFile) -> anyhow::Result<()> {
     let writer = BufWriter::new(
         OpenOptions::new()
             .write(true)
             .create(true)
-            .open(TEAMS_PATH_538)?,
+            .truncate(true)
+            .open(season::data_path(TEAMS_PATH_538))?,
     );
-    serde_json::to_writer_pretty(writer, &teams)?;
+    serde_json::to_writer_pretty(writer, file)?;
     Ok(())
 }
 
-/// Load 538 tournament team information written to file
+/// Bring a teams file written by an older version up to [`SCHEMA_VERSION`] one step at a
+/// time. Returns whether anything changed.
+fn migrate(value: &mut Value) -> anyhow::Result<bool> {
+    let version = match value {
+        Value::Array(_) => 0,
+        Value::Object(fields) => match fields.get("schema_version") {
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| anyhow!("Unexpected schema version {}", version))?,
+            None => 1,
+        },
+        _ => return Err(anyhow!("Expected a list of teams or an object")),
+    };
+    if version > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Written by a newer version with schema {}, this version reads up to {}",
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+    for from in version..SCHEMA_VERSION {
+        log::info!(
+            "Upgrading {} from schema {} to {}",
+            season::data_path(TEAMS_PATH_538),
+            from,
+            from + 1
+        );
+        *value = match (from, value.take()) {
+            // Where and when these teams came from wasn't recorded, so assume this year's
+            // page and date them as long ago
+            (0, teams) => json!({
+                "scraped_at": 0,
+                "source_url": URL,
+                "year": YEAR,
+                "tool_version": "unknown",
+                "teams": teams,
+            }),
+            (_, mut file) => {
+                file["schema_version"] = json!(from + 1);
+                file
+            }
+        };
+    }
+    Ok(version < SCHEMA_VERSION)
+}
+
+/// Whether write-teams-table has saved the teams yet
+pub fn teams_saved() -> bool {
+    Path::new(&season::data_path(TEAMS_PATH_538)).exists()
+}
+
+/// Load 538 tournament team information written to file, checking it was scraped from the
+/// page for this year's tournament
 pub fn load_teams() -> anyhow::Result<Vec<Team>> {
-    let reader = BufReader::new(File::open(TEAMS_PATH_538)?);
-    Ok(serde_json::from_reader(reader)?)
+    let path = season::data_path(TEAMS_PATH_538);
+    let reader = BufReader::new(File::open(&path)?);
+    let mut value: Value = serde_json::from_reader(reader)?;
+    let migrated = migrate(&mut value).with_context(|| format!("Could not read {}", path))?;
+    let file: TeamsFile = serde_json::from_value(value)
+        .with_context(|| format!("Unexpected format for {}, re-run write-teams-table", path))?;
+    if migrated {
+        save_teams_file(&file)?;
+    }
+    let url = page_url();
+    if file.year != season::year() || file.source_url != url {
+        return Err(anyhow!(
+            "{} holds the {} teams from {}, but the {} teams from {} are needed. Re-run \
+             write-teams-table",
+            path,
+            file.year,
+            file.source_url,
+            season::year(),
+            url
+        ));
+    }
+    log::debug!(
+        "Loaded teams scraped at {} by version {}",
+        file.scraped_at,
+        file.tool_version
+    );
+    let age = SystemTime::now().duration_since(UNIX_EPOCH + Duration::from_secs(file.scraped_at));
+    if age.map(|age| age > STALE_AFTER).unwrap_or(false) {
+        log::warn!(
+            "{} was scraped over a week ago, re-run write-teams-table for current ratings",
+            path
+        );
+    }
+    Ok(file.teams)
+}
+
+/// Loose form of a team name used to match names from other sites against 538 names, e.g.
+/// "Saint Mary's (CA)" and "St. Mary's" both become "stmarys"
+pub(crate) fn normalize_name(name: &str) -> String {
+    let name = name.split(" (").next().unwrap_or(name).to_lowercase();
+    let name = name.replace("saint", "st").replace("state", "st");
+    name.chars().filter(|c| c.is_alphanumeric()).collect()
 }
 
-/// Convert the 538 team name to an HTML-friendly name used in element classes
-pub fn construct_html_name(name: &str) -> String {
-    name.chars()
-        .filter(|&c| c == '-' || c.is_alphabetic())
-        .collect()
+/// Find the team matching a name which may come from a source other than 538
+pub fn find_team<'a>(teams: &'a [Team], name: &str) -> Option<&'a Team> {
+    teams.iter().find(|team| team.name == name).or_else(|| {
+        let normalized = normalize_name(name);
+        teams
+            .iter()
+            .find(|team| normalize_name(&team.name) == normalized)
+    })
 }
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+use scraper::{ElementRef, Html, Selector};
+use thirtyfour::By;
+
+use super::{save_teams_file, Team, TeamsFile, SCHEMA_VERSION};
+use crate::analytic::RoundProbabilities;
+use crate::parse::parse_round_chance;
+use crate::selectors::selectors;
+use crate::session::Session;
+use crate::timings::{self, Phase};
+use crate::tournament::{Region, Seed};
+use crate::{cache, page_url, preflight, season};
+
+/// Cache key for the HTML of the teams table
+const TEAM_TABLE_CACHE_KEY: &str = "team_table";
+
+/// Scrape the 538 teams table and write all 68 participating teams to a file. Must use a
+/// 538 source so that the names match to naming in HTML classes by 538. Teams sharing a
+/// region and seed meet in the First Four, and are marked as such.
+pub async fn write_teams(driver: &Session) -> anyhow::Result<()> {
+    let html = team_table_html(driver).await?;
+    let parsed = Html::parse_fragment(&html);
+    let rows = Selector::parse(&selectors().team_rows).unwrap();
+    let cell = |row: ElementRef, class: &str| {
+        let selector = Selector::parse(&format!(".{}", class)).unwrap();
+        row.select(&selector).next().map(|cell| cell.inner_html())
+    };
+    let mut teams = vec![];
+    for team in parsed.select(&rows) {
+        let name_seed_text = cell(team, "team-name").context("No team name found")?;
+        let mut name_seed = name_seed_text.split(" <span>");
+        let name = name_seed.next().context("No team name found")?;
+
+        // First Four teams have a seed like "16a", so only keep the numeric part
+        let seed = name_seed
+            .next()
+            .context("No seed found")?
+            .strip_suffix("</span>")
+            .context("Unexpected line structure")?
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+            .parse()?;
+        let region = cell(team, "region").context("No region found")?;
+        let conference = cell(team, "conference");
+        let rating = cell(team, "rating").and_then(|rating| rating.trim().parse().ok());
+        let team = Team {
+            name: name.to_string(),
+            region: Region::from_str(&region)?,
+            seed: Seed::new(seed)?,
+            first_four_opponent: None,
+            conference,
+            rating,
+        };
+        log::info!("Found team {}", name);
+        teams.push(team);
+    }
+    mark_first_four(&mut teams)?;
+    let file = TeamsFile {
+        schema_version: SCHEMA_VERSION,
+        scraped_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        source_url: page_url(),
+        year: season::year(),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        teams,
+    };
+    save_teams_file(&file)
+}
+
+/// Read each team's chance of winning its game in each round from the teams table, a full
+/// forecast in one page load
+pub async fn scrape_round_chances(driver: &Session) -> anyhow::Result<RoundProbabilities> {
+    let html = team_table_html(driver).await?;
+    let parsed = Html::parse_fragment(&html);
+    let rows = Selector::parse(&selectors().team_rows).unwrap();
+    let round_cells = Selector::parse(&selectors().round_cells).unwrap();
+    let name_cell = Selector::parse(".team-name").unwrap();
+    let mut probs = RoundProbabilities::default();
+    for row in parsed.select(&rows) {
+        let name_seed = row
+            .select(&name_cell)
+            .next()
+            .context("No team name found")?
+            .inner_html();
+        let name = name_seed
+            .split(" <span>")
+            .next()
+            .context("No team name found")?;
+        let cells = row
+            .select(&round_cells)
+            .map(|cell| parse_round_chance(&cell.text().collect::<String>()))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .with_context(|| format!("Could not read the chances of {}", name))?;
+        let rounds = cells
+            .len()
+            .checked_sub(6)
+            .map(|first| &cells[first..])
+            .ok_or_else(|| {
+                anyhow!(
+                    "Expected 6 round chances for {}, found {}",
+                    name,
+                    cells.len()
+                )
+            })?;
+        let mut round_probs = [0.; 6];
+        round_probs.copy_from_slice(rounds);
+        probs.probs.insert(name.to_string(), round_probs);
+    }
+    if probs.probs.is_empty() {
+        return Err(anyhow!("No teams found in the teams table"));
+    }
+    log::info!("Read round chances for {} teams", probs.probs.len());
+    Ok(probs)
+}
+
+/// HTML of the teams table, loading the page for it unless it was cached
+async fn team_table_html(driver: &Session) -> anyhow::Result<String> {
+    if let Some(html) = cache::get(TEAM_TABLE_CACHE_KEY) {
+        return Ok(html);
+    }
+    let html = timings::time(Phase::TeamsTable, async {
+        // Also waits for the table, which is filled in after load
+        preflight::load_page(driver).await?;
+        let table = driver.find(By::Css(&selectors().team_table)).await?;
+        Ok::<_, anyhow::Error>(table.outer_html().await?)
+    })
+    .await?;
+    cache::put(TEAM_TABLE_CACHE_KEY, &html);
+    Ok(html)
+}
+
+/// Pair up teams which share a region and seed, since those meet in the First Four
+fn mark_first_four(teams: &mut [Team]) -> anyhow::Result<()> {
+    let mut slots: HashMap<(usize, u8), Vec<usize>> = HashMap::new();
+    for (i, team) in teams.iter().enumerate() {
+        slots
+            .entry((team.region.to_ind(), team.seed.0))
+            .or_default()
+            .push(i);
+    }
+    for inds in slots.values() {
+        match inds.as_slice() {
+            [_] => {}
+            &[a, b] => {
+                teams[a].first_four_opponent = Some(teams[b].name.clone());
+                teams[b].first_four_opponent = Some(teams[a].name.clone());
+                log::info!(
+                    "{} plays {} in the First Four",
+                    teams[a].name,
+                    teams[b].name
+                );
+            }
+            _ => {
+                let team = &teams[inds[0]];
+                return Err(anyhow!(
+                    "Too many teams seeded {} in the {}",
+                    team.seed.0,
+                    team.region
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::time::sleep;
+
+/// Pause after each click or hover, in ms
+static ACTION_DELAY_MS: AtomicU64 = AtomicU64::new(0);
+/// Minimum gap between WebDriver calls, in ms
+static MIN_INTERVAL_MS: AtomicU64 = AtomicU64::new(0);
+/// When the latest WebDriver call was scheduled, in ms since the Unix epoch
+static NEXT_CALL_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Set how politely to drive the browser: a pause after every click or hover, and a cap on
+/// how many WebDriver calls are made per second across the whole program
+pub fn configure(action_delay: Duration, max_calls_per_sec: Option<f64>) {
+    ACTION_DELAY_MS.store(action_delay.as_millis() as u64, Ordering::Relaxed);
+    let interval = max_calls_per_sec
+        .filter(|rate| *rate > 0.)
+        .map(|rate| (1000. / rate) as u64)
+        .unwrap_or(0);
+    MIN_INTERVAL_MS.store(interval, Ordering::Relaxed);
+}
+
+/// Wait for a slot under the rate limit before making a WebDriver call
+pub async fn rate_limit() {
+    let interval = MIN_INTERVAL_MS.load(Ordering::Relaxed);
+    if interval == 0 {
+        return;
+    }
+    loop {
+        let now = now_ms();
+        let last = NEXT_CALL_MS.load(Ordering::Relaxed);
+        let next = now.max(last + interval);
+        // Claim the slot so concurrent callers queue up behind it
+        if NEXT_CALL_MS
+            .compare_exchange(last, next, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            sleep(Duration::from_millis(next - now)).await;
+            return;
+        }
+    }
+}
+
+/// Pause after an interaction with the page
+pub async fn action_delay() {
+    let delay = ACTION_DELAY_MS.load(Ordering::Relaxed);
+    if delay > 0 {
+        sleep(Duration::from_millis(delay)).await;
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
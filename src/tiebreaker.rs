@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::teams::normalize_name;
+use crate::tournament::{MatchupInd, RoundKind, Tournament};
+
+/// Combined points in a typical title game, used without efficiency ratings
+const AVERAGE_TOTAL: f64 = 140.;
+
+/// Points per 100 possessions for an average team, used to scale efficiency matchups
+const AVERAGE_EFFICIENCY: f64 = 104.;
+
+/// Scale of the logistic curve relating point margin to win probability
+pub(crate) const MARGIN_SCALE: f64 = 6.;
+
+/// One row of an efficiency file, e.g. `Gonzaga,72.6,120.5,89.9`
+#[derive(Debug, Deserialize)]
+struct EfficiencyRecord {
+    team: String,
+    /// Possessions per 40 minutes
+    tempo: f64,
+    /// Points scored per 100 possessions
+    offense: f64,
+    /// Points allowed per 100 possessions
+    defense: f64,
+}
+
+/// Tempo and adjusted efficiencies per team, KenPom style
+#[derive(Debug, Default)]
+pub struct Efficiencies {
+    teams: HashMap<String, EfficiencyRecord>,
+}
+
+impl Efficiencies {
+    /// Load ratings from a CSV with a `team,tempo,offense,defense` header
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = csv::Reader::from_path(path)
+            .with_context(|| format!("Could not open efficiency file {}", path.display()))?;
+        let mut teams = HashMap::new();
+        for record in reader.deserialize() {
+            let record: EfficiencyRecord = record?;
+            teams.insert(normalize_name(&record.team), record);
+        }
+        Ok(Self { teams })
+    }
+
+    fn get(&self, team: &str) -> Option<&EfficiencyRecord> {
+        self.teams.get(&normalize_name(team))
+    }
+
+    /// Chance of `team1` beating `team2` from the margin their ratings predict, None unless
+    /// both teams are rated
+    pub fn win_probability(&self, team1: &str, team2: &str) -> Option<f64> {
+        let (points1, points2) = expected_points(self.get(team1)?, self.get(team2)?);
+        Some(1. / (1. + (-(points1 - points2) / MARGIN_SCALE).exp()))
+    }
+}
+
+/// Points each team is expected to score against the other, from tempo and efficiency
+fn expected_points(team1: &EfficiencyRecord, team2: &EfficiencyRecord) -> (f64, f64) {
+    let possessions = (team1.tempo + team2.tempo) / 2.;
+    (
+        possessions * team1.offense * team2.defense / AVERAGE_EFFICIENCY / 100.,
+        possessions * team2.offense * team1.defense / AVERAGE_EFFICIENCY / 100.,
+    )
+}
+
+/// Predicted final score of the title game, for pool tiebreakers
+#[derive(Debug, Clone, Serialize)]
+pub struct ScorePrediction {
+    pub winner: String,
+    pub loser: String,
+    pub winner_points: u32,
+    pub loser_points: u32,
+}
+
+impl Display for ScorePrediction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}, {} {}",
+            self.winner, self.winner_points, self.loser, self.loser_points
+        )
+    }
+}
+
+/// Predict the score of the bracket's title game. With efficiency ratings for both teams the
+/// expected points come from tempo and efficiency, otherwise a typical total is split by the
+/// margin implied by the game's win probability. None if the title game isn't decided.
+pub fn predict_title_score(
+    bracket: &Tournament,
+    efficiencies: Option<&Efficiencies>,
+) -> Option<ScorePrediction> {
+    let final_game = &bracket.rounds[&RoundKind::Round(bracket.field.round_count())].matchups[0];
+    let winner_ind = final_game.winner()?;
+    let loser_ind = match winner_ind {
+        MatchupInd::Team1 => MatchupInd::Team2,
+        MatchupInd::Team2 => MatchupInd::Team1,
+    };
+    let winner = final_game.team(winner_ind)?.to_string();
+    let loser = final_game.team(loser_ind)?.to_string();
+
+    let ratings = efficiencies.and_then(|e| Some((e.get(&winner)?, e.get(&loser)?)));
+    let (mut winner_points, mut loser_points) = match ratings {
+        Some((w, l)) => expected_points(w, l),
+        None => {
+            let prob = match (final_game.win_probability(), winner_ind) {
+                (Some(prob), MatchupInd::Team1) => prob,
+                (Some(prob), MatchupInd::Team2) => 1. - prob,
+                (None, _) => 0.5,
+            }
+            .clamp(0.01, 0.99);
+            let margin = MARGIN_SCALE * (prob / (1. - prob)).ln();
+            ((AVERAGE_TOTAL + margin) / 2., (AVERAGE_TOTAL - margin) / 2.)
+        }
+    };
+    winner_points = winner_points.round();
+    loser_points = loser_points.round();
+    // The pick has to win, even when the ratings favor the other team
+    if winner_points <= loser_points {
+        winner_points = loser_points + 1.;
+    }
+    Some(ScorePrediction {
+        winner,
+        loser,
+        winner_points: winner_points as u32,
+        loser_points: loser_points as u32,
+    })
+}
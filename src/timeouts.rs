@@ -0,0 +1,37 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How long to keep polling for an element on the 538 page before giving up, in ms
+static ELEMENT_TIMEOUT_MS: AtomicU64 = AtomicU64::new(5000);
+
+/// Set how long to wait for elements on the 538 page, which renders its bracket after load
+pub fn set_element_timeout(timeout: Duration) {
+    ELEMENT_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// How long to wait for elements on the 538 page
+pub fn element_timeout() -> Duration {
+    Duration::from_millis(ELEMENT_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// How long to wait after hovering a team for its win percentage to render, in ms
+static HOVER_TIMEOUT_MS: AtomicU64 = AtomicU64::new(5000);
+
+/// How often to look for the win percentage again while waiting on a hover, in ms
+static HOVER_POLL_MS: AtomicU64 = AtomicU64::new(250);
+
+/// Set how long to wait for a hover's win percentage and how often to check for it
+pub fn set_hover_wait(timeout: Duration, poll_interval: Duration) {
+    HOVER_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+    HOVER_POLL_MS.store(poll_interval.as_millis().max(1) as u64, Ordering::Relaxed);
+}
+
+/// How long to wait for a hover's win percentage
+pub fn hover_timeout() -> Duration {
+    Duration::from_millis(HOVER_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// How often to check for a hover's win percentage
+pub fn hover_poll_interval() -> Duration {
+    Duration::from_millis(HOVER_POLL_MS.load(Ordering::Relaxed))
+}
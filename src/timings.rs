@@ -0,0 +1,145 @@
+use std::fs::File;
+use std::future::Future;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Steps of driving the 538 page which are timed
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Phase {
+    /// Loading the page until its bracket and teams table have rendered
+    PageLoad,
+    /// Reading the teams table, including loading the page for it when not cached
+    TeamsTable,
+    /// Hovering over a team until its win percentage shows
+    Hover,
+    /// Clicking a team until it shows up in the next round
+    Click,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [
+        Phase::PageLoad,
+        Phase::TeamsTable,
+        Phase::Hover,
+        Phase::Click,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::PageLoad => "page load",
+            Phase::TeamsTable => "teams table",
+            Phase::Hover => "hover",
+            Phase::Click => "click",
+        }
+    }
+}
+
+/// Times each phase has been timed, by phase
+static COUNTS: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+/// Total time spent in each phase, in microseconds
+static TOTAL_US: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+/// Longest single time in each phase, in microseconds
+static MAX_US: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+/// Add one time spent in a phase
+pub fn record(phase: Phase, elapsed: Duration) {
+    let ind = phase as usize;
+    let micros = elapsed.as_micros() as u64;
+    COUNTS[ind].fetch_add(1, Ordering::Relaxed);
+    TOTAL_US[ind].fetch_add(micros, Ordering::Relaxed);
+    MAX_US[ind].fetch_max(micros, Ordering::Relaxed);
+}
+
+/// Run a step and record how long it took as part of the phase, whether or not it succeeded
+pub async fn time<F: Future>(phase: Phase, step: F) -> F::Output {
+    let start = Instant::now();
+    let out = step.await;
+    record(phase, start.elapsed());
+    out
+}
+
+/// Time spent in one phase
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub phase: &'static str,
+    pub count: u64,
+    pub total_secs: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Time spent in each phase over a run
+#[derive(Debug, Clone, Serialize)]
+pub struct Timings {
+    /// Phases timed at least once. They can overlap, e.g. the teams table loads the page
+    pub phases: Vec<PhaseTiming>,
+    /// Length of the whole run
+    pub total_secs: f64,
+}
+
+/// The phases timed so far, in a run taking `total`
+pub fn timings(total: Duration) -> Timings {
+    let phases = Phase::ALL
+        .iter()
+        .filter_map(|&phase| {
+            let ind = phase as usize;
+            let count = COUNTS[ind].load(Ordering::Relaxed);
+            if count == 0 {
+                return None;
+            }
+            let total_us = TOTAL_US[ind].load(Ordering::Relaxed) as f64;
+            Some(PhaseTiming {
+                phase: phase.name(),
+                count,
+                total_secs: total_us / 1e6,
+                mean_ms: total_us / count as f64 / 1e3,
+                max_ms: MAX_US[ind].load(Ordering::Relaxed) as f64 / 1e3,
+            })
+        })
+        .collect();
+    Timings {
+        phases,
+        total_secs: total.as_secs_f64(),
+    }
+}
+
+/// Log the time spent in each phase
+pub fn report(timings: &Timings) {
+    for timing in &timings.phases {
+        log::info!(
+            "{:<12} {:>6} x {:>9.1}ms mean, {:>9.1}ms max, {:>8.1}s in all",
+            timing.phase,
+            timing.count,
+            timing.mean_ms,
+            timing.max_ms,
+            timing.total_secs
+        );
+    }
+    log::info!("Total time {:.1}s", timings.total_secs);
+}
+
+/// Write the timings as JSON
+pub fn save(timings: &Timings, path: &Path) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), timings)?;
+    log::info!("Saved timings to {}", path.display());
+    Ok(())
+}
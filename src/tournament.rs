@@ -1,13 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
 use std::hash::Hash;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
 use std::str::FromStr;
 
 use anyhow::anyhow;
 use colored::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::teams::{construct_html_name, Team};
+use crate::field::Field;
+use crate::output;
+use crate::parse::construct_html_name;
+use crate::teams::Team;
 
 /// Bracket regions
 #[derive(Copy, Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
@@ -28,6 +34,13 @@ impl Region {
             Self::Midwest => 3,
         }
     }
+
+    /// Region with the given index from [`Region::to_ind`]
+    pub fn from_ind(ind: usize) -> Option<Self> {
+        [Self::West, Self::East, Self::South, Self::Midwest]
+            .get(ind)
+            .copied()
+    }
 }
 
 impl Display for Region {
@@ -90,12 +103,21 @@ impl MatchupInd {
 }
 
 /// One matchup in a round
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Matchup {
     /// Teams playing in this matchup, None if not determined yet
     teams: [Option<String>; 2],
+    /// Seeds of the teams playing in this matchup
+    #[serde(default)]
+    seeds: [Option<Seed>; 2],
     /// Who won the matchup, None if not complete
     winner: Option<MatchupInd>,
+    /// Chance of the first team winning used when simulating this game, None if not simulated
+    #[serde(default)]
+    win_prob: Option<f64>,
+    /// Games won by each team when the matchup is a series, None for single games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    series: Option<[usize; 2]>,
     /// What # matchup this is in the round (used so we know where to advance the winner to)
     index: usize,
 }
@@ -109,10 +131,66 @@ impl Matchup {
     }
     /// Get the competing teams, should only be called when both teams exist
     pub fn teams(&self) -> [String; 2] {
-        [
-            self.teams[0].as_ref().unwrap().clone(),
-            self.teams[1].as_ref().unwrap().clone(),
-        ]
+        let [team1, team2] = self.try_teams().expect("Both teams must be set");
+        [team1.to_string(), team2.to_string()]
+    }
+
+    /// Get the competing teams, None if either team is not determined yet
+    pub fn try_teams(&self) -> Option<[&str; 2]> {
+        Some([self.team(MatchupInd::Team1)?, self.team(MatchupInd::Team2)?])
+    }
+
+    /// Get one of the competing teams, None if not determined yet
+    pub fn team(&self, ind: MatchupInd) -> Option<&str> {
+        self.teams[ind.to_ind()].as_deref()
+    }
+
+    /// Seed of one of the competing teams, None if not determined yet
+    pub fn seed(&self, ind: MatchupInd) -> Option<Seed> {
+        self.seeds[ind.to_ind()]
+    }
+
+    /// Who won the matchup, None if not complete
+    pub fn winner(&self) -> Option<MatchupInd> {
+        self.winner
+    }
+
+    /// Name of the team who won the matchup, None if not complete
+    pub fn winning_team(&self) -> Option<&str> {
+        self.team(self.winner?)
+    }
+
+    /// Name of the team who lost the matchup, None if not complete
+    pub fn losing_team(&self) -> Option<&str> {
+        match self.winner? {
+            MatchupInd::Team1 => self.team(MatchupInd::Team2),
+            MatchupInd::Team2 => self.team(MatchupInd::Team1),
+        }
+    }
+
+    /// What # matchup this is in the round
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Chance of the first team winning used when simulating this game, None if not simulated
+    pub fn win_probability(&self) -> Option<f64> {
+        self.win_prob
+    }
+
+    /// Record the chance of the first team winning used when simulating this game
+    pub fn set_win_probability(&mut self, prob: f64) {
+        self.win_prob = Some(prob);
+    }
+
+    /// Games won by each team in this matchup's series, None for single games
+    pub fn series_score(&self) -> Option<[usize; 2]> {
+        self.series
+    }
+
+    /// Record how a series went
+    pub fn set_series_score(&mut self, games: [usize; 2]) {
+        self.series = Some(games);
     }
 
     /// Set the winner of this matchup
@@ -143,15 +221,35 @@ impl Matchup {
         self.winner.is_some()
     }
 
+    /// Reset this matchup to undecided
+    pub fn clear_winner(&mut self) {
+        self.winner = None;
+        self.win_prob = None;
+        self.series = None;
+    }
+
     /// Include a team in this matchup. Must have space for another team
-    fn add_team(&mut self, name: &str) -> &mut Self {
-        if self.teams[0].is_none() {
-            self.teams[0] = Some(name.to_string())
-        } else if self.teams[1].is_none() {
-            self.teams[1] = Some(name.to_string())
-        } else {
-            panic!("Both teams already set!");
+    fn add_team(&mut self, name: &str, seed: Option<Seed>) -> &mut Self {
+        let ind = self
+            .teams
+            .iter()
+            .position(Option::is_none)
+            .expect("Both teams already set!");
+        self.teams[ind] = Some(name.to_string());
+        self.seeds[ind] = seed;
+        self
+    }
+
+    /// Remove a team from this matchup, leaving its slot open
+    fn remove_team(&mut self, name: &str) -> &mut Self {
+        for (slot, seed) in self.teams.iter_mut().zip(&mut self.seeds) {
+            if slot.as_deref() == Some(name) {
+                *slot = None;
+                *seed = None;
+            }
         }
+        self.win_prob = None;
+        self.series = None;
         self
     }
 
@@ -159,20 +257,66 @@ impl Matchup {
         self.winner == Some(team)
     }
 
+    /// Teams are shown with their seed, with winners in green and losers in red. Without colors
+    /// (e.g. `NO_COLOR` or `--color never`) winners are marked with a `*` instead. With
+    /// `--result-markers symbols` winners are marked with ✓ and losers with ✗ as well, and
+    /// styled in bold and struck through where colors are on.
     fn get_team_display(&self, ind: MatchupInd) -> ColoredString {
-        let name = self.teams[ind.to_ind()].as_deref().unwrap_or("___");
+        let name = match (self.team(ind), self.seed(ind)) {
+            (Some(team), Some(seed)) => format!("({}) {}", seed.0, team),
+            (Some(team), None) => team.to_string(),
+            (None, _) => "___".to_string(),
+        };
+        let symbols = output::symbols();
         if !self.completed() {
             name.normal()
         } else if self.team_won(ind) {
-            name.green()
+            if symbols {
+                format!("✓ {}", name).green().bold()
+            } else if control::SHOULD_COLORIZE.should_colorize() {
+                name.green()
+            } else {
+                format!("{}*", name).normal()
+            }
+        } else if symbols {
+            format!("✗ {}", name).red().strikethrough()
         } else {
             name.red()
         }
     }
 }
 
+/// Simulated games read "winner (72%) over loser", series "winner over loser 4-2", everything
+/// else "team1 vs team2"
 impl Display for Matchup {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let (Some(winner), Some(games)) = (self.winner, self.series) {
+            let loser = match winner {
+                MatchupInd::Team1 => MatchupInd::Team2,
+                MatchupInd::Team2 => MatchupInd::Team1,
+            };
+            return writeln!(
+                f,
+                "{} over {} {}-{}",
+                self.get_team_display(winner),
+                self.get_team_display(loser),
+                games[winner.to_ind()],
+                games[loser.to_ind()]
+            );
+        }
+        if let (Some(winner), Some(prob)) = (self.winner, self.win_prob) {
+            let (loser, winner_prob) = match winner {
+                MatchupInd::Team1 => (MatchupInd::Team2, prob),
+                MatchupInd::Team2 => (MatchupInd::Team1, 1. - prob),
+            };
+            return writeln!(
+                f,
+                "{} ({:.0}%) over {}",
+                self.get_team_display(winner),
+                winner_prob * 100.,
+                self.get_team_display(loser)
+            );
+        }
         writeln!(
             f,
             "{} vs {}",
@@ -192,32 +336,51 @@ pub enum RoundKind {
 }
 
 impl RoundKind {
+    /// Round number, with the play-in round as 0
+    pub fn number(&self) -> usize {
+        match self {
+            RoundKind::PlayIn => 0,
+            RoundKind::Round(r) => *r,
+        }
+    }
+
     pub fn next_round(&self) -> Option<Self> {
         match self {
             RoundKind::PlayIn => Some(RoundKind::Round(1)),
-            RoundKind::Round(r) if r < &6 => Some(RoundKind::Round(r + 1)),
-            _ => None,
+            RoundKind::Round(r) => Some(RoundKind::Round(r + 1)),
         }
     }
 
-    pub fn matchup_count(&self) -> usize {
-        match self {
-            RoundKind::PlayIn => 4,
-            RoundKind::Round(round) => 2_usize.pow((6 - round) as u32),
+    /// Name of the round in a bracket of `round_count` rounds, counted back from the title
+    /// game, so the first round of 16 teams is the Sweet 16
+    pub fn name(&self, round_count: usize) -> String {
+        let round_num = match self {
+            RoundKind::PlayIn => return "First Four".to_string(),
+            RoundKind::Round(round_num) => *round_num,
+        };
+        match round_count.checked_sub(round_num) {
+            Some(0) => "Championship".to_string(),
+            Some(1) => "Final Four".to_string(),
+            Some(2) => "Elite Eight".to_string(),
+            Some(3) => "Sweet 16".to_string(),
+            Some(rounds_after) if rounds_after < usize::BITS as usize - 1 => {
+                format!("Round of {}", 2usize << rounds_after)
+            }
+            _ => format!("Round {}", round_num),
         }
     }
 }
 
+/// Name of the round in the NCAA's 64-team bracket, see [`Tournament::round_name`] for
+/// brackets of other fields
 impl Display for RoundKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::PlayIn => write!(f, "Play-in"),
-            Self::Round(round) => write!(f, "Round {}", round),
-        }
+        write!(f, "{}", self.name(Field::NCAA.round_count()))
     }
 }
 
 /// Round in a tournament
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Round {
     /// What round this is
     pub round: RoundKind,
@@ -226,28 +389,53 @@ pub struct Round {
 }
 
 impl Round {
-    /// Initialize an empty normal round
-    pub fn empty(round: usize) -> Self {
-        let round = RoundKind::Round(round);
-        let num_matchups = round.matchup_count();
-        let matchups = (0..num_matchups).map(Matchup::new).collect();
-        Self { round, matchups }
+    /// Initialize an empty normal round of a bracket for the given field
+    pub fn empty(field: &Field, round_num: usize) -> Self {
+        let matchups = (0..field.matchup_count(round_num))
+            .map(Matchup::new)
+            .collect();
+        Self {
+            round: RoundKind::Round(round_num),
+            matchups,
+        }
     }
 
-    pub fn add_team_to_matchup(&mut self, team: &str, ind: usize) {
-        self.matchups[ind].add_team(team);
+    pub fn add_team_to_matchup(&mut self, team: &str, seed: Seed, ind: usize) {
+        self.matchups[ind].add_team(team, Some(seed));
     }
 
-    pub fn new_round1(teams: &mut [Team]) -> Self {
-        teams.sort_by_key(|team| team.seed.0 as usize + team.region.to_ind() * 16);
-        let mut round = Self::empty(1);
-        debug_assert_eq!(round.round.matchup_count() * 2, teams.len());
-
+    /// Place every team of the field in its first round game by region and seed
+    pub fn new_round1(field: &Field, teams: &mut [Team]) -> anyhow::Result<Self> {
+        if teams.len() != field.teams {
+            return Err(anyhow!(
+                "Expected {} teams for the bracket but got {}",
+                field.teams,
+                teams.len()
+            ));
+        }
+        teams.sort_by_key(|team| (team.region.to_ind(), team.seed));
+        let mut round = Self::empty(field, 1);
         for team in teams {
-            let matchup_ind = matchup_ind(team.seed.0) + 8 * team.region.to_ind();
-            round.add_team_to_matchup(team.name(), matchup_ind);
+            let ind = field
+                .first_round_matchup(team.region.to_ind(), team.seed)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "({}) {} in the {} doesn't fit a bracket of {} teams in {} regions",
+                        team.seed.0,
+                        team.name(),
+                        team.region,
+                        field.teams,
+                        field.regions
+                    )
+                })?;
+            // Teams are placed best seed first, so a repeated seed would otherwise take its
+            // opponent's slot
+            if round.matchups[ind].seeds.contains(&Some(team.seed)) {
+                return Err(anyhow!("The {} has two {} seeds", team.region, team.seed.0));
+            }
+            round.add_team_to_matchup(team.name(), team.seed, ind);
         }
-        round
+        Ok(round)
     }
 
     pub fn get_matchup_with_team_mut(&mut self, team: &str) -> &mut Matchup {
@@ -284,42 +472,45 @@ impl Display for Round {
     }
 }
 
-/// Matchup number for a given seed in their region going top to bottom
-fn matchup_ind(mut seed: u8) -> usize {
-    if seed > 8 {
-        seed = 17 - seed;
-    }
-    match seed {
-        1 => 0,
-        8 => 1,
-        5 => 2,
-        4 => 3,
-        6 => 4,
-        3 => 5,
-        7 => 6,
-        2 => 7,
-        _ => unreachable!(),
-    }
-}
-
 /// A complete tournament
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Tournament {
-    /// All rounds in this tournament
+    /// All rounds in this tournament. Stored as a list in files since JSON keys must be strings
+    #[serde(serialize_with = "serialize_rounds")]
+    #[serde(deserialize_with = "deserialize_rounds")]
     pub rounds: HashMap<RoundKind, Round>,
+    /// Shape of the bracket. Files from before other fields were supported are NCAA brackets
+    #[serde(default)]
+    pub field: Field,
 }
 
-impl Tournament {
-    /// Initialize from a list of teams. The first round will be set using these teams
-    pub fn new(teams: &mut [Team], current_results: HashMap<RoundKind, HashSet<String>>) -> Self {
-        let mut rounds = HashMap::new();
-        let round1 = Round::new_round1(teams);
-        rounds.insert(round1.round, round1);
+fn serialize_rounds<S: Serializer>(
+    rounds: &HashMap<RoundKind, Round>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut rounds = rounds.values().collect::<Vec<_>>();
+    rounds.sort_by_key(|round| round.round.number());
+    rounds.serialize(serializer)
+}
 
-        for round_num in 2..=6 {
-            let round = Round::empty(round_num);
-            rounds.insert(round.round, round);
-        }
-        let mut tournament = Self { rounds };
+fn deserialize_rounds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<RoundKind, Round>, D::Error> {
+    let rounds = Vec::<Round>::deserialize(deserializer)?;
+    Ok(rounds
+        .into_iter()
+        .map(|round| (round.round, round))
+        .collect())
+}
+
+impl Tournament {
+    /// Initialize an NCAA tournament from its 64 teams, advancing the teams in
+    /// `current_results`, which are keyed by the round they have reached
+    pub fn new(
+        teams: &mut [Team],
+        current_results: HashMap<RoundKind, HashSet<String>>,
+    ) -> anyhow::Result<Self> {
+        let mut tournament = Self::with_field(Field::NCAA, teams)?;
         for round_kind in (1..=5).map(RoundKind::Round) {
             let mut teams_to_advance = vec![];
             if let Some(cur_teams) = current_results.get(&round_kind.next_round().unwrap()) {
@@ -340,7 +531,20 @@ impl Tournament {
                 tournament.advance_team(&team, round_kind);
             }
         }
-        tournament
+        Ok(tournament)
+    }
+
+    /// An empty bracket for any field, with the teams placed in the first round by their
+    /// region and seed
+    pub fn with_field(field: Field, teams: &mut [Team]) -> anyhow::Result<Self> {
+        let round1 = Round::new_round1(&field, teams)?;
+        let mut rounds = HashMap::new();
+        rounds.insert(round1.round, round1);
+        for round_num in 2..=field.round_count() {
+            let round = Round::empty(&field, round_num);
+            rounds.insert(round.round, round);
+        }
+        Ok(Self { rounds, field })
     }
 
     pub fn advance_team(&mut self, team: &str, round: RoundKind) {
@@ -348,23 +552,131 @@ impl Tournament {
             .get_matchup_with_team_mut(team)
             .set_winning_team(team);
 
-        let matchup_ind = self.rounds[&round].get_matchup_with_team(team).index;
+        let matchup = self.rounds[&round].get_matchup_with_team(team);
+        let (matchup_ind, seed) = (matchup.index, matchup.seed(matchup.winner.unwrap()));
 
         let next_round_ind = matchup_ind / 2;
-        if let Some(next_round) = round.next_round() {
-            self.rounds.get_mut(&next_round).unwrap().matchups[next_round_ind].add_team(team);
+        if let Some(next_round) = self.next_round(round) {
+            self.get_round_mut(next_round).matchups[next_round_ind].add_team(team, seed);
         }
     }
 
+    /// Undo advancing a team out of the given round. The team is removed from all later rounds,
+    /// and any later winners which depended on it are cleared as well. Does nothing if the team
+    /// isn't in this round or did not win its matchup in it.
+    pub fn retract_team(&mut self, team: &str, round: RoundKind) {
+        let matchup = match self
+            .rounds
+            .get_mut(&round)
+            .and_then(|round| round.matchups.iter_mut().find(|m| m.includes_team(team)))
+        {
+            Some(matchup) => matchup,
+            None => return,
+        };
+        if matchup.winning_team() != Some(team) {
+            return;
+        }
+        matchup.clear_winner();
+
+        if let Some(next_round) = self.next_round(round) {
+            let next_matchup = self.rounds[&next_round].get_matchup_with_team(team);
+            if let Some(next_winner) = next_matchup.winning_team().map(str::to_string) {
+                self.retract_team(&next_winner, next_round);
+            }
+            self.get_round_mut(next_round)
+                .get_matchup_with_team_mut(team)
+                .remove_team(team);
+        }
+    }
+
+    /// Teams who have lost a game in this bracket
+    pub fn eliminated_teams(&self) -> HashSet<&str> {
+        self.rounds
+            .values()
+            .flat_map(|round| &round.matchups)
+            .filter_map(|matchup| matchup.losing_team())
+            .collect()
+    }
+
+    /// Round in which two teams would meet if both kept winning, None if either team isn't in
+    /// round 1
+    pub fn meeting_round(&self, team1: &str, team2: &str) -> Option<usize> {
+        let round1 = &self.rounds[&RoundKind::Round(1)].matchups;
+        let position = |team| round1.iter().position(|m| m.includes_team(team));
+        let (mut ind1, mut ind2) = (position(team1)?, position(team2)?);
+        let mut round_num = 1;
+        while ind1 != ind2 {
+            ind1 /= 2;
+            ind2 /= 2;
+            round_num += 1;
+        }
+        Some(round_num)
+    }
+
+    /// The team who won the championship, None if not decided
+    pub fn champion(&self) -> Option<&str> {
+        self.rounds[&RoundKind::Round(self.field.round_count())].matchups[0].winning_team()
+    }
+
+    /// Teams who won their game two rounds before the title game, so the Final Four of a
+    /// full bracket. Empty for a bracket with fewer than three rounds.
+    pub fn final_four(&self) -> Vec<&str> {
+        let round_num = match self.field.round_count().checked_sub(2) {
+            Some(round_num) if round_num >= 1 => round_num,
+            _ => return vec![],
+        };
+        self.rounds[&RoundKind::Round(round_num)]
+            .matchups
+            .iter()
+            .filter_map(|m| m.winning_team())
+            .collect()
+    }
+
+    /// Name of a round of this bracket, counted back from its title game
+    pub fn round_name(&self, round: RoundKind) -> String {
+        self.field.round_name(round)
+    }
+
+    /// The round after this one, None after the title game
+    pub(crate) fn next_round(&self, round: RoundKind) -> Option<RoundKind> {
+        round
+            .next_round()
+            .filter(|next| next.number() <= self.field.round_count())
+    }
+
     pub fn get_round_mut(&mut self, round: RoundKind) -> &mut Round {
         self.rounds.get_mut(&round).unwrap()
     }
+
+    /// Write this bracket to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?,
+        );
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Load a bracket previously written with [`Tournament::save`]
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
 }
 
 impl Display for Tournament {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for round_num in 1..=6 {
-            write!(f, "{}", self.rounds[&RoundKind::Round(round_num)])?;
+        for round_num in 1..=self.field.round_count() {
+            let round = &self.rounds[&RoundKind::Round(round_num)];
+            writeln!(f, "{}", self.round_name(round.round))?;
+            for matchup in &round.matchups {
+                write!(f, "{}", matchup)?;
+            }
+            writeln!(f)?;
         }
         Ok(())
     }
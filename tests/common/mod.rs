@@ -0,0 +1,114 @@
+// Runs tasks against a saved copy of the 538 page instead of the live site.
+//
+// The tests need a WebDriver server, at `WEBDRIVER_URL` or Selenium's usual address, and
+// the saved page, so they are ignored by default. The page is too large to keep in the repo
+// and is no longer live, so to run them open the 2022 page as archived at
+// https://web.archive.org/web/2022/https://projects.fivethirtyeight.com/2022-march-madness-predictions/
+// in Chrome, wait for the bracket to load, and use "Save page as, complete" into
+// `tests/fixtures/538/index.html`. Then run `cargo test -- --ignored`.
+//
+// Each task runs as its own process of the built binary, pointed at the served page and
+// working in a fresh directory, so tests running side by side don't share any state.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::{env, fs, thread};
+
+use march_madness::endpoint::DriverKind;
+use march_madness::URL_VAR;
+
+/// Where the saved page lives
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/538");
+
+/// Serves the files of a directory over HTTP on a local port until the test exits
+pub struct FixtureServer {
+    pub url: String,
+}
+
+impl FixtureServer {
+    /// Serve the saved 538 page, panicking with instructions if it hasn't been saved
+    pub fn start() -> Self {
+        let dir = PathBuf::from(FIXTURE_DIR);
+        assert!(
+            dir.join("index.html").exists(),
+            "Save the 538 page into {}/index.html to run the fixture tests, see tests/common",
+            FIXTURE_DIR
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // One bad request shouldn't take the page down for the rest of the test
+                let _ = respond(stream, &dir);
+            }
+        });
+        Self { url }
+    }
+}
+
+fn respond(mut stream: TcpStream, dir: &Path) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let path = match path.trim_start_matches('/') {
+        "" => PathBuf::from("index.html"),
+        path => PathBuf::from(path),
+    };
+    // Only serve files inside the fixture directory
+    let inside = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    match fs::read(dir.join(&path)) {
+        Ok(body) if inside => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: \
+                 close\r\n\r\n",
+                content_type(&path),
+                body.len()
+            )?;
+            stream.write_all(&body)
+        }
+        _ => stream
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+    }
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "application/javascript",
+        Some("css") => "text/css",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Run a task of the binary against the fixture page in a fresh directory named for the
+/// test, so the files it writes don't mix with a real run's. Returns the directory and what
+/// the task printed, panicking if it failed.
+pub fn run_task(server: &FixtureServer, test: &str, args: &[&str]) -> (PathBuf, String) {
+    let dir = env::temp_dir().join(format!("march-madness-{}-{}", test, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let endpoint = env::var("WEBDRIVER_URL")
+        .unwrap_or_else(|_| DriverKind::Selenium.default_endpoint().into());
+    let output = Command::new(env!("CARGO_BIN_EXE_march-madness"))
+        .current_dir(&dir)
+        .env(URL_VAR, &server.url)
+        .args(["--driver-endpoint", &endpoint, "--chrome-arg=--headless"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (dir, String::from_utf8(output.stdout).unwrap())
+}
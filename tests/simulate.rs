@@ -0,0 +1,28 @@
+mod common;
+
+use common::FixtureServer;
+use march_madness::summary::SimulationSummary;
+use march_madness::tournament::{RoundKind, Tournament};
+use serde_json::Value;
+
+#[test]
+#[ignore = "needs a WebDriver server and the saved 538 page"]
+fn simulate_on_saved_page() {
+    let server = FixtureServer::start();
+    // The teams table is scraped on the way, as the bracket is laid out from it
+    let (_, stdout) = common::run_task(
+        &server,
+        "simulate",
+        &["--output", "json", "simulate", "--seed", "0"],
+    );
+    let mut printed: Value = serde_json::from_str(&stdout).unwrap();
+    let bracket: Tournament = serde_json::from_value(printed["bracket"].take()).unwrap();
+    let summary: SimulationSummary = serde_json::from_value(printed["summary"].take()).unwrap();
+
+    assert_eq!(summary.runs, 1);
+    for round_num in 1..=6 {
+        let round = &bracket.rounds[&RoundKind::Round(round_num)];
+        assert!(round.matchups.iter().all(|matchup| matchup.completed()));
+    }
+    assert!(bracket.champion().is_some());
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use std::fs;
+
+use common::FixtureServer;
+use march_madness::teams::Team;
+use serde_json::Value;
+
+#[test]
+#[ignore = "needs a WebDriver server and the saved 538 page"]
+fn write_teams_table_from_saved_page() {
+    let server = FixtureServer::start();
+    let (dir, _) = common::run_task(&server, "write-teams-table", &["write-teams-table"]);
+
+    let mut file: Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("teams.json")).unwrap()).unwrap();
+    assert_eq!(file["source_url"], server.url.as_str());
+    let teams: Vec<Team> = serde_json::from_value(file["teams"].take()).unwrap();
+    assert_eq!(teams.len(), 68);
+    assert_eq!(teams.iter().filter(|team| team.in_first_four()).count(), 8);
+}